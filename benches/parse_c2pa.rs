@@ -1,5 +1,13 @@
+use std::{
+    fs::File,
+    io::{BufReader, Seek, SeekFrom},
+};
+
 use codspeed_criterion_compat::{black_box, criterion_group, criterion_main, Criterion};
-use jumbf::parser::DataBox;
+use jumbf::parser::{
+    layout::{extract_box_to, BoxLayout},
+    DataBox, SuperBox,
+};
 
 const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../src/tests/fixtures/C.c2pa");
 
@@ -9,5 +17,44 @@ pub fn parse_c2pa(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, parse_c2pa);
+pub fn parse_c2pa_super_box(c: &mut Criterion) {
+    c.bench_function("parse sample C2PA Manifest Store as superbox", |b| {
+        b.iter(|| SuperBox::from_slice(black_box(C2PA_MANIFEST_STORE)).unwrap());
+    });
+}
+
+pub fn layout_c2pa_from_file(c: &mut Criterion) {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/C.c2pa");
+
+    c.bench_function("read layout of sample C2PA Manifest Store from file", |b| {
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        b.iter(|| {
+            reader.seek(SeekFrom::Start(0)).unwrap();
+            BoxLayout::from_reader(black_box(&mut reader)).unwrap()
+        });
+    });
+}
+
+pub fn extract_c2pa_claim_from_file(c: &mut Criterion) {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/C.c2pa");
+    let label = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim";
+
+    c.bench_function("extract claim from sample C2PA Manifest Store file", |b| {
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        let mut claim = Vec::new();
+        b.iter(|| {
+            reader.seek(SeekFrom::Start(0)).unwrap();
+            claim.clear();
+            extract_box_to(black_box(&mut reader), label, &mut claim).unwrap()
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    parse_c2pa,
+    parse_c2pa_super_box,
+    layout_c2pa_from_file,
+    extract_c2pa_claim_from_file
+);
 criterion_main!(benches);
@@ -0,0 +1,102 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! A small command-line tool for inspecting JUMBF files.
+//!
+//! ```sh
+//! cargo run --example jumbf --features sha2 -- diff OLD.jumbf NEW.jumbf
+//! ```
+
+use std::{env, error::Error, fs, process::ExitCode};
+
+use jumbf::{
+    digest::{Digest, Sha256},
+    parser::{BoxDiff, DiffKind, SuperBox},
+};
+
+const USAGE: &str = "usage: jumbf diff <OLD> <NEW>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["diff", old, new] => diff(old, new),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("jumbf: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn diff(old_path: &str, new_path: &str) -> Result<(), Box<dyn Error>> {
+    let old = fs::read(old_path)?;
+    let new = fs::read(new_path)?;
+
+    let (_, old) = SuperBox::from_slice(&old).map_err(|err| format!("{old_path}: {err}"))?;
+    let (_, new) = SuperBox::from_slice(&new).map_err(|err| format!("{new_path}: {err}"))?;
+
+    for diff in old.diff(&new) {
+        println!("{}", describe(&diff));
+    }
+
+    Ok(())
+}
+
+fn describe(diff: &BoxDiff) -> String {
+    let path = if diff.path.is_empty() {
+        "(root)".to_owned()
+    } else {
+        diff.path.to_string()
+    };
+    let tbox = String::from_utf8_lossy(&diff.tbox.0);
+    let name = format!("{path} [{tbox} #{index}]", index = diff.index);
+
+    match (diff.kind(), diff.old, diff.new) {
+        (DiffKind::Changed, Some(old), Some(new)) => format!(
+            "~ {name} {old_len} -> {new_len} bytes ({old_len_delta:+}), sha256 {old_hash} -> {new_hash}",
+            old_len = old.len(),
+            new_len = new.len(),
+            old_len_delta = new.len() as i64 - old.len() as i64,
+            old_hash = short_hash(old),
+            new_hash = short_hash(new),
+        ),
+        (DiffKind::Added, _, Some(new)) => format!(
+            "+ {name} {len} bytes, sha256 {hash}",
+            len = new.len(),
+            hash = short_hash(new)
+        ),
+        (_, Some(old), _) => format!(
+            "- {name} {len} bytes, sha256 {hash}",
+            len = old.len(),
+            hash = short_hash(old)
+        ),
+        _ => format!("? {name}"),
+    }
+}
+
+fn short_hash(data: &[u8]) -> String {
+    Sha256
+        .digest(data)
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
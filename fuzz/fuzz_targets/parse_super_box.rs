@@ -0,0 +1,30 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![no_main]
+
+use jumbf::parser::{ParseOptions, SuperBox};
+use libfuzzer_sys::fuzz_target;
+
+// Parse arbitrary bytes as a superbox. Any result is acceptable as long as
+// the parser doesn't panic.
+fuzz_target!(|data: &[u8]| {
+    let _ = SuperBox::from_slice(data);
+
+    // Also exercise the recovery paths, which visit more of damaged input.
+    let options = ParseOptions::default()
+        .set_lenient_description_box(true)
+        .set_lenient_label_encoding(true)
+        .set_demote_malformed_super_boxes(true);
+    let _ = SuperBox::from_slice_with_recovery(data, &options);
+});
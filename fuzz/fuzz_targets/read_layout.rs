@@ -0,0 +1,44 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![no_main]
+
+use std::io::Cursor;
+
+use jumbf::{
+    parser::layout::BoxLayout,
+    test_utils::{FlakyReader, InjectedError},
+};
+use libfuzzer_sys::fuzz_target;
+
+// Read the layout of a superbox from a stream which fails at an arbitrary
+// offset. The first two bytes of input choose the offset; the rest is the
+// stream. A read failure must be reported as such, and if the failing
+// offset is never read, the result must match reading without failures.
+fuzz_target!(|data: &[u8]| {
+    let Some((fail_at, data)) = data.split_first_chunk::<2>() else {
+        return;
+    };
+    let fail_at = u16::from_be_bytes(*fail_at) as u64;
+
+    let mut reader = FlakyReader::new(Cursor::new(data), fail_at);
+    let result = BoxLayout::from_reader(&mut reader);
+
+    if reader.has_failed() {
+        let injected = result.as_ref().err().and_then(InjectedError::find);
+        assert_eq!(injected, Some(&InjectedError { offset: fail_at }));
+    } else {
+        let expected = BoxLayout::from_reader(&mut Cursor::new(data));
+        assert_eq!(result.ok(), expected.ok());
+    }
+});
@@ -0,0 +1,31 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![no_main]
+
+use std::io::Cursor;
+
+use jumbf::{model::SuperBoxModel, parser::SuperBox};
+use libfuzzer_sys::fuzz_target;
+
+// Build JUMBF from an arbitrary box tree, parse it back, and verify that
+// the parsed tree matches the original.
+fuzz_target!(|model: SuperBoxModel| {
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    model.write_jumbf(&mut jumbf).unwrap();
+    let jumbf = jumbf.into_inner();
+
+    let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(SuperBoxModel::from(&sbox), model);
+});
@@ -63,3 +63,9 @@ pub const DESCRIPTION_BOX_TYPE: BoxType = BoxType(*b"jumd");
 
 /// Box type for JUMBF super box (`b"jumb"`).
 pub const SUPER_BOX_TYPE: BoxType = BoxType(*b"jumb");
+
+/// Box type for ISO BMFF free space box (`b"free"`).
+pub const FREE_BOX_TYPE: BoxType = BoxType(*b"free");
+
+/// Box type for C2PA salt private box (`b"c2sh"`).
+pub const C2PA_SALT_BOX_TYPE: BoxType = BoxType(*b"c2sh");
@@ -0,0 +1,173 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    io::{Error, ErrorKind, Result, Seek, Write},
+    ops::Range,
+};
+
+use crate::{
+    box_type::SUPER_BOX_TYPE,
+    builder::{
+        to_box::{patch_size, write_jumbf},
+        SuperBoxBuilder, ToBox,
+    },
+    BoxType,
+};
+
+/// A `BoxWriter` writes JUMBF boxes whose sizes aren't known in advance
+/// to a seekable stream in a single pass.
+///
+/// Call [`begin_box()`] to start a box, write its payload using the
+/// [`Write`] implementation (or by adding complete boxes with
+/// [`write_box()`]), and then call [`end_box()`]. A placeholder header is
+/// written when the box is started; its size is filled in when the box is
+/// ended. Boxes may be nested. Only the offsets of open boxes are kept in
+/// memory; payloads are passed directly to the underlying stream.
+///
+/// Boxes written this way are limited to 4GB, since the box header is
+/// reserved before the size is known.
+///
+/// ## Example
+///
+/// ```
+/// # fn example() -> std::io::Result<()> {
+/// use std::io::{Cursor, Write};
+///
+/// use jumbf::{
+///     builder::{BoxWriter, SuperBoxBuilder},
+///     BoxType,
+/// };
+///
+/// let mut writer = BoxWriter::new(Cursor::new(Vec::<u8>::new()));
+///
+/// writer.begin_super_box(&SuperBoxBuilder::new(&[0u8; 16]).set_label("stream"))?;
+///
+/// writer.begin_box(BoxType(*b"abcd"))?;
+/// for chunk in [b"streamed ".as_slice(), b"payload"] {
+///     writer.write_all(chunk)?;
+/// }
+/// writer.end_box()?;
+///
+/// writer.end_box()?;
+///
+/// let jumbf = writer.finish()?.into_inner();
+/// assert_eq!(&jumbf[4..8], b"jumb");
+/// assert!(jumbf.ends_with(b"streamed payload"));
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+///
+/// [`begin_box()`]: Self::begin_box
+/// [`write_box()`]: Self::write_box
+/// [`end_box()`]: Self::end_box
+pub struct BoxWriter<W: Write + Seek> {
+    inner: W,
+    open_boxes: Vec<u64>,
+}
+
+impl<W: Write + Seek> BoxWriter<W> {
+    /// Create a `BoxWriter` which writes to `inner`, starting at its
+    /// current position.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            open_boxes: vec![],
+        }
+    }
+
+    /// Start a new box of type `tbox`.
+    ///
+    /// If another box is open, the new box becomes part of its payload.
+    pub fn begin_box(&mut self, tbox: BoxType) -> Result<()> {
+        let offset = self.inner.stream_position()?;
+
+        let mut header = [0u8; 8];
+        header[4..8].copy_from_slice(&tbox.0);
+        self.inner.write_all(&header)?;
+
+        self.open_boxes.push(offset);
+        Ok(())
+    }
+
+    /// Start a new superbox described by `sbox`.
+    ///
+    /// The description box and any child boxes already added to `sbox` are
+    /// written immediately. Further child boxes may then be written before
+    /// calling [`end_box()`].
+    ///
+    /// Returns an error if `sbox` uses [`set_computed_hash()`], since the
+    /// hash could not include child boxes written later.
+    ///
+    /// [`end_box()`]: Self::end_box
+    /// [`set_computed_hash()`]: SuperBoxBuilder::set_computed_hash
+    pub fn begin_super_box(&mut self, sbox: &SuperBoxBuilder) -> Result<()> {
+        if sbox.has_computed_hash() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "can't stream a superbox with a computed hash",
+            ));
+        }
+
+        self.begin_box(SUPER_BOX_TYPE)?;
+        sbox.write_payload(&mut self.inner)
+    }
+
+    /// Write a complete box, whose size is known in advance, as part of the
+    /// payload of the currently open box (if any).
+    pub fn write_box(&mut self, boxx: &dyn ToBox) -> Result<()> {
+        write_jumbf(boxx, &mut self.inner)
+    }
+
+    /// Finish the most recently started box by filling in its size.
+    ///
+    /// Returns the range of the stream occupied by the box.
+    pub fn end_box(&mut self) -> Result<Range<u64>> {
+        let start = self
+            .open_boxes
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "no box is open"))?;
+
+        patch_size(&mut self.inner, start)
+    }
+
+    /// Returns the number of boxes which have been started but not ended.
+    pub fn open_boxes(&self) -> usize {
+        self.open_boxes.len()
+    }
+
+    /// Return the underlying stream.
+    ///
+    /// Returns an error if any box is still open.
+    pub fn finish(self) -> Result<W> {
+        if !self.open_boxes.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{count} box(es) still open", count = self.open_boxes.len()),
+            ));
+        }
+
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write + Seek> Write for BoxWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
@@ -14,7 +14,10 @@
 use std::{borrow::Cow, io::Result};
 
 use crate::{
-    builder::{ToBox, WriteAndSeek},
+    builder::{
+        to_box::{add_size, write_all_vectored},
+        ToBox, WriteAndSeek,
+    },
     BoxType,
 };
 
@@ -34,7 +37,7 @@ use crate::{
 /// [`SuperBoxBuilder`]: crate::builder::SuperBoxBuilder
 pub struct DataBoxBuilder<'a> {
     tbox: BoxType,
-    data: Cow<'a, [u8]>,
+    segments: Vec<Cow<'a, [u8]>>,
 }
 
 impl<'a> DataBoxBuilder<'a> {
@@ -45,7 +48,7 @@ impl<'a> DataBoxBuilder<'a> {
     pub fn from_borrowed(tbox: BoxType, data: &'a [u8]) -> Self {
         Self {
             tbox,
-            data: Cow::from(data),
+            segments: vec![Cow::from(data)],
         }
     }
 
@@ -55,9 +58,42 @@ impl<'a> DataBoxBuilder<'a> {
     pub fn from_owned(tbox: BoxType, data: Vec<u8>) -> Self {
         Self {
             tbox,
-            data: Cow::from(data),
+            segments: vec![Cow::from(data)],
         }
     }
+
+    /// Create a `DataBoxBuilder` from a JUMBF box type and a payload which
+    /// is the concatenation of `segments`.
+    ///
+    /// This avoids copying a payload that is assembled from several
+    /// buffers (for example, a header, body, and footer) into a single
+    /// allocation. Each segment may be borrowed or owned.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, ToBox},
+    ///     BoxType,
+    /// };
+    ///
+    /// let body = b"body".to_vec();
+    /// let dbox = DataBoxBuilder::from_segments(
+    ///     BoxType(*b"abcd"),
+    ///     vec![
+    ///         Cow::Borrowed(b"head"),
+    ///         Cow::Owned(body),
+    ///         Cow::Borrowed(b"foot"),
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(dbox.payload_size().unwrap(), 12);
+    /// ```
+    pub fn from_segments(tbox: BoxType, segments: Vec<Cow<'a, [u8]>>) -> Self {
+        Self { tbox, segments }
+    }
 }
 
 impl<'a> ToBox for DataBoxBuilder<'a> {
@@ -66,10 +102,13 @@ impl<'a> ToBox for DataBoxBuilder<'a> {
     }
 
     fn payload_size(&self) -> Result<usize> {
-        Ok(self.data.len())
+        self.segments
+            .iter()
+            .try_fold(0, |size, segment| add_size(size, segment.len()))
     }
 
     fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
-        to_stream.write_all(&self.data)
+        let segments: Vec<&[u8]> = self.segments.iter().map(|s| s.as_ref()).collect();
+        write_all_vectored(to_stream, &segments)
     }
 }
@@ -16,12 +16,18 @@
 //!
 //! [JUMBF (ISO/IEC 19566-5:2019)]: (https://www.iso.org/standard/73604.html)
 
+mod box_writer;
 mod data_box_builder;
 mod placeholder_data_box;
-mod super_box_builder;
+mod sequence;
+mod signature_placeholder;
+pub(crate) mod super_box_builder;
 pub(crate) mod to_box;
 
+pub use box_writer::BoxWriter;
 pub use data_box_builder::DataBoxBuilder;
 pub use placeholder_data_box::PlaceholderDataBox;
-pub use super_box_builder::SuperBoxBuilder;
+pub use sequence::write_jumbf_sequence;
+pub use signature_placeholder::SignaturePlaceholder;
+pub use super_box_builder::{BoxPlan, ChildOrder, SuperBoxBuilder};
 pub use to_box::{ToBox, WriteAndSeek};
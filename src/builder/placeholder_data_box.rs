@@ -13,7 +13,7 @@
 
 use std::{
     cell::RefCell,
-    io::{Error, ErrorKind, Result},
+    io::{Error, Result},
 };
 
 use crate::{
@@ -35,6 +35,7 @@ pub struct PlaceholderDataBox {
     tbox: BoxType,
     size: usize,
     offset: RefCell<Option<u64>>,
+    written_len: RefCell<usize>,
 }
 
 impl PlaceholderDataBox {
@@ -46,9 +47,35 @@ impl PlaceholderDataBox {
             tbox,
             size,
             offset: RefCell::new(None),
+            written_len: RefCell::new(0),
         }
     }
 
+    /// Return the number of payload bytes reserved by this placeholder.
+    pub fn capacity(&self) -> usize {
+        self.size
+    }
+
+    /// Return the number of payload bytes that have been written into this
+    /// placeholder since it was last written as part of a superbox.
+    ///
+    /// This is measured from the start of the placeholder to the end of the
+    /// furthest replacement, so any gaps left between calls to
+    /// [`replace_payload_at()`] are counted as written.
+    ///
+    /// [`replace_payload_at()`]: Self::replace_payload_at()
+    pub fn written_len(&self) -> usize {
+        *self.written_len.borrow()
+    }
+
+    /// Return the number of payload bytes after [`written_len()`] which are
+    /// still available.
+    ///
+    /// [`written_len()`]: Self::written_len()
+    pub fn remaining(&self) -> usize {
+        self.size - self.written_len()
+    }
+
     /// Return the offset in the stream where the payload can be written.
     ///
     /// Will return `None` before the superbox's [`write_jumbf()`] method is
@@ -71,25 +98,67 @@ impl PlaceholderDataBox {
     /// [`offset()`]: Self::offset()
     pub fn replace_payload(&self, to_stream: &mut dyn WriteAndSeek, payload: &[u8]) -> Result<()> {
         if payload.len() > self.size {
-            return Err(Error::new(
-                ErrorKind::Other,
+            return Err(Error::other(
                 format!("replace_payload: payload ({len} bytes) is larger than reserved capacity ({reserve} bytes)", len = payload.len(), reserve = self.size)
             ));
         }
 
+        self.write_at(to_stream, 0, payload, "replace_payload")
+    }
+
+    /// Replace part of the zero-filled placeholder content with actual
+    /// content, starting `offset` bytes into the placeholder.
+    ///
+    /// This allows a payload to be written in several steps (for example, a
+    /// signature followed later by a timestamp). Placeholder content outside
+    /// of the replaced range is left unchanged.
+    ///
+    /// An error will be returned if `payload` would extend beyond the
+    /// placeholder size specified when this `PlaceholderDataBox` was created.
+    pub fn replace_payload_at(
+        &self,
+        to_stream: &mut dyn WriteAndSeek,
+        offset: usize,
+        payload: &[u8],
+    ) -> Result<()> {
+        if offset
+            .checked_add(payload.len())
+            .map_or(true, |end| end > self.size)
+        {
+            return Err(Error::other(format!(
+                "replace_payload_at: payload ({len} bytes) at offset {offset} extends beyond reserved capacity ({reserve} bytes)",
+                len = payload.len(),
+                reserve = self.size
+            )));
+        }
+
+        self.write_at(to_stream, offset, payload, "replace_payload_at")
+    }
+
+    fn write_at(
+        &self,
+        to_stream: &mut dyn WriteAndSeek,
+        at: usize,
+        payload: &[u8],
+        caller: &str,
+    ) -> Result<()> {
         let offset = self.offset.borrow();
 
         if let Some(offset) = *offset {
-            to_stream.seek(std::io::SeekFrom::Start(offset))?;
-            to_stream.write_all(payload)
+            to_stream.seek(std::io::SeekFrom::Start(offset + at as u64))?;
+            to_stream.write_all(payload)?;
+
+            let end = at + payload.len();
+            let mut written_len = self.written_len.borrow_mut();
+            *written_len = (*written_len).max(end);
+            Ok(())
         } else {
             // HINT: If you receive this error, be sure to call write_jumbf() on a superbox
             // containing this box first.
 
-            Err(Error::new(
-                ErrorKind::Other,
-                "replace_payload: no offset recorded; call write_jumbf() first".to_string(),
-            ))
+            Err(Error::other(format!(
+                "{caller}: no offset recorded; call write_jumbf() first"
+            )))
         }
     }
 }
@@ -108,13 +177,13 @@ impl ToBox for PlaceholderDataBox {
 
         match offset {
             0 => {
-                return Err(Error::new(
-                    ErrorKind::Other,
+                return Err(Error::other(
                     "placeholder stream should have some data already",
                 ));
             }
             _ => {
                 self.offset.replace(Some(offset));
+                self.written_len.replace(0);
             }
         };
 
@@ -0,0 +1,76 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{io::Result, ops::Range};
+
+use crate::builder::{
+    to_box::{add_size, jumbf_size, write_jumbf},
+    ToBox, WriteAndSeek,
+};
+
+/// Write a sequence of sibling top-level boxes (typically superboxes) to a
+/// JUMBF stream, one immediately after another.
+///
+/// The size of every box is computed before anything is written, so an
+/// error in any box (for example, a superbox that exceeds its
+/// [maximum size]) is reported before the stream is modified.
+///
+/// Returns the byte range of the stream occupied by each box, in the same
+/// order as `boxes`. The overall extent of the sequence runs from the start
+/// of the first range to the end of the last.
+///
+/// ## Example
+///
+/// ```
+/// # fn example() -> std::io::Result<()> {
+/// use std::io::Cursor;
+///
+/// use jumbf::builder::{write_jumbf_sequence, SuperBoxBuilder, ToBox};
+///
+/// let first = SuperBoxBuilder::new(&[0u8; 16]).set_label("first");
+/// let second = SuperBoxBuilder::new(&[0u8; 16]).set_label("second");
+///
+/// let mut jumbf = Cursor::new(Vec::<u8>::new());
+/// let ranges = write_jumbf_sequence(&[&first as &dyn ToBox, &second], &mut jumbf)?;
+///
+/// assert_eq!(ranges, vec![0..39, 39..79]);
+/// assert_eq!(jumbf.get_ref().len(), 79);
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+///
+/// [maximum size]: crate::builder::SuperBoxBuilder::set_max_size
+pub fn write_jumbf_sequence(
+    boxes: &[&dyn ToBox],
+    to_stream: &mut dyn WriteAndSeek,
+) -> Result<Vec<Range<u64>>> {
+    let mut sizes = Vec::with_capacity(boxes.len());
+    let mut total_size: usize = 0;
+    for boxx in boxes {
+        let size = jumbf_size(*boxx)?;
+        total_size = add_size(total_size, size)?;
+        sizes.push(size as u64);
+    }
+
+    let mut offset = to_stream.stream_position()?;
+    let mut ranges = Vec::with_capacity(boxes.len());
+
+    for (boxx, size) in boxes.iter().zip(sizes) {
+        write_jumbf(*boxx, to_stream)?;
+        ranges.push(offset..offset + size);
+        offset += size;
+    }
+
+    Ok(ranges)
+}
@@ -0,0 +1,174 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    io::{Error, ErrorKind, Read, Result, Seek, SeekFrom},
+    ops::Range,
+};
+
+use crate::{
+    builder::{PlaceholderDataBox, SuperBoxBuilder, ToBox, WriteAndSeek},
+    BoxType,
+};
+
+/// A `SignaturePlaceholder` reserves space for a signature over the JUMBF
+/// data structure which contains it.
+///
+/// Signing a JUMBF data structure which contains its own signature requires
+/// several steps:
+///
+/// 1. Add the `SignaturePlaceholder` to a [`SuperBoxBuilder`] using
+///    [`SuperBoxBuilder::add_borrowed_child_box()`].
+/// 2. Call [`write_jumbf()`] to write the outermost superbox. This returns the
+///    byte ranges of the stream which should be signed: everything that was
+///    written _except_ the signature's reserved space.
+/// 3. Call [`signed_data()`] to read those byte ranges and sign them.
+/// 4. Call [`write_signature()`] to write the signature into the reserved
+///    space.
+///
+/// ## Example
+///
+/// ```
+/// # fn example() -> std::io::Result<()> {
+/// use std::io::Cursor;
+///
+/// use jumbf::{
+///     builder::{DataBoxBuilder, SignaturePlaceholder, SuperBoxBuilder},
+///     BoxType,
+/// };
+///
+/// let sig = SignaturePlaceholder::new(BoxType(*b"sigx"), 64);
+///
+/// let sbox = SuperBoxBuilder::new(&[0u8; 16])
+///     .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"claim"))
+///     .add_borrowed_child_box(&sig);
+///
+/// let mut jumbf = Cursor::new(Vec::<u8>::new());
+/// let ranges = sig.write_jumbf(&sbox, &mut jumbf)?;
+///
+/// let signed_data = sig.signed_data(&ranges, &mut jumbf)?;
+/// let signature = b"not a real signature"; // sign `signed_data` here
+///
+/// sig.write_signature(&mut jumbf, signature)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`write_jumbf()`]: Self::write_jumbf()
+/// [`signed_data()`]: Self::signed_data()
+/// [`write_signature()`]: Self::write_signature()
+pub struct SignaturePlaceholder {
+    placeholder: PlaceholderDataBox,
+}
+
+impl SignaturePlaceholder {
+    /// Create a new signature placeholder that will reserve `capacity` bytes.
+    ///
+    /// The box will be given the JUMBF box type specified by `tbox`.
+    pub fn new(tbox: BoxType, capacity: usize) -> Self {
+        Self {
+            placeholder: PlaceholderDataBox::new(tbox, capacity),
+        }
+    }
+
+    /// Write `sbox`, which must contain this placeholder, to `to_stream`.
+    ///
+    /// Returns the byte ranges within `to_stream` which should be signed.
+    /// These are the portions of the superbox before and after the space
+    /// reserved for the signature. Either range may be empty.
+    pub fn write_jumbf(
+        &self,
+        sbox: &SuperBoxBuilder,
+        to_stream: &mut dyn WriteAndSeek,
+    ) -> Result<[Range<u64>; 2]> {
+        let start = to_stream.stream_position()?;
+        sbox.write_jumbf(to_stream)?;
+        let end = to_stream.stream_position()?;
+
+        let placeholder = self.placeholder.offset().and_then(|offset| {
+            let placeholder_end = offset.checked_add(self.placeholder.capacity() as u64)?;
+            (offset >= start && placeholder_end <= end).then_some(offset..placeholder_end)
+        });
+
+        let Some(placeholder) = placeholder else {
+            return Err(Error::other(
+                "signature placeholder was not written as part of the superbox",
+            ));
+        };
+
+        Ok([start..placeholder.start, placeholder.end..end])
+    }
+
+    /// Read the data which should be signed from `stream`.
+    ///
+    /// `ranges` should be the value returned by [`write_jumbf()`]. An
+    /// [`ErrorKind::InvalidInput`] error is returned if a range ends before
+    /// it starts, and an [`ErrorKind::UnexpectedEof`] error is returned if
+    /// `stream` ends before the end of a range.
+    ///
+    /// [`write_jumbf()`]: Self::write_jumbf()
+    pub fn signed_data<S: Read + Seek>(
+        &self,
+        ranges: &[Range<u64>],
+        stream: &mut S,
+    ) -> Result<Vec<u8>> {
+        let mut data = vec![];
+
+        for range in ranges {
+            let Some(len) = range.end.checked_sub(range.start) else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("signed_data: range {range:?} ends before it starts"),
+                ));
+            };
+
+            stream.seek(SeekFrom::Start(range.start))?;
+            let read = stream.by_ref().take(len).read_to_end(&mut data)?;
+            if (read as u64) < len {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Write the signature into the space reserved by this placeholder.
+    ///
+    /// If the signature is shorter than the reserved capacity, the remaining
+    /// space is left zero-filled. An error will be returned if the signature
+    /// is larger than the reserved capacity or if [`write_jumbf()`] has not
+    /// been called.
+    ///
+    /// [`write_jumbf()`]: Self::write_jumbf()
+    pub fn write_signature(
+        &self,
+        to_stream: &mut dyn WriteAndSeek,
+        signature: &[u8],
+    ) -> Result<()> {
+        self.placeholder.replace_payload(to_stream, signature)
+    }
+}
+
+impl ToBox for SignaturePlaceholder {
+    fn box_type(&self) -> BoxType {
+        self.placeholder.box_type()
+    }
+
+    fn payload_size(&self) -> Result<usize> {
+        self.placeholder.payload_size()
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        self.placeholder.write_payload(to_stream)
+    }
+}
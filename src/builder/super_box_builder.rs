@@ -11,14 +11,20 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::io::Result;
+use std::{
+    any::Any,
+    io::{self, Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+};
 
 use crate::{
-    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    box_type::{C2PA_SALT_BOX_TYPE, DESCRIPTION_BOX_TYPE, FREE_BOX_TYPE, SUPER_BOX_TYPE},
     builder::{
-        to_box::{jumbf_size, write_jumbf},
-        ToBox, WriteAndSeek,
+        to_box::{add_size, jumbf_size, jumbf_size_from_payload_size, patch_size, write_jumbf},
+        DataBoxBuilder, ToBox, WriteAndSeek,
     },
+    digest::Digest,
+    model::SuperBoxModel,
+    toggles::Toggles,
     BoxType,
 };
 
@@ -77,6 +83,34 @@ use crate::{
 pub struct SuperBoxBuilder<'a> {
     desc: DescriptionBoxBuilder,
     child_boxes: Vec<OwnedOrBorrowedBox<'a>>,
+    child_order: ChildOrder,
+    hash_digest: Option<Box<dyn Digest>>,
+    max_size: Option<usize>,
+    alignment: Option<usize>,
+}
+
+/// Describes the order in which a [`SuperBoxBuilder`] writes its child boxes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ChildOrder {
+    /// Child boxes are written in the order in which they were added.
+    #[default]
+    Insertion,
+
+    /// Child boxes are sorted by box type and then by the byte-wise
+    /// content of their payloads.
+    ///
+    /// Two superboxes which have the same description and the same set of
+    /// child boxes will serialize to identical bytes regardless of the order
+    /// in which the child boxes were added. This is useful when the
+    /// serialized JUMBF will be hashed or deduplicated.
+    ///
+    /// Each child box is serialized once to determine the sort order and
+    /// the result is kept in memory until it is written. Child boxes which
+    /// depend on their position in the stream (such as
+    /// [`PlaceholderDataBox`]) are serialized again when written.
+    ///
+    /// [`PlaceholderDataBox`]: crate::builder::PlaceholderDataBox
+    Canonical,
 }
 
 impl<'a> SuperBoxBuilder<'a> {
@@ -89,6 +123,67 @@ impl<'a> SuperBoxBuilder<'a> {
         Self {
             desc: DescriptionBoxBuilder::new(uuid),
             child_boxes: vec![],
+            child_order: ChildOrder::default(),
+            hash_digest: None,
+            max_size: None,
+            alignment: None,
+        }
+    }
+
+    /// Create a new, empty superbox with the given UUID and (requestable)
+    /// label.
+    ///
+    /// This is equivalent to `SuperBoxBuilder::new(uuid).set_label(label)`.
+    pub fn with_uuid_and_label<S: AsRef<str>>(uuid: &[u8; 16], label: S) -> Self {
+        Self::new(uuid).set_label(label)
+    }
+
+    /// Create a superbox builder which describes an owned
+    /// [`SuperBoxModel`].
+    ///
+    /// The description box is copied from `model`. Child boxes (including
+    /// nested superboxes) are borrowed from `model` and written as is.
+    /// Additional child boxes may be added and the description may be
+    /// changed using the usual `set_*` and `add_*` methods.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use jumbf::{
+    ///     builder::SuperBoxBuilder,
+    ///     model::{ChildBoxModel, DataBoxModel, SuperBoxModel},
+    ///     BoxType,
+    /// };
+    ///
+    /// let model = SuperBoxModel {
+    ///     label: Some("example".to_owned()),
+    ///     requestable: true,
+    ///     child_boxes: vec![ChildBoxModel::DataBox(DataBoxModel {
+    ///         tbox: BoxType(*b"json"),
+    ///         data: b"{}".to_vec(),
+    ///     })],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let sbox = SuperBoxBuilder::from_model(&model).set_id(7);
+    ///
+    /// let mut jumbf = Cursor::new(Vec::<u8>::new());
+    /// sbox.write_jumbf(&mut jumbf).unwrap();
+    /// ```
+    pub fn from_model(model: &'a SuperBoxModel) -> Self {
+        Self {
+            desc: model.desc(),
+            child_boxes: model
+                .child_boxes
+                .iter()
+                .map(|child| OwnedOrBorrowedBox::BorrowedBox(child.as_to_box()))
+                .collect(),
+            child_order: ChildOrder::default(),
+            hash_digest: None,
+            max_size: None,
+            alignment: None,
         }
     }
 
@@ -137,6 +232,25 @@ impl<'a> SuperBoxBuilder<'a> {
         self
     }
 
+    /// Compute a hash of this superbox's data payload using `digest` when
+    /// the superbox is written and store it in the description box.
+    ///
+    /// The hash covers the serialized form of all child boxes. Any
+    /// [`PlaceholderDataBox`] children are hashed in their zero-filled form.
+    ///
+    /// The description box can only store a 32-byte hash, so
+    /// [`write_jumbf()`] will return an error if `digest` produces a result
+    /// of any other size. This replaces any hash provided via
+    /// [`set_sha256_hash()`].
+    ///
+    /// [`PlaceholderDataBox`]: crate::builder::PlaceholderDataBox
+    /// [`write_jumbf()`]: Self::write_jumbf()
+    /// [`set_sha256_hash()`]: Self::set_sha256_hash()
+    pub fn set_computed_hash(mut self, digest: impl Digest + 'static) -> Self {
+        self.hash_digest = Some(Box::new(digest));
+        self
+    }
+
     /// Provide an application-specific "private" box within
     /// the description box. Takes ownership of the box.
     pub fn set_private_box(mut self, private: impl ToBox + 'static) -> Self {
@@ -144,6 +258,41 @@ impl<'a> SuperBoxBuilder<'a> {
         self
     }
 
+    /// Add a salt to this superbox using the C2PA convention: a `c2sh`
+    /// private box within the description box whose payload is `salt`.
+    ///
+    /// Salting a C2PA assertion makes its hash unpredictable, so that the
+    /// hash doesn't reveal the content of a redacted assertion. The salt is
+    /// included in the hash of the superbox (see [`SuperBox::digest()`]).
+    ///
+    /// This replaces any private box previously provided.
+    ///
+    /// [`SuperBox::digest()`]: crate::parser::SuperBox::digest
+    pub fn set_salt(self, salt: &[u8]) -> Self {
+        self.set_private_box(DataBoxBuilder::from_owned(
+            C2PA_SALT_BOX_TYPE,
+            salt.to_vec(),
+        ))
+    }
+
+    /// Add a salt of `len` random bytes to this superbox using the C2PA
+    /// convention. See [`set_salt()`].
+    ///
+    /// The random bytes are obtained from the operating system via the
+    /// [`getrandom`] crate. Returns an error if they are unavailable.
+    ///
+    /// This method is available when the `getrandom` crate feature is
+    /// enabled.
+    ///
+    /// [`set_salt()`]: Self::set_salt
+    /// [`getrandom`]: https://docs.rs/getrandom
+    #[cfg(feature = "getrandom")]
+    pub fn set_random_salt(self, len: usize) -> Result<Self> {
+        let mut salt = vec![0u8; len];
+        getrandom::getrandom(&mut salt).map_err(Error::from)?;
+        Ok(self.set_salt(&salt))
+    }
+
     /// Add a child box. Takes ownership of the box.
     pub fn add_child_box(mut self, boxx: impl ToBox + 'static) -> Self {
         self.child_boxes
@@ -151,6 +300,43 @@ impl<'a> SuperBoxBuilder<'a> {
         self
     }
 
+    /// Add a child superbox with the given `uuid` and (requestable) `label`,
+    /// whose contents are described by the closure `f`.
+    ///
+    /// `f` is passed a new, empty [`SuperBoxBuilder`] and should return it
+    /// after adding the child's content. This allows nested structures to be
+    /// declared inline, without an intermediate variable for each superbox.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, SuperBoxBuilder},
+    ///     BoxType,
+    /// };
+    ///
+    /// let manifest_uuid = [1u8; 16];
+    /// let assertions_uuid = [2u8; 16];
+    /// let json_uuid = [3u8; 16];
+    ///
+    /// let sbox = SuperBoxBuilder::new(&manifest_uuid)
+    ///     .set_label("manifest")
+    ///     .add_child_super_box(&assertions_uuid, "assertions", |b| {
+    ///         b.add_child_super_box(&json_uuid, "data", |b| {
+    ///             b.add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"json"), b"{}"))
+    ///         })
+    ///     });
+    ///
+    /// assert!(sbox.find("assertions/data").is_some());
+    /// ```
+    pub fn add_child_super_box<S, F>(self, uuid: &[u8; 16], label: S, f: F) -> Self
+    where
+        S: AsRef<str>,
+        F: FnOnce(SuperBoxBuilder<'static>) -> SuperBoxBuilder<'static>,
+    {
+        self.add_child_box(f(SuperBoxBuilder::new(uuid).set_label(label)))
+    }
+
     /// Add a child box without taking ownership.
     ///
     /// The child box's lifetime must be at least as long as this superbox.
@@ -159,10 +345,569 @@ impl<'a> SuperBoxBuilder<'a> {
         self
     }
 
+    /// Add a boxed child box. Takes ownership of the box.
+    ///
+    /// Unlike [`add_child_box()`], the child box need not be `'static`; it
+    /// may borrow data which lives at least as long as this superbox. This
+    /// allows a list of child boxes of different types (including your own
+    /// [`ToBox`] implementations) to be assembled ahead of time.
+    ///
+    /// Superboxes added this way can't be found by [`find()`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, SuperBoxBuilder, ToBox},
+    ///     BoxType,
+    /// };
+    ///
+    /// let data = b"some data".to_vec();
+    ///
+    /// let children: Vec<Box<dyn ToBox + '_>> = vec![
+    ///     Box::new(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), &data)),
+    ///     Box::new(SuperBoxBuilder::new(&[0u8; 16]).set_label("empty")),
+    /// ];
+    ///
+    /// let sbox = children
+    ///     .into_iter()
+    ///     .fold(SuperBoxBuilder::new(&[0u8; 16]), |sbox, child| {
+    ///         sbox.add_boxed_child_box(child)
+    ///     });
+    /// ```
+    ///
+    /// [`add_child_box()`]: Self::add_child_box()
+    /// [`find()`]: Self::find()
+    pub fn add_boxed_child_box(mut self, boxx: Box<dyn ToBox + 'a>) -> Self {
+        self.child_boxes.push(OwnedOrBorrowedBox::Boxed(boxx));
+        self
+    }
+
+    /// Returns the toggles which will be written in this superbox's
+    /// description box.
+    pub fn toggles(&self) -> Toggles {
+        self.desc
+            .toggles(self.desc.hash.is_some() || self.hash_digest.is_some())
+    }
+
+    /// Set the order in which child boxes will be written.
+    ///
+    /// This builder always uses the smallest possible box header encoding,
+    /// so [`ChildOrder::Canonical`] is sufficient to make its output
+    /// canonical when no alignment is set. The `free` boxes inserted by
+    /// [`set_alignment()`] are part of the output, so aligned output is only
+    /// canonical if the same alignment is applied consistently (i.e. to the
+    /// same superboxes, with the same value). Note that this setting applies
+    /// only to this superbox; call it on each nested `SuperBoxBuilder` as
+    /// needed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # fn example() -> std::io::Result<()> {
+    /// use std::io::Cursor;
+    ///
+    /// use jumbf::{
+    ///     builder::{ChildOrder, DataBoxBuilder, SuperBoxBuilder},
+    ///     BoxType,
+    /// };
+    ///
+    /// let a = DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"a");
+    /// let b = DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"b");
+    ///
+    /// let sbox1 = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_child_order(ChildOrder::Canonical)
+    ///     .add_borrowed_child_box(&a)
+    ///     .add_borrowed_child_box(&b);
+    ///
+    /// let sbox2 = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_child_order(ChildOrder::Canonical)
+    ///     .add_borrowed_child_box(&b)
+    ///     .add_borrowed_child_box(&a);
+    ///
+    /// let mut jumbf1 = Cursor::new(Vec::<u8>::new());
+    /// sbox1.write_jumbf(&mut jumbf1)?;
+    ///
+    /// let mut jumbf2 = Cursor::new(Vec::<u8>::new());
+    /// sbox2.write_jumbf(&mut jumbf2)?;
+    ///
+    /// assert_eq!(jumbf1.into_inner(), jumbf2.into_inner());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`set_alignment()`]: Self::set_alignment
+    pub fn set_child_order(mut self, child_order: ChildOrder) -> Self {
+        self.child_order = child_order;
+        self
+    }
+
+    /// Limit the total size of this superbox, including its box header, to
+    /// `max_size` bytes.
+    ///
+    /// If the superbox would be larger than this, [`write_jumbf()`] returns
+    /// an error before anything is written to the stream. This also applies
+    /// when this superbox is nested within another superbox.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::{Cursor, ErrorKind};
+    ///
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, SuperBoxBuilder},
+    ///     BoxType,
+    /// };
+    ///
+    /// let sbox = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_max_size(64)
+    ///     .add_child_box(DataBoxBuilder::from_owned(BoxType(*b"abcd"), vec![0; 100]));
+    ///
+    /// let mut jumbf = Cursor::new(Vec::<u8>::new());
+    /// let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    ///
+    /// assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    /// assert!(jumbf.get_ref().is_empty());
+    /// ```
+    ///
+    /// [`write_jumbf()`]: Self::write_jumbf()
+    pub fn set_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Align the child boxes of this superbox to multiples of `alignment`
+    /// bytes, measured from the start of this superbox.
+    ///
+    /// The payload of each child data box will start at an aligned offset.
+    /// Each child superbox will itself start at an aligned offset, so a
+    /// child superbox which has the same alignment will have its own
+    /// children aligned consistently with this one.
+    ///
+    /// Alignment is achieved by inserting zero-filled `free` boxes between
+    /// child boxes as needed. (The description box is always the first box
+    /// in the superbox and is never preceded by padding.) Since a `free` box
+    /// is at least 8 bytes long, some padding may be larger than `alignment`.
+    /// Parsers will see the `free` boxes as ordinary child data boxes.
+    ///
+    /// To align child boxes within a file, write the outermost superbox at an
+    /// offset which is a multiple of `alignment`. An `alignment` of 0 or 1
+    /// disables alignment.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, SuperBoxBuilder},
+    ///     BoxType,
+    /// };
+    ///
+    /// let sbox = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_alignment(64)
+    ///     .add_child_box(DataBoxBuilder::from_owned(BoxType(*b"abcd"), vec![1; 10]));
+    ///
+    /// let mut jumbf = Cursor::new(Vec::<u8>::new());
+    /// sbox.write_jumbf(&mut jumbf).unwrap();
+    ///
+    /// // The data box header is followed by its payload at offset 64.
+    /// let jumbf = jumbf.into_inner();
+    /// assert_eq!(&jumbf[60..64], b"abcd");
+    /// assert_eq!(jumbf[64..], [1; 10]);
+    /// ```
+    pub fn set_alignment(mut self, alignment: usize) -> Self {
+        self.alignment = (alignment > 1).then_some(alignment);
+        self
+    }
+
+    /// Find a descendant superbox of this superbox by following `path`.
+    ///
+    /// `path` is a `/`-separated list of labels, starting with a label of
+    /// one of this superbox's children. As with [`SuperBox::find_by_label()`],
+    /// each label must be requestable and must identify exactly one child.
+    ///
+    /// Only superboxes which were added via [`add_child_box()`] can be
+    /// found. Borrowed child boxes are not searched.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jumbf::builder::SuperBoxBuilder;
+    ///
+    /// let claim = SuperBoxBuilder::new(&[0u8; 16]).set_label("claim");
+    /// let manifest = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_label("manifest")
+    ///     .add_child_box(claim);
+    /// let mut store = SuperBoxBuilder::new(&[0u8; 16])
+    ///     .set_label("c2pa")
+    ///     .add_child_box(manifest);
+    ///
+    /// assert!(store.find("manifest/claim").is_some());
+    /// assert!(store.find("manifest/signature").is_none());
+    ///
+    /// assert!(store.update("manifest/claim", |claim| claim.set_id(42)));
+    /// ```
+    ///
+    /// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label()
+    /// [`add_child_box()`]: Self::add_child_box()
+    pub fn find(&self, path: &str) -> Option<&SuperBoxBuilder<'a>> {
+        path.split('/').try_fold(self, |sbox, label| {
+            sbox.find_child(label).map(|child| child as _)
+        })
+    }
+
+    /// Replace the descendant superbox found by [`find()`] with the result
+    /// of calling `f` on it.
+    ///
+    /// Returns `false` (and does not call `f`) if no such superbox exists.
+    ///
+    /// [`find()`]: Self::find()
+    pub fn update<F>(&mut self, path: &str, f: F) -> bool
+    where
+        F: FnOnce(SuperBoxBuilder<'static>) -> SuperBoxBuilder<'static>,
+    {
+        let mut labels = path.split('/');
+        let Some(mut sbox) = labels.next().and_then(|label| self.find_child_mut(label)) else {
+            return false;
+        };
+
+        for label in labels {
+            let Some(child) = sbox.find_child_mut(label) else {
+                return false;
+            };
+            sbox = child;
+        }
+
+        let original = std::mem::replace(sbox, SuperBoxBuilder::new(&[0; 16]));
+        *sbox = f(original);
+        true
+    }
+
+    fn find_child(&self, label: &str) -> Option<&SuperBoxBuilder<'static>> {
+        let index = self.find_child_index(label)?;
+        self.child_boxes[index].as_super_box()
+    }
+
+    fn find_child_mut(&mut self, label: &str) -> Option<&mut SuperBoxBuilder<'static>> {
+        let index = self.find_child_index(label)?;
+        self.child_boxes[index].as_super_box_mut()
+    }
+
+    fn find_child_index(&self, label: &str) -> Option<usize> {
+        let mut matching_children = self.child_boxes.iter().enumerate().filter(|(_, child)| {
+            child.as_super_box().is_some_and(|sbox| {
+                sbox.desc.requestable && sbox.desc.label.as_deref() == Some(label)
+            })
+        });
+
+        let (index, _) = matching_children.next()?;
+        if matching_children.next().is_some() {
+            return None;
+        }
+        Some(index)
+    }
+
+    pub(crate) fn has_computed_hash(&self) -> bool {
+        self.hash_digest.is_some()
+    }
+
+    /// Compute the size of this superbox and of each of its child boxes,
+    /// without writing anything.
+    ///
+    /// Unlike [`write_jumbf()`], this does not fail if this superbox (or a
+    /// nested superbox) exceeds the limit set by [`set_max_size()`]. Instead,
+    /// the limit is reported in [`BoxPlan::max_size`], so that you can see
+    /// which child boxes are responsible.
+    ///
+    /// Nested superboxes which were added via [`add_child_box()`] or
+    /// [`add_child_super_box()`] are broken down in the same way. Any other
+    /// child box (including a borrowed superbox) is reported as a single
+    /// box. Child boxes are listed in the order in which they will be
+    /// written, starting with the description box and including any
+    /// padding added for alignment.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jumbf::{
+    ///     builder::{DataBoxBuilder, SuperBoxBuilder},
+    ///     BoxType,
+    /// };
+    ///
+    /// let sbox = SuperBoxBuilder::with_uuid_and_label(&[0u8; 16], "manifest")
+    ///     .set_max_size(200)
+    ///     .add_child_super_box(&[0u8; 16], "small", |b| {
+    ///         b.add_child_box(DataBoxBuilder::from_owned(BoxType(*b"abcd"), vec![0; 10]))
+    ///     })
+    ///     .add_child_super_box(&[0u8; 16], "large", |b| {
+    ///         b.add_child_box(DataBoxBuilder::from_owned(BoxType(*b"abcd"), vec![0; 1000]))
+    ///     });
+    ///
+    /// let plan = sbox.plan().unwrap();
+    /// assert!(plan.exceeds_max_size());
+    ///
+    /// let largest = plan.children.iter().max_by_key(|c| c.size).unwrap();
+    /// assert_eq!(largest.label.as_deref(), Some("large"));
+    /// assert_eq!(largest.size, 1047);
+    /// ```
+    ///
+    /// [`write_jumbf()`]: Self::write_jumbf()
+    /// [`set_max_size()`]: Self::set_max_size()
+    /// [`add_child_box()`]: Self::add_child_box()
+    /// [`add_child_super_box()`]: Self::add_child_super_box()
+    pub fn plan(&self) -> Result<BoxPlan> {
+        let desc_size = self.desc_size()?;
+
+        let mut children = vec![BoxPlan::new(DESCRIPTION_BOX_TYPE, desc_size)];
+        let mut payload_size = desc_size;
+
+        for child in self.ordered_children()? {
+            let padding = self.padding_before(payload_size, child.as_ref());
+            if padding > 0 {
+                children.push(BoxPlan::new(FREE_BOX_TYPE, padding));
+                payload_size = add_size(payload_size, padding)?;
+            }
+
+            let plan = match child.child.as_super_box() {
+                Some(sbox) => sbox.plan()?,
+                None => BoxPlan::new(child.as_ref().box_type(), child.jumbf_size()?),
+            };
+
+            payload_size = add_size(payload_size, plan.size)?;
+            children.push(plan);
+        }
+
+        Ok(BoxPlan {
+            tbox: SUPER_BOX_TYPE,
+            label: self.desc.label.clone(),
+            size: jumbf_size_from_payload_size(payload_size)?,
+            max_size: self.max_size,
+            children,
+        })
+    }
+
     /// Write this superbox and all of its child boxes to a JUMBF stream.
     pub fn write_jumbf(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
         write_jumbf(self, to_stream)
     }
+
+    /// Write this superbox and all of its child boxes into an existing
+    /// stream, starting at `offset`.
+    ///
+    /// If `free_space` is `None`, the superbox is written at `offset`,
+    /// overwriting whatever data was there.
+    ///
+    /// If `free_space` is `Some(len)`, the superbox must fit within the `len`
+    /// bytes starting at `offset` (typically an existing `free` box which is
+    /// being replaced). Any space left over after the superbox is filled
+    /// with a new, zero-filled `free` box so that the surrounding container
+    /// remains valid. An error is returned, and nothing is written, if the
+    /// superbox doesn't fit or if the space left over is too small (less
+    /// than 8 bytes) to hold a `free` box.
+    ///
+    /// On success, returns the size of the superbox in bytes and leaves the
+    /// stream positioned after the last byte written.
+    pub fn write_jumbf_at(
+        &self,
+        to_stream: &mut dyn WriteAndSeek,
+        offset: u64,
+        free_space: Option<u64>,
+    ) -> Result<u64> {
+        let size = jumbf_size(self)? as u64;
+
+        let padding = match free_space {
+            None => 0,
+            Some(len) => {
+                let padding = len.checked_sub(size).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("superbox of {size} bytes does not fit in {len} bytes"),
+                    )
+                })?;
+
+                if padding > 0 && padding < 8 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("{padding} bytes left after superbox is too small for a free box"),
+                    ));
+                }
+
+                if padding > u32::MAX as u64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("free box of {padding} bytes is too large (limit is 4GB)"),
+                    ));
+                }
+
+                padding
+            }
+        };
+
+        to_stream.seek(SeekFrom::Start(offset))?;
+        write_jumbf(self, to_stream)?;
+
+        if padding > 0 {
+            write_jumbf(&FreeBox(padding as usize - 8), to_stream)?;
+        }
+
+        Ok(size)
+    }
+
+    fn desc_size(&self) -> Result<usize> {
+        match self.hash_digest {
+            Some(_) => jumbf_size(&ComputedHashDescription {
+                desc: &self.desc,
+                hash: [0; 32],
+            }),
+            None => jumbf_size(&self.desc),
+        }
+    }
+
+    fn ordered_children(&self) -> Result<Vec<OrderedChild<'_, 'a>>> {
+        if self.child_order == ChildOrder::Insertion {
+            return Ok(self
+                .child_boxes
+                .iter()
+                .map(|child| OrderedChild { child, jumbf: None })
+                .collect());
+        }
+
+        let mut keyed = Vec::with_capacity(self.child_boxes.len());
+        for child in &self.child_boxes {
+            let (jumbf, positioned) = serialize_child(child)?;
+            keyed.push((jumbf, positioned, child));
+        }
+
+        // Sort key is box type followed by payload, which is the serialized
+        // box without its 4-byte size field.
+        keyed.sort_by(|a, b| a.0[4..].cmp(&b.0[4..]));
+
+        Ok(keyed
+            .into_iter()
+            .map(|(jumbf, positioned, child)| OrderedChild {
+                child,
+                jumbf: (!positioned).then_some(jumbf),
+            })
+            .collect())
+    }
+
+    // Size of this superbox's payload if `children` are written after a
+    // description box of `desc_size` bytes, including any padding.
+    fn padded_payload_size(&self, children: &[OrderedChild], desc_size: usize) -> Result<usize> {
+        let mut size = desc_size;
+        for child in children {
+            size = add_size(size, self.padding_before(size, child.as_ref()))?;
+            size = add_size(size, child.jumbf_size()?)?;
+        }
+        Ok(size)
+    }
+
+    fn check_max_size(&self, payload_size: usize) -> Result<()> {
+        if let Some(max_size) = self.max_size {
+            let total_size = jumbf_size_from_payload_size(payload_size)?;
+            if total_size > max_size {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "superbox of {total_size} bytes exceeds maximum size of {max_size} bytes"
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn computed_hash(
+        &self,
+        digest: &dyn Digest,
+        children: &[OrderedChild],
+        desc_size: usize,
+    ) -> Result<[u8; 32]> {
+        let mut payload = Cursor::new(Vec::<u8>::new());
+        self.write_children(children, desc_size, &mut payload)?;
+
+        digest
+            .digest(payload.get_ref())
+            .try_into()
+            .map_err(|hash: Vec<u8>| {
+                Error::other(format!(
+                    "computed hash must be 32 bytes, {alg} produced {len} bytes",
+                    alg = digest.algorithm(),
+                    len = hash.len()
+                ))
+            })
+    }
+}
+
+impl<'a> SuperBoxBuilder<'a> {
+    // Write child boxes, and any padding required for alignment, starting
+    // `desc_size` bytes into this superbox's payload.
+    fn write_children(
+        &self,
+        children: &[OrderedChild],
+        desc_size: usize,
+        to_stream: &mut dyn WriteAndSeek,
+    ) -> Result<()> {
+        if self.alignment.is_none() {
+            for child in children {
+                child.write_jumbf(to_stream)?;
+            }
+            return Ok(());
+        }
+
+        let mut size = desc_size;
+
+        for child in children {
+            let padding = self.padding_before(size, child.as_ref());
+            if padding > 0 {
+                write_jumbf(&FreeBox(padding - 8), to_stream)?;
+            }
+
+            child.write_jumbf(to_stream)?;
+            size += padding + child.jumbf_size()?;
+        }
+
+        Ok(())
+    }
+
+    // Number of bytes of padding needed before `child` if it is written
+    // `payload_offset` bytes into this superbox's payload.
+    fn padding_before(&self, payload_offset: usize, child: &dyn ToBox) -> usize {
+        let Some(alignment) = self.alignment else {
+            return 0;
+        };
+
+        // Superboxes are aligned at the start of the box so that their
+        // children can be aligned relative to it; other boxes are aligned at
+        // the start of their payload. Box headers are always 8 bytes here.
+        let box_offset = 8 + payload_offset;
+        let target = if child.box_type() == SUPER_BOX_TYPE {
+            box_offset
+        } else {
+            box_offset + 8
+        };
+
+        // A free box can't be shorter than its 8-byte header.
+        let mut padding = (alignment - target % alignment) % alignment;
+        while padding > 0 && padding < 8 {
+            padding += alignment;
+        }
+        padding
+    }
+}
+
+/// Creates a new, empty superbox whose UUID is all zeros.
+///
+/// This crate never chooses a UUID on your behalf, so that the output is
+/// deterministic. The all-zeros UUID is not registered to any content type;
+/// most applications should use [`SuperBoxBuilder::new()`] or
+/// [`SuperBoxBuilder::with_uuid_and_label()`] instead.
+impl<'a> Default for SuperBoxBuilder<'a> {
+    fn default() -> Self {
+        Self::new(&[0u8; 16])
+    }
 }
 
 impl<'a> ToBox for SuperBoxBuilder<'a> {
@@ -170,24 +915,99 @@ impl<'a> ToBox for SuperBoxBuilder<'a> {
         SUPER_BOX_TYPE
     }
 
+    fn payload_size_known(&self) -> bool {
+        // A size limit must be checked before anything is written.
+        self.max_size.is_some()
+            || (self.desc.payload_size_known()
+                && self
+                    .child_boxes
+                    .iter()
+                    .all(|child| child.as_ref().payload_size_known()))
+    }
+
     fn payload_size(&self) -> Result<usize> {
-        let mut size: usize = jumbf_size(&self.desc)?;
+        let desc_size = self.desc_size()?;
 
-        for child in &self.child_boxes {
-            size += jumbf_size(child.as_ref())?;
-        }
+        let size = if self.alignment.is_some() {
+            // Padding depends on the order in which children are written.
+            self.padded_payload_size(&self.ordered_children()?, desc_size)?
+        } else {
+            let mut size = desc_size;
+            for child in &self.child_boxes {
+                size = add_size(size, jumbf_size(child.as_ref())?)?;
+            }
+            size
+        };
 
+        self.check_max_size(size)?;
         Ok(size)
     }
 
     fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
-        write_jumbf(&self.desc, to_stream)?;
+        let children = self.ordered_children()?;
+        let desc_size = self.desc_size()?;
 
-        for child in &self.child_boxes {
-            write_jumbf(child.as_ref(), to_stream)?;
+        if self.max_size.is_some() {
+            // A parent superbox with canonical child order writes this
+            // superbox without asking for its size first.
+            self.check_max_size(self.padded_payload_size(&children, desc_size)?)?;
         }
 
-        Ok(())
+        if let Some(digest) = self.hash_digest.as_ref() {
+            let hash = self.computed_hash(digest.as_ref(), &children, desc_size)?;
+            write_jumbf(
+                &ComputedHashDescription {
+                    desc: &self.desc,
+                    hash,
+                },
+                to_stream,
+            )?;
+        } else {
+            write_jumbf(&self.desc, to_stream)?;
+        }
+
+        self.write_children(&children, desc_size, to_stream)
+    }
+}
+
+/// Describes the size of a box which will be written by a
+/// [`SuperBoxBuilder`], as returned by [`SuperBoxBuilder::plan()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoxPlan {
+    /// Box type.
+    pub tbox: BoxType,
+
+    /// Label of this superbox, if it is a superbox with a label.
+    pub label: Option<String>,
+
+    /// Size of the entire box, including its header.
+    pub size: usize,
+
+    /// Maximum size of this box, if one was set via
+    /// [`SuperBoxBuilder::set_max_size()`].
+    pub max_size: Option<usize>,
+
+    /// Child boxes, if this is a superbox which was broken down. The
+    /// description box is included.
+    pub children: Vec<BoxPlan>,
+}
+
+impl BoxPlan {
+    fn new(tbox: BoxType, size: usize) -> Self {
+        Self {
+            tbox,
+            label: None,
+            size,
+            max_size: None,
+            children: vec![],
+        }
+    }
+
+    /// Returns `true` if this box, or any box within it, is larger than
+    /// its maximum size.
+    pub fn exceeds_max_size(&self) -> bool {
+        self.max_size.is_some_and(|max_size| self.size > max_size)
+            || self.children.iter().any(BoxPlan::exceeds_max_size)
     }
 }
 
@@ -199,29 +1019,29 @@ impl<'a> ToBox for SuperBoxBuilder<'a> {
 /// description box are available as part of `SuperBoxBuilder`. The description
 /// box is generated automatically when `SuperBoxBuilder.write_jumbf()` is
 /// called.
-struct DescriptionBoxBuilder {
+pub(crate) struct DescriptionBoxBuilder {
     /// Application-specific UUID for the superbox's data type.
-    uuid: [u8; 16],
+    pub(crate) uuid: [u8; 16],
 
     /// Application-specific label for the superbox.
-    label: Option<String>,
+    pub(crate) label: Option<String>,
 
     /// True if the superbox containing this description box can
     /// be requested.
-    requestable: bool,
+    pub(crate) requestable: bool,
 
     /// Application-specific 32-bit ID.
-    id: Option<u32>,
+    pub(crate) id: Option<u32>,
 
     /// SHA-256 hash of the superbox's data payload.
-    hash: Option<[u8; 32]>,
+    pub(crate) hash: Option<[u8; 32]>,
 
     /// Application-specific "private" box within description box.
-    private: Option<Box<dyn ToBox>>,
+    pub(crate) private: Option<Box<dyn ToBox>>,
 }
 
 impl DescriptionBoxBuilder {
-    fn new(uuid: &[u8; 16]) -> Self {
+    pub(crate) fn new(uuid: &[u8; 16]) -> Self {
         Self {
             uuid: *uuid,
             label: None,
@@ -238,59 +1058,59 @@ impl ToBox for DescriptionBoxBuilder {
         DESCRIPTION_BOX_TYPE
     }
 
-    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
-        use crate::toggles;
-
-        to_stream.write_all(&self.uuid)?;
-
-        // Calculate toggles byte.
-        let mut toggles = 0u8;
-
-        // Toggle bit 0 (0x01) indicates that this superbox can be requested
-        // via URI requests.
-        if self.requestable {
-            toggles |= toggles::REQUESTABLE;
-        }
-
-        // Toggle bit 1 (0x02) indicates that the label has an optional textual label.
-        if self.label.is_some() {
-            toggles |= toggles::HAS_LABEL;
-        }
+    fn payload_size_known(&self) -> bool {
+        self.private
+            .as_ref()
+            .map_or(true, |private| private.payload_size_known())
+    }
 
-        // Toggle bit 2 (0x04) indicates that the label has an optional
-        // application-specific 32-bit identifier.
-        if self.id.is_some() {
-            toggles |= toggles::HAS_ID;
-        }
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        self.write_payload_with_hash(self.hash.as_ref(), to_stream)
+    }
+}
 
-        // Toggle bit 3 (0x08) indicates that a SHA-256 hash of the superbox's
-        // data box is present.
-        if self.hash.is_some() {
-            toggles |= toggles::HAS_HASH;
-        }
+impl DescriptionBoxBuilder {
+    fn toggles(&self, has_hash: bool) -> Toggles {
+        let mut toggles = Toggles::empty();
+        toggles.set(Toggles::REQUESTABLE, self.requestable);
+        toggles.set(Toggles::HAS_LABEL, self.label.is_some());
+        toggles.set(Toggles::HAS_ID, self.id.is_some());
+        toggles.set(Toggles::HAS_HASH, has_hash);
+        toggles.set(Toggles::HAS_PRIVATE_BOX, self.private.is_some());
+        toggles
+    }
 
-        // Toggle bit 4 (0x10) indicates that an application-specific "private"
-        // box is contained within the description box.
-        if self.private.is_some() {
-            toggles |= toggles::HAS_PRIVATE_BOX;
-        }
+    fn write_payload_with_hash(
+        &self,
+        hash: Option<&[u8; 32]>,
+        to_stream: &mut dyn WriteAndSeek,
+    ) -> Result<()> {
+        // Gather the fixed fields so they can be written in one call.
+        let fields_len = 16 // UUID
+            + 1 // toggles
+            + self.label.as_ref().map_or(0, |label| label.len() + 1)
+            + self.id.map_or(0, |_| 4)
+            + hash.map_or(0, |_| 32);
+        let mut fields: Vec<u8> = Vec::with_capacity(fields_len);
 
-        let toggles_slice = [toggles];
-        to_stream.write_all(&toggles_slice)?;
+        fields.extend_from_slice(&self.uuid);
+        fields.push(self.toggles(hash.is_some()).bits());
 
         if let Some(label) = self.label.as_ref() {
-            to_stream.write_all(label.as_bytes())?;
-            to_stream.write_all(&[0u8])?;
+            fields.extend_from_slice(label.as_bytes());
+            fields.push(0);
         }
 
         if let Some(id) = self.id {
-            write_be_u32(to_stream, id)?;
+            fields.extend_from_slice(&id.to_be_bytes());
         }
 
-        if let Some(hash) = self.hash {
-            to_stream.write_all(&hash)?;
+        if let Some(hash) = hash {
+            fields.extend_from_slice(hash);
         }
 
+        to_stream.write_all(&fields)?;
+
         if let Some(private) = self.private.as_ref() {
             write_jumbf(private.as_ref(), to_stream)?;
         }
@@ -299,6 +1119,27 @@ impl ToBox for DescriptionBoxBuilder {
     }
 }
 
+// Description box whose hash was computed by `SuperBoxBuilder` at write time.
+struct ComputedHashDescription<'d> {
+    desc: &'d DescriptionBoxBuilder,
+    hash: [u8; 32],
+}
+
+impl<'d> ToBox for ComputedHashDescription<'d> {
+    fn box_type(&self) -> BoxType {
+        DESCRIPTION_BOX_TYPE
+    }
+
+    fn payload_size_known(&self) -> bool {
+        self.desc.payload_size_known()
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        self.desc
+            .write_payload_with_hash(Some(&self.hash), to_stream)
+    }
+}
+
 // DESIGN NOTE: This looks a lot like (and was inspired by) the built-in
 // `Cow` type, but is distinct for a couple of reasons:
 //
@@ -307,21 +1148,149 @@ impl ToBox for DescriptionBoxBuilder {
 // 2. In this particular use case, we never need to convert between referenced
 //    and owned data. This allows us to use this simpler implementation.
 enum OwnedOrBorrowedBox<'a> {
-    OwnedBox(Box<dyn ToBox>),
+    OwnedBox(Box<dyn OwnedToBox>),
     BorrowedBox(&'a dyn ToBox),
+
+    // Owned, but may borrow data from elsewhere, so can't be `Any`.
+    Boxed(Box<dyn ToBox + 'a>),
 }
 
 impl<'a> OwnedOrBorrowedBox<'a> {
     fn as_ref(&self) -> &dyn ToBox {
         match self {
-            OwnedOrBorrowedBox::OwnedBox(boxx) => boxx.as_ref(),
+            OwnedOrBorrowedBox::OwnedBox(boxx) => (**boxx).as_to_box(),
             OwnedOrBorrowedBox::BorrowedBox(boxx) => *boxx,
+            OwnedOrBorrowedBox::Boxed(boxx) => boxx.as_ref(),
+        }
+    }
+
+    fn as_super_box(&self) -> Option<&SuperBoxBuilder<'static>> {
+        match self {
+            OwnedOrBorrowedBox::OwnedBox(boxx) => (**boxx).as_any().downcast_ref(),
+            OwnedOrBorrowedBox::BorrowedBox(_) | OwnedOrBorrowedBox::Boxed(_) => None,
+        }
+    }
+
+    fn as_super_box_mut(&mut self) -> Option<&mut SuperBoxBuilder<'static>> {
+        match self {
+            OwnedOrBorrowedBox::OwnedBox(boxx) => (**boxx).as_any_mut().downcast_mut(),
+            OwnedOrBorrowedBox::BorrowedBox(_) | OwnedOrBorrowedBox::Boxed(_) => None,
+        }
+    }
+}
+
+// Owned child boxes are stored as `Any` so that nested `SuperBoxBuilder`s
+// can be found again after they have been added.
+//
+// Note that `Box<dyn OwnedToBox>` is itself an `OwnedToBox` (since `Box`
+// implements `ToBox`), so these methods must be called on the boxed value
+// rather than on the `Box`.
+trait OwnedToBox: ToBox {
+    fn as_to_box(&self) -> &dyn ToBox;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: ToBox + 'static> OwnedToBox for T {
+    fn as_to_box(&self) -> &dyn ToBox {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// A child box in the order in which it will be written.
+struct OrderedChild<'c, 'a> {
+    child: &'c OwnedOrBorrowedBox<'a>,
+
+    // The serialized box, if it was serialized to determine the order and
+    // can be copied to the stream as is.
+    jumbf: Option<Vec<u8>>,
+}
+
+impl<'c, 'a> OrderedChild<'c, 'a> {
+    fn as_ref(&self) -> &dyn ToBox {
+        self.child.as_ref()
+    }
+
+    fn jumbf_size(&self) -> Result<usize> {
+        match &self.jumbf {
+            Some(jumbf) => Ok(jumbf.len()),
+            None => jumbf_size(self.as_ref()),
         }
     }
+
+    fn write_jumbf(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        match &self.jumbf {
+            Some(jumbf) => to_stream.write_all(jumbf),
+            None => write_jumbf(self.as_ref(), to_stream),
+        }
+    }
+}
+
+// Serialize `child` into memory, generating its payload only once. Also
+// returns `true` if the child asked for or changed its position in the
+// stream while writing its payload, in which case the bytes are only good
+// for sorting.
+fn serialize_child(child: &OwnedOrBorrowedBox) -> Result<(Vec<u8>, bool)> {
+    let child = child.as_ref();
+
+    let mut stream = PositionTracker::default();
+    let mut header = [0u8; 8];
+    header[4..8].copy_from_slice(&child.box_type().0);
+    stream.jumbf.write_all(&header)?;
+
+    child.write_payload(&mut stream)?;
+    patch_size(&mut stream.jumbf, 0)?;
+
+    Ok((stream.jumbf.into_inner(), stream.positioned))
+}
+
+// An in-memory stream which notes whether it was ever asked to seek.
+// (`stream_position()` is implemented in terms of `seek()`.)
+#[derive(Default)]
+struct PositionTracker {
+    jumbf: Cursor<Vec<u8>>,
+    positioned: bool,
+}
+
+impl Write for PositionTracker {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.jumbf.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.jumbf.flush()
+    }
 }
 
-fn write_be_u32(to_stream: &mut dyn WriteAndSeek, v: u32) -> Result<()> {
-    // Q&D implementation of big-endian formatting.
-    let v_slice: [u8; 4] = [(v >> 24) as u8, (v >> 16) as u8, (v >> 8) as u8, v as u8];
-    to_stream.write_all(&v_slice)
+impl Seek for PositionTracker {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.positioned = true;
+        self.jumbf.seek(pos)
+    }
+}
+
+// A zero-filled `free` box, used for alignment padding.
+struct FreeBox(usize);
+
+impl ToBox for FreeBox {
+    fn box_type(&self) -> BoxType {
+        FREE_BOX_TYPE
+    }
+
+    fn payload_size(&self) -> Result<usize> {
+        Ok(self.0)
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        io::copy(&mut io::repeat(0).take(self.0 as u64), to_stream)?;
+        Ok(())
+    }
 }
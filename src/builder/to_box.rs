@@ -11,7 +11,10 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::io::{Error, ErrorKind, Result, Seek, SeekFrom, Write};
+use std::{
+    io::{Error, ErrorKind, IoSlice, Result, Seek, SeekFrom, Write},
+    ops::Range,
+};
 
 use crate::BoxType;
 
@@ -19,6 +22,54 @@ const MAX_32BIT_PAYLOAD_SIZE: usize = 0xfffffff7;
 
 /// The `ToBox` trait allows any data type to generate a JUMBF data box.
 ///
+/// ## Custom box types
+///
+/// Implementing `ToBox` is the way to add your own kinds of boxes to a
+/// JUMBF data structure. The trait is object-safe, so your implementations
+/// can be mixed freely with the built-in ones (such as [`DataBoxBuilder`]
+/// and [`SuperBoxBuilder`]) as `Box<dyn ToBox>` values. Boxed values may be
+/// added to a superbox using [`add_child_box()`] or, if they borrow data,
+/// [`add_boxed_child_box()`].
+///
+/// ```
+/// use std::io::Result;
+///
+/// use jumbf::{
+///     builder::{DataBoxBuilder, SuperBoxBuilder, ToBox, WriteAndSeek},
+///     BoxType,
+/// };
+///
+/// // Writes a payload of `self.0` zero bytes.
+/// struct Zeros(usize);
+///
+/// impl ToBox for Zeros {
+///     fn box_type(&self) -> BoxType {
+///         BoxType(*b"zero")
+///     }
+///
+///     fn payload_size(&self) -> Result<usize> {
+///         Ok(self.0)
+///     }
+///
+///     fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+///         to_stream.write_all(&vec![0; self.0])
+///     }
+/// }
+///
+/// let children: Vec<Box<dyn ToBox>> = vec![
+///     Box::new(Zeros(4)),
+///     Box::new(DataBoxBuilder::from_owned(
+///         BoxType(*b"json"),
+///         b"{}".to_vec(),
+///     )),
+/// ];
+///
+/// let mut sbox = SuperBoxBuilder::new(&[0u8; 16]);
+/// for child in children {
+///     sbox = sbox.add_child_box(child);
+/// }
+/// ```
+///
 /// ## Design constraints
 ///
 /// Any stream presented here must implement the standard [`Write`] and [`Seek`]
@@ -28,6 +79,10 @@ const MAX_32BIT_PAYLOAD_SIZE: usize = 0xfffffff7;
 ///
 /// [`box_type()`]: Self::box_type()
 /// [`write_payload()`]: Self::write_payload()
+/// [`DataBoxBuilder`]: crate::builder::DataBoxBuilder
+/// [`SuperBoxBuilder`]: crate::builder::SuperBoxBuilder
+/// [`add_child_box()`]: crate::builder::SuperBoxBuilder::add_child_box
+/// [`add_boxed_child_box()`]: crate::builder::SuperBoxBuilder::add_boxed_child_box
 /// [`PlaceholderDataBox`]: crate::builder::PlaceholderDataBox
 pub trait ToBox {
     /// Specifies the type of information which will be provided by the
@@ -68,6 +123,31 @@ pub trait ToBox {
         Ok(counting_sink.count)
     }
 
+    /// Returns `true` if the size of the payload is known before it is
+    /// written.
+    ///
+    /// A `ToBox` implementation which generates its payload on the fly (for
+    /// example, by compressing or streaming data from elsewhere) may return
+    /// `false` here. When such a box is written, a placeholder box header is
+    /// written first and the size is filled in after [`write_payload()`]
+    /// returns, so the payload is generated only once. The same applies to
+    /// any superbox which contains such a box.
+    ///
+    /// [`payload_size()`] may still be called in some cases (for example,
+    /// when the box is nested within a superbox that uses
+    /// [`set_alignment()`] or [`set_max_size()`]), so it must continue to
+    /// return the correct size.
+    ///
+    /// The default implementation returns `true`.
+    ///
+    /// [`write_payload()`]: Self::write_payload()
+    /// [`payload_size()`]: Self::payload_size()
+    /// [`set_alignment()`]: crate::builder::SuperBoxBuilder::set_alignment
+    /// [`set_max_size()`]: crate::builder::SuperBoxBuilder::set_max_size
+    fn payload_size_known(&self) -> bool {
+        true
+    }
+
     /// Write the payload for this box to the JUMBF stream.
     ///
     /// The payload must be exactly the size previously specified by
@@ -77,48 +157,125 @@ pub trait ToBox {
     fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()>;
 }
 
+impl<T: ToBox + ?Sized> ToBox for Box<T> {
+    fn box_type(&self) -> BoxType {
+        (**self).box_type()
+    }
+
+    fn payload_size(&self) -> Result<usize> {
+        (**self).payload_size()
+    }
+
+    fn payload_size_known(&self) -> bool {
+        (**self).payload_size_known()
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        (**self).write_payload(to_stream)
+    }
+}
+
 pub(crate) fn jumbf_size(boxx: &dyn ToBox) -> Result<usize> {
-    Ok(jumbf_size_from_payload_size(boxx.payload_size()?))
+    jumbf_size_from_payload_size(boxx.payload_size()?)
 }
 
 pub(crate) fn write_jumbf(boxx: &dyn ToBox, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
-    let payload_size = boxx.payload_size()?;
-    let jumbf_size = jumbf_size_from_payload_size(payload_size);
+    if !boxx.payload_size_known() {
+        let start = to_stream.stream_position()?;
 
-    if payload_size <= MAX_32BIT_PAYLOAD_SIZE {
-        let size_slice: [u8; 4] = [
-            (jumbf_size >> 24) as u8,
-            (jumbf_size >> 16) as u8,
-            (jumbf_size >> 8) as u8,
-            jumbf_size as u8,
-        ];
-        to_stream.write_all(&size_slice)?;
-    } else {
-        // TO DO: Support for >4GB payloads.
-        unimplemented!();
+        let mut header = [0u8; 8];
+        header[4..8].copy_from_slice(&boxx.box_type().0);
+        to_stream.write_all(&header)?;
+
+        boxx.write_payload(to_stream)?;
+        patch_size(to_stream, start)?;
+        return Ok(());
     }
 
-    // TO DO: Check stream position and verify that exactly the
-    // specified number of bytes was written.
+    let payload_size = boxx.payload_size()?;
+    let jumbf_size = jumbf_size_from_payload_size(payload_size)?;
+
+    let mut header = [0u8; 8];
+    header[0..4].copy_from_slice(&(jumbf_size as u32).to_be_bytes());
+    header[4..8].copy_from_slice(&boxx.box_type().0);
+    to_stream.write_all(&header)?;
+
+    boxx.write_payload(to_stream)
+}
+
+// Fill in the size of a box which starts at `start` and ends at the
+// current position of `to_stream`, leaving the stream positioned at the
+// end of the box. Returns the range occupied by the box.
+pub(crate) fn patch_size(to_stream: &mut dyn WriteAndSeek, start: u64) -> Result<Range<u64>> {
+    let end = to_stream.stream_position()?;
+    let len = end - start;
 
-    let box_type = boxx.box_type();
-    to_stream.write_all(&box_type.0)?;
+    let size = u32::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("box of {len} bytes is too large (limit is 4GB)"),
+        )
+    })?;
 
-    boxx.write_payload(to_stream)?;
+    to_stream.seek(SeekFrom::Start(start))?;
+    to_stream.write_all(&size.to_be_bytes())?;
+    to_stream.seek(SeekFrom::Start(end))?;
+
+    Ok(start..end)
+}
+
+// Write all of `bufs` to `to_stream`, using vectored writes where the
+// stream supports them.
+pub(crate) fn write_all_vectored(to_stream: &mut dyn WriteAndSeek, bufs: &[&[u8]]) -> Result<()> {
+    let mut bufs: Vec<&[u8]> = bufs.iter().copied().filter(|b| !b.is_empty()).collect();
+    let mut start = 0;
+
+    while start < bufs.len() {
+        let slices: Vec<IoSlice> = bufs[start..].iter().map(|b| IoSlice::new(b)).collect();
+
+        let mut written = match to_stream.write_vectored(&slices) {
+            Ok(0) => {
+                return Err(Error::new(
+                    ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ));
+            }
+            Ok(n) => n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        while start < bufs.len() && written >= bufs[start].len() {
+            written -= bufs[start].len();
+            start += 1;
+        }
+
+        if written > 0 {
+            bufs[start] = &bufs[start][written..];
+        }
+    }
 
     Ok(())
 }
 
-fn jumbf_size_from_payload_size(payload_size: usize) -> usize {
+pub(crate) fn jumbf_size_from_payload_size(payload_size: usize) -> Result<usize> {
     if payload_size <= MAX_32BIT_PAYLOAD_SIZE {
-        payload_size + 8
+        Ok(payload_size + 8)
     } else {
         // TO DO: Support for >4GB payloads.
-        unimplemented!();
-        // payload_size + 16
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("box payload of {payload_size} bytes is too large (limit is 4GB)"),
+        ))
     }
 }
 
+// Add box sizes, returning an error rather than wrapping on overflow.
+pub(crate) fn add_size(a: usize, b: usize) -> Result<usize> {
+    a.checked_add(b)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "JUMBF box size overflow"))
+}
+
 /// A stream that implements [`Write`] and [`Seek`] traits.
 ///
 /// Required for [`ToBox`].
@@ -133,8 +290,7 @@ struct CountingSink {
 impl Seek for CountingSink {
     fn seek(&mut self, _pos: SeekFrom) -> Result<u64> {
         // Shouldn't need to seek while counting payload size.
-        Err(Error::new(
-            ErrorKind::Other,
+        Err(Error::other(
             "shouldn't need to seek while calculating payload size",
         ))
     }
@@ -143,7 +299,7 @@ impl Seek for CountingSink {
 impl Write for CountingSink {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         let len = buf.len();
-        self.count += len;
+        self.count = add_size(self.count, len)?;
         Ok(len)
     }
 
@@ -0,0 +1,165 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Well-known JUMBF content types.
+//!
+//! The UUID in a JUMBF description box identifies the type of content
+//! carried by its superbox. This module lists the content types defined by
+//! the JUMBF standard (ISO/IEC 19566-5) and by the C2PA technical
+//! specification, and can look up a content type by its UUID.
+//!
+//! ## Example
+//!
+//! ```
+//! use jumbf::content_type::{self, ContentType};
+//!
+//! let ct = ContentType::from_uuid(&content_type::JSON.uuid).unwrap();
+//! assert_eq!(ct.name, "json");
+//!
+//! assert_eq!(ContentType::from_uuid(&[0; 16]), None);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+/// A known JUMBF content type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ContentType {
+    /// UUID used in the description box of superboxes of this type.
+    pub uuid: [u8; 16],
+
+    /// Short, human-readable name for this content type (i.e. `"json"`).
+    pub name: &'static str,
+}
+
+impl ContentType {
+    /// Find the known content type, if any, with the given UUID.
+    pub fn from_uuid(uuid: &[u8; 16]) -> Option<&'static ContentType> {
+        KNOWN_CONTENT_TYPES.iter().find(|ct| ct.uuid == *uuid)
+    }
+}
+
+impl Display for ContentType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+// Many content types are derived from a four-character code using the
+// ISO base UUID (`xxxxxxxx-0011-0010-8000-00AA00389B71`).
+const fn iso_uuid(fourcc: &[u8; 4]) -> [u8; 16] {
+    [
+        fourcc[0], fourcc[1], fourcc[2], fourcc[3], 0x00, 0x11, 0x00, 0x10, 0x80, 0x00, 0x00, 0xaa,
+        0x00, 0x38, 0x9b, 0x71,
+    ]
+}
+
+/// Contiguous codestream content type (ISO/IEC 19566-5).
+pub const CODESTREAM: ContentType = ContentType {
+    uuid: [
+        0x65, 0x79, 0xd6, 0xfb, 0xdb, 0xa2, 0x44, 0x6b, 0xb2, 0xac, 0x1b, 0x82, 0xfe, 0xeb, 0x89,
+        0xd1,
+    ],
+    name: "codestream",
+};
+
+/// XML content type (ISO/IEC 19566-5).
+pub const XML: ContentType = ContentType {
+    uuid: iso_uuid(b"xml "),
+    name: "xml",
+};
+
+/// JSON content type (ISO/IEC 19566-5).
+pub const JSON: ContentType = ContentType {
+    uuid: iso_uuid(b"json"),
+    name: "json",
+};
+
+/// UUID content type (ISO/IEC 19566-5).
+pub const UUID: ContentType = ContentType {
+    uuid: iso_uuid(b"uuid"),
+    name: "uuid",
+};
+
+/// Embedded file content type (ISO/IEC 19566-5).
+pub const EMBEDDED_FILE: ContentType = ContentType {
+    uuid: [
+        0x40, 0xcb, 0x0c, 0x32, 0xbb, 0x8a, 0x48, 0x9d, 0xa7, 0x0b, 0x2a, 0xd6, 0xf4, 0x7f, 0x43,
+        0x69,
+    ],
+    name: "embedded file",
+};
+
+/// CBOR content type (ISO/IEC 19566-5).
+pub const CBOR: ContentType = ContentType {
+    uuid: iso_uuid(b"cbor"),
+    name: "cbor",
+};
+
+/// C2PA manifest store.
+pub const C2PA_MANIFEST_STORE: ContentType = ContentType {
+    uuid: iso_uuid(b"c2pa"),
+    name: "c2pa manifest store",
+};
+
+/// C2PA standard manifest.
+pub const C2PA_MANIFEST: ContentType = ContentType {
+    uuid: iso_uuid(b"c2ma"),
+    name: "c2pa manifest",
+};
+
+/// C2PA update manifest.
+pub const C2PA_UPDATE_MANIFEST: ContentType = ContentType {
+    uuid: iso_uuid(b"c2um"),
+    name: "c2pa update manifest",
+};
+
+/// C2PA assertion store.
+pub const C2PA_ASSERTION_STORE: ContentType = ContentType {
+    uuid: iso_uuid(b"c2as"),
+    name: "c2pa assertion store",
+};
+
+/// C2PA claim.
+pub const C2PA_CLAIM: ContentType = ContentType {
+    uuid: iso_uuid(b"c2cl"),
+    name: "c2pa claim",
+};
+
+/// C2PA claim signature.
+pub const C2PA_CLAIM_SIGNATURE: ContentType = ContentType {
+    uuid: iso_uuid(b"c2cs"),
+    name: "c2pa claim signature",
+};
+
+/// C2PA verifiable credential store.
+pub const C2PA_CREDENTIAL_STORE: ContentType = ContentType {
+    uuid: iso_uuid(b"c2vc"),
+    name: "c2pa credential store",
+};
+
+/// All content types known to this crate.
+pub const KNOWN_CONTENT_TYPES: &[ContentType] = &[
+    CODESTREAM,
+    XML,
+    JSON,
+    UUID,
+    EMBEDDED_FILE,
+    CBOR,
+    C2PA_MANIFEST_STORE,
+    C2PA_MANIFEST,
+    C2PA_UPDATE_MANIFEST,
+    C2PA_ASSERTION_STORE,
+    C2PA_CLAIM,
+    C2PA_CLAIM_SIGNATURE,
+    C2PA_CREDENTIAL_STORE,
+];
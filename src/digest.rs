@@ -0,0 +1,60 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Pluggable message digest algorithms.
+//!
+//! This crate does not perform any cryptography itself. Wherever a digest
+//! is needed (verifying description box hashes, generating hashed URIs,
+//! and computing hashes in [`SuperBoxBuilder`]), it calls an implementation
+//! of the [`Digest`] trait supplied by the application. This allows
+//! environments with their own cryptography providers (for example, FIPS
+//! modules or hardware) to use them.
+//!
+//! When the `sha2` crate feature is enabled, a [`Sha256`] implementation
+//! based on the [`sha2`] crate is also available.
+//!
+//! [`SuperBoxBuilder`]: crate::builder::SuperBoxBuilder
+//! [`sha2`]: https://docs.rs/sha2
+
+/// A message digest algorithm.
+pub trait Digest {
+    /// Name of the algorithm (i.e. `"sha256"`).
+    ///
+    /// For C2PA usage, this should be one of the algorithm identifiers
+    /// defined by the C2PA specification.
+    fn algorithm(&self) -> &str;
+
+    /// Compute the digest of `data`.
+    fn digest(&self, data: &[u8]) -> Vec<u8>;
+}
+
+/// The SHA-256 digest algorithm as implemented by the [`sha2`] crate.
+///
+/// This type is available when the `sha2` crate feature is enabled.
+///
+/// [`sha2`]: https://docs.rs/sha2
+#[cfg(feature = "sha2")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Sha256;
+
+#[cfg(feature = "sha2")]
+impl Digest for Sha256 {
+    fn algorithm(&self) -> &str {
+        "sha256"
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        use sha2::Digest as _;
+        sha2::Sha256::digest(data).to_vec()
+    }
+}
@@ -25,10 +25,26 @@ pub use box_type::BoxType;
 
 pub mod builder;
 
+pub mod content_type;
+
 #[cfg(feature = "parser")]
 mod debug;
 
+pub mod digest;
+
+pub mod model;
+
 #[cfg(feature = "parser")]
 pub mod parser;
 
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+#[cfg(feature = "test-utils")]
+pub mod testkit;
+
 mod toggles;
+pub use toggles::{ParseTogglesError, Toggles};
@@ -0,0 +1,321 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! A simple owned data model for JUMBF data structures.
+//!
+//! Unlike the types in the [`parser`] module, these types own their data
+//! and have no lifetime parameters. They can be serialized to JUMBF via
+//! the [`ToBox`] trait.
+//!
+//! When the `parser` feature is enabled, each type can be created from the
+//! corresponding parser type using [`From`], and [`BoxTree::from_slice()`]
+//! parses an entire sequence of top-level boxes.
+//!
+//! [`parser`]: crate::parser
+//! [`ToBox`]: crate::builder::ToBox
+
+use std::io::{Cursor, Result};
+
+use crate::{
+    box_type::SUPER_BOX_TYPE,
+    builder::{
+        super_box_builder::DescriptionBoxBuilder,
+        to_box::{add_size, jumbf_size, write_jumbf},
+        DataBoxBuilder, ToBox, WriteAndSeek,
+    },
+    BoxType,
+};
+
+/// Serialize a superbox model and all of its descendants to a new byte
+/// vector.
+///
+/// ## Example
+///
+/// ```
+/// use jumbf::model::{to_jumbf, SuperBoxModel};
+///
+/// let jumbf = to_jumbf(&SuperBoxModel::default()).unwrap();
+/// assert_eq!(jumbf.len(), 33);
+/// ```
+pub fn to_jumbf(sbox: &SuperBoxModel) -> Result<Vec<u8>> {
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf)?;
+    Ok(jumbf.into_inner())
+}
+
+/// An owned representation of a sequence of top-level JUMBF boxes, such as
+/// the contents of a standalone JUMBF file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BoxTree {
+    /// Top-level boxes, in order.
+    pub boxes: Vec<ChildBoxModel>,
+}
+
+impl BoxTree {
+    /// Write each top-level box, in order, to a JUMBF stream.
+    pub fn write_jumbf(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        for boxx in &self.boxes {
+            write_jumbf(boxx.as_to_box(), to_stream)?;
+        }
+        Ok(())
+    }
+}
+
+/// An owned representation of a JUMBF superbox.
+///
+/// The fields of the description box are stored directly in this struct.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SuperBoxModel {
+    /// Application-specific UUID for the superbox's data type.
+    pub uuid: [u8; 16],
+
+    /// Application-specific label for the superbox.
+    pub label: Option<String>,
+
+    /// True if the superbox can be requested via
+    /// [`SuperBox::find_by_label()`].
+    ///
+    /// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+    pub requestable: bool,
+
+    /// Application-specific 32-bit ID.
+    pub id: Option<u32>,
+
+    /// SHA-256 hash of the superbox's data payload.
+    pub hash: Option<[u8; 32]>,
+
+    /// Application-specific "private" box within description box.
+    pub private: Option<DataBoxModel>,
+
+    /// Child boxes.
+    pub child_boxes: Vec<ChildBoxModel>,
+}
+
+impl SuperBoxModel {
+    /// Write this superbox and all of its child boxes to a JUMBF stream.
+    pub fn write_jumbf(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        write_jumbf(self, to_stream)
+    }
+
+    pub(crate) fn desc(&self) -> DescriptionBoxBuilder {
+        let mut desc = DescriptionBoxBuilder::new(&self.uuid);
+        desc.label.clone_from(&self.label);
+        desc.requestable = self.requestable;
+        desc.id = self.id;
+        desc.hash = self.hash;
+        desc.private = self
+            .private
+            .as_ref()
+            .map(|p| Box::new(p.clone()) as Box<dyn ToBox>);
+        desc
+    }
+}
+
+impl ToBox for SuperBoxModel {
+    fn box_type(&self) -> BoxType {
+        SUPER_BOX_TYPE
+    }
+
+    fn payload_size(&self) -> Result<usize> {
+        let mut size: usize = jumbf_size(&self.desc())?;
+
+        for child in &self.child_boxes {
+            size = add_size(size, jumbf_size(child.as_to_box())?)?;
+        }
+
+        Ok(size)
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        write_jumbf(&self.desc(), to_stream)?;
+
+        for child in &self.child_boxes {
+            write_jumbf(child.as_to_box(), to_stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An owned representation of a single box within a [`SuperBoxModel`],
+/// which may itself be a superbox or a regular box.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChildBoxModel {
+    /// A superbox.
+    SuperBox(SuperBoxModel),
+
+    /// Any other kind of box.
+    DataBox(DataBoxModel),
+}
+
+impl ChildBoxModel {
+    pub(crate) fn as_to_box(&self) -> &dyn ToBox {
+        match self {
+            Self::SuperBox(sbox) => sbox,
+            Self::DataBox(dbox) => dbox,
+        }
+    }
+}
+
+/// An owned representation of a JUMBF data box.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataBoxModel {
+    /// Box type.
+    pub tbox: BoxType,
+
+    /// Box contents.
+    pub data: Vec<u8>,
+}
+
+impl ToBox for DataBoxModel {
+    fn box_type(&self) -> BoxType {
+        self.tbox
+    }
+
+    fn payload_size(&self) -> Result<usize> {
+        Ok(self.data.len())
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        to_stream.write_all(&self.data)
+    }
+}
+
+impl From<DataBoxModel> for DataBoxBuilder<'static> {
+    fn from(dbox: DataBoxModel) -> Self {
+        DataBoxBuilder::from_owned(dbox.tbox, dbox.data)
+    }
+}
+
+impl<'a> From<&'a DataBoxModel> for DataBoxBuilder<'a> {
+    fn from(dbox: &'a DataBoxModel) -> Self {
+        DataBoxBuilder::from_borrowed(dbox.tbox, &dbox.data)
+    }
+}
+
+#[cfg(feature = "parser")]
+mod parser_impls {
+    use super::{BoxTree, ChildBoxModel, DataBoxModel, SuperBoxModel};
+    use crate::parser::{boxes_from_slice, ChildBox, DataBox, Error, SuperBox};
+
+    impl BoxTree {
+        /// Parse a sequence of top-level JUMBF boxes.
+        ///
+        /// Boxes of type `jumb` are parsed as superboxes. All other boxes are
+        /// kept as data boxes. The entire input must consist of boxes.
+        pub fn from_slice(i: &[u8]) -> Result<Self, Error> {
+            Ok(Self {
+                boxes: boxes_from_slice(i)?.iter().map(Into::into).collect(),
+            })
+        }
+    }
+
+    impl From<&SuperBox<'_>> for SuperBoxModel {
+        fn from(sbox: &SuperBox<'_>) -> Self {
+            Self {
+                uuid: *sbox.desc.uuid,
+                label: sbox.desc.label.map(|l| l.to_owned()),
+                requestable: sbox.desc.requestable,
+                id: sbox.desc.id,
+                hash: sbox.desc.hash.copied(),
+                private: sbox.desc.private.as_ref().map(DataBoxModel::from),
+                child_boxes: sbox.child_boxes.iter().map(ChildBoxModel::from).collect(),
+            }
+        }
+    }
+
+    impl From<&ChildBox<'_>> for ChildBoxModel {
+        fn from(child: &ChildBox<'_>) -> Self {
+            match child {
+                ChildBox::SuperBox(sbox) => Self::SuperBox(sbox.into()),
+                ChildBox::DataBox(dbox) => Self::DataBox(dbox.into()),
+            }
+        }
+    }
+
+    impl From<&DataBox<'_>> for DataBoxModel {
+        fn from(dbox: &DataBox<'_>) -> Self {
+            Self {
+                tbox: dbox.tbox,
+                data: dbox.data.to_vec(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use super::{ChildBoxModel, DataBoxModel, SuperBoxModel};
+    use crate::{
+        box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+        BoxType,
+    };
+
+    // Limits the size of generated trees so that fuzzers spend their time
+    // on interesting structures rather than enormous ones.
+    const MAX_DEPTH: usize = 4;
+    const MAX_CHILDREN: usize = 4;
+
+    impl<'a> Arbitrary<'a> for SuperBoxModel {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            arbitrary_super_box(u, 0)
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for DataBoxModel {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            // Avoid generating data boxes that the parser would interpret
+            // as superboxes or description boxes.
+            let mut tbox = BoxType(u.arbitrary()?);
+            if tbox == SUPER_BOX_TYPE || tbox == DESCRIPTION_BOX_TYPE {
+                tbox = BoxType(*b"data");
+            }
+
+            Ok(Self {
+                tbox,
+                data: u.arbitrary()?,
+            })
+        }
+    }
+
+    fn arbitrary_super_box(u: &mut Unstructured, depth: usize) -> arbitrary::Result<SuperBoxModel> {
+        // Labels are NUL-terminated when serialized, so they can't contain
+        // NUL characters.
+        let label: Option<String> = u.arbitrary()?;
+        let label = label.map(|label| label.replace('\0', ""));
+
+        let mut sbox = SuperBoxModel {
+            uuid: u.arbitrary()?,
+            label,
+            requestable: u.arbitrary()?,
+            id: u.arbitrary()?,
+            hash: u.arbitrary()?,
+            private: u.arbitrary()?,
+            child_boxes: vec![],
+        };
+
+        let child_count = u.int_in_range(0..=MAX_CHILDREN)?;
+        for _ in 0..child_count {
+            let child = if depth < MAX_DEPTH && u.arbitrary()? {
+                ChildBoxModel::SuperBox(arbitrary_super_box(u, depth + 1)?)
+            } else {
+                ChildBoxModel::DataBox(u.arbitrary()?)
+            };
+            sbox.child_boxes.push(child);
+        }
+
+        Ok(sbox)
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! An arena-allocated variant of the [`SuperBox`] parse tree.
+//!
+//! Services that parse many JUMBF data structures can use this to allocate
+//! every node of a parse tree from a single [`Bump`] arena, which is then
+//! freed in one step when the arena is dropped or reset.
+//!
+//! This module is available when the `bumpalo` crate feature is enabled.
+//!
+//! [`SuperBox`]: crate::parser::SuperBox
+
+use std::fmt::{Debug, Formatter};
+
+use bumpalo::{collections::Vec as BumpVec, Bump};
+
+use crate::{
+    box_type::SUPER_BOX_TYPE,
+    debug::*,
+    parser::{
+        options::ParseContext,
+        super_box::{count_boxes, parse_child_boxes, ChildSink},
+        DataBox, DescriptionBox, Error, LabelPath, ParseOptions, ParseResult,
+    },
+};
+
+/// A JUMBF superbox whose child list is allocated in a [`Bump`] arena.
+///
+/// This is otherwise equivalent to [`SuperBox`].
+///
+/// [`SuperBox`]: crate::parser::SuperBox
+pub struct ArenaSuperBox<'bump, 'a> {
+    /// Description box.
+    pub desc: DescriptionBox<'a>,
+
+    /// Child boxes, allocated within the arena.
+    pub child_boxes: BumpVec<'bump, ArenaChildBox<'bump, 'a>>,
+
+    /// Original box data.
+    ///
+    /// This the original byte slice that was parsed to create this box.
+    /// It is preserved in case a future client wishes to re-serialize this
+    /// box as is.
+    pub original: &'a [u8],
+}
+
+impl<'bump, 'a> ArenaSuperBox<'bump, 'a> {
+    /// Parse a byte-slice as a JUMBF superbox, allocating the resulting tree
+    /// in `bump`, and return a tuple of the remainder of the input and a
+    /// reference to the parsed super box. Children of this superbox which are
+    /// also superboxes will be parsed recursively, to a limit of
+    /// [`DEFAULT_DEPTH_LIMIT`] nested boxes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hex_literal::hex;
+    /// use jumbf::parser::arena::ArenaSuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let bump = Bump::new();
+    /// let (rem, sbox) = ArenaSuperBox::from_slice_in(&jumbf, &bump).unwrap();
+    /// assert!(rem.is_empty());
+    /// assert_eq!(sbox.desc.label, Some("test.superbox"));
+    /// ```
+    ///
+    /// [`DEFAULT_DEPTH_LIMIT`]: ParseOptions::DEFAULT_DEPTH_LIMIT
+    pub fn from_slice_in(
+        i: &'a [u8],
+        bump: &'bump Bump,
+    ) -> ParseResult<'a, &'bump ArenaSuperBox<'bump, 'a>> {
+        Self::from_slice_with_options_in(i, &ParseOptions::default(), bump)
+    }
+
+    /// Parse a byte-slice as a JUMBF superbox, allocating the resulting tree
+    /// in `bump`, and return a tuple of the remainder of the input and a
+    /// reference to the parsed super box. Parsing behavior is controlled by
+    /// `options`.
+    pub fn from_slice_with_options_in(
+        i: &'a [u8],
+        options: &ParseOptions,
+        bump: &'bump Bump,
+    ) -> ParseResult<'a, &'bump ArenaSuperBox<'bump, 'a>> {
+        let mut ctx = ParseContext::new(options, i);
+        let (i, data_box) = DataBox::from_slice(i)?;
+        let sbox = Self::from_data_box_with_context(&data_box, &mut ctx, 0, bump)?;
+        ctx.check_trailing_data(i)?;
+        Ok((i, bump.alloc(sbox)))
+    }
+
+    fn from_data_box_with_context(
+        data_box: &DataBox<'a>,
+        ctx: &mut ParseContext,
+        depth: usize,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error> {
+        if data_box.tbox != SUPER_BOX_TYPE {
+            return Err(Error::InvalidSuperBoxType(data_box.tbox));
+        }
+
+        ctx.check_cancelled()?;
+        ctx.enter_super_box(depth);
+
+        let (i, desc) = DescriptionBox::from_super_box_payload(data_box.data, ctx.options)
+            .map_err(|err| ctx.locate(data_box.data, err))?;
+        if desc.private.is_some() {
+            ctx.metrics.boxes_parsed += 1;
+        }
+
+        let mut children = ArenaChildren {
+            boxes: BumpVec::with_capacity_in(count_boxes(i), bump),
+            bump,
+        };
+        parse_child_boxes(i, &desc, ctx, depth, &mut children)?;
+        let child_boxes = children.boxes;
+
+        ctx.report_progress(data_box.original);
+
+        Ok(Self {
+            desc,
+            child_boxes,
+            original: data_box.original,
+        })
+    }
+
+    /// Find a child superbox of this superbox by label and verify that
+    /// exactly one such child exists.
+    ///
+    /// This follows the same rules as [`SuperBox::find_by_label()`].
+    ///
+    /// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+    pub fn find_by_label(&self, label: &str) -> Option<&Self> {
+        self.find_by_label_path(&LabelPath::from(label))
+    }
+
+    /// Find a descendant of this superbox by following `path`.
+    ///
+    /// This follows the same rules as [`SuperBox::find_by_label_path()`].
+    ///
+    /// [`SuperBox::find_by_label_path()`]: crate::parser::SuperBox::find_by_label_path
+    pub fn find_by_label_path(&self, path: &LabelPath) -> Option<&Self> {
+        path.components().try_fold(self, |sbox, label| {
+            let mut matching_children = sbox.child_boxes.iter().filter_map(|child_box| {
+                child_box
+                    .as_super_box()
+                    .filter(|sbox| sbox.desc.requestable && sbox.desc.label == Some(label))
+            });
+
+            let sbox = matching_children.next()?;
+            if matching_children.next().is_some() {
+                return None;
+            }
+            Some(sbox)
+        })
+    }
+
+    /// If the first child box of this superbox is a data box, return it.
+    /// Otherwise, return `None`.
+    pub fn data_box(&self) -> Option<&DataBox<'a>> {
+        self.child_boxes
+            .first()
+            .and_then(|child_box| child_box.as_data_box())
+    }
+}
+
+impl<'bump, 'a> Debug for ArenaSuperBox<'bump, 'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("ArenaSuperBox")
+            .field("desc", &self.desc)
+            .field("child_boxes", &self.child_boxes)
+            .field("original", &DebugByteSlice(self.original))
+            .finish()
+    }
+}
+
+// Collects the child boxes of an `ArenaSuperBox` within the arena.
+struct ArenaChildren<'bump, 'a> {
+    boxes: BumpVec<'bump, ArenaChildBox<'bump, 'a>>,
+    bump: &'bump Bump,
+}
+
+impl<'bump, 'a> ChildSink<'a> for ArenaChildren<'bump, 'a> {
+    fn push_super_box(
+        &mut self,
+        d: &DataBox<'a>,
+        ctx: &mut ParseContext,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let sbox = ArenaSuperBox::from_data_box_with_context(d, ctx, depth, self.bump)?;
+        self.boxes.push(ArenaChildBox::SuperBox(sbox));
+        Ok(())
+    }
+
+    fn push_data_box(&mut self, d: DataBox<'a>) {
+        self.boxes.push(ArenaChildBox::DataBox(d));
+    }
+}
+
+/// This type represents a single box within an [`ArenaSuperBox`],
+/// which may itself be a superbox or or a regular box.
+#[derive(Debug)]
+pub enum ArenaChildBox<'bump, 'a> {
+    /// A superbox.
+    SuperBox(ArenaSuperBox<'bump, 'a>),
+
+    /// Any other kind of box.
+    DataBox(DataBox<'a>),
+}
+
+impl<'bump, 'a> ArenaChildBox<'bump, 'a> {
+    /// If this represents a nested super box, return a reference to that
+    /// superbox.
+    pub fn as_super_box(&self) -> Option<&ArenaSuperBox<'bump, 'a>> {
+        if let Self::SuperBox(sb) = self {
+            Some(sb)
+        } else {
+            None
+        }
+    }
+
+    /// If this represents a nested data box, return a reference to that data
+    /// box.
+    pub fn as_data_box(&self) -> Option<&DataBox<'a>> {
+        if let Self::DataBox(db) = self {
+            Some(db)
+        } else {
+            None
+        }
+    }
+}
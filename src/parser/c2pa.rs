@@ -0,0 +1,315 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Conveniences for working with [C2PA] manifest stores.
+//!
+//! This module is available when the `c2pa` crate feature is enabled. It
+//! only understands the JUMBF structure of C2PA data; it does not parse or
+//! validate assertion content. The one exception is [`check_references()`],
+//! which scans claims for JUMBF URIs without otherwise decoding them.
+//!
+//! [C2PA]: https://c2pa.org/specifications/
+
+use std::str::from_utf8;
+
+use crate::{
+    content_type::ContentType,
+    parser::{ChildBox, DataBox, LabelMatch, LabelPath, SuperBox},
+};
+
+/// An assertion found by [`assertions()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Assertion<'s, 'a> {
+    /// Label of the assertion (i.e. `"c2pa.actions"`).
+    pub label: &'a str,
+
+    /// Content type of the assertion, if its UUID is a known content type.
+    pub content_type: Option<&'static ContentType>,
+
+    /// The assertion superbox.
+    pub sbox: &'s SuperBox<'a>,
+
+    /// The data box which carries the assertion's content, if any.
+    ///
+    /// This is the last data box in the assertion superbox. For most
+    /// assertions there is only one. For embedded file assertions, this is
+    /// the binary data (`bidb`) box, which follows the file description
+    /// (`bfdb`) box.
+    pub payload: Option<&'s DataBox<'a>>,
+}
+
+/// Iterate over the assertions in a C2PA assertion store.
+///
+/// `store` should be the `c2pa.assertions` superbox of a manifest. Each
+/// labeled child superbox is returned as an [`Assertion`]. Other child boxes
+/// are skipped.
+///
+/// ## Example
+///
+/// ```
+/// use hex_literal::hex;
+/// use jumbf::{
+///     content_type,
+///     parser::{c2pa::assertions, SuperBox},
+/// };
+///
+/// let jumbf = hex!(
+///     "0000005e" // box size
+///     "6a756d62" // box type = 'jumb'
+///         "00000029" // box size
+///         "6a756d64" // box type = 'jumd'
+///         "6332617300110010800000aa00389b71" // UUID (assertion store)
+///         "03" // toggles
+///         "633270612e617373657274696f6e7300" // label = "c2pa.assertions"
+///         // ---
+///         "0000002d" // box size
+///         "6a756d62" // box type = 'jumb'
+///             "0000001b" // box size
+///             "6a756d64" // box type = 'jumd'
+///             "6a736f6e00110010800000aa00389b71" // UUID (JSON)
+///             "03" // toggles
+///             "6100" // label = "a"
+///             // ---
+///             "0000000a" // box size
+///             "6a736f6e" // box type = 'json'
+///             "7b7d" // payload = "{}"
+/// );
+///
+/// let (_, store) = SuperBox::from_slice(&jumbf).unwrap();
+/// let found: Vec<_> = assertions(&store).collect();
+///
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].label, "a");
+/// assert_eq!(found[0].content_type, Some(&content_type::JSON));
+/// assert_eq!(found[0].payload.unwrap().data, b"{}");
+/// ```
+pub fn assertions<'s, 'a>(store: &'s SuperBox<'a>) -> impl Iterator<Item = Assertion<'s, 'a>> {
+    store
+        .child_boxes
+        .iter()
+        .filter_map(|child_box| match child_box {
+            ChildBox::SuperBox(sbox) => Some(Assertion {
+                label: sbox.desc.label?,
+                content_type: sbox.desc.content_type(),
+                sbox,
+                payload: sbox
+                    .child_boxes
+                    .iter()
+                    .rev()
+                    .find_map(|child_box| match child_box {
+                        ChildBox::DataBox(dbox) => Some(dbox),
+                        ChildBox::SuperBox(_) => None,
+                    }),
+            }),
+            ChildBox::DataBox(_) => None,
+        })
+}
+
+/// A JUMBF URI in a claim which doesn't resolve to exactly one superbox, as
+/// reported by [`check_references()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReferenceIssue<'s, 'a> {
+    /// The manifest whose claim contains the reference.
+    pub manifest: &'s SuperBox<'a>,
+
+    /// The data box which contains the reference.
+    pub claim: &'s DataBox<'a>,
+
+    /// The reference as it appears in the claim (i.e.
+    /// `self#jumbf=c2pa.assertions/c2pa.actions`).
+    pub uri: &'a str,
+
+    /// Offset of the reference within the claim data box's payload.
+    pub offset: usize,
+
+    /// Describes why the reference doesn't resolve.
+    pub kind: ReferenceIssueKind,
+}
+
+/// Describes why a JUMBF URI doesn't resolve.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReferenceIssueKind {
+    /// No superbox matches the reference.
+    Dangling,
+
+    /// More than one superbox matches some component of the reference.
+    Ambiguous,
+}
+
+/// Check that every JUMBF URI in the claims of a C2PA manifest store
+/// resolves to exactly one superbox.
+///
+/// `store` should be the top-level `c2pa` superbox. The claim of each
+/// manifest (its `c2pa.claim` or `c2pa.claim.v2` superbox) is scanned for
+/// `self#jumbf=` URIs encoded as JSON or CBOR strings. Relative URIs are
+/// resolved from the manifest which contains the claim and absolute URIs
+/// (i.e. `self#jumbf=/c2pa/...`) from `store`. Any query (such as `?hl=`)
+/// is ignored. As for [`SuperBox::find_by_label()`], only requestable
+/// superboxes are considered.
+///
+/// Returns one [`ReferenceIssue`] for each reference which doesn't resolve,
+/// in document order. An empty result means every reference resolved.
+///
+/// ## Example
+///
+/// ```
+/// use jumbf::parser::{c2pa::check_references, SuperBox};
+///
+/// # let jumbf = include_bytes!("../tests/fixtures/C.c2pa");
+/// let (_, store) = SuperBox::from_slice(jumbf).unwrap();
+/// assert!(check_references(&store).is_empty());
+/// ```
+pub fn check_references<'s, 'a>(store: &'s SuperBox<'a>) -> Vec<ReferenceIssue<'s, 'a>> {
+    let mut issues = vec![];
+
+    let manifests = store
+        .child_boxes
+        .iter()
+        .filter_map(|child_box| match child_box {
+            ChildBox::SuperBox(sbox) => Some(sbox),
+            ChildBox::DataBox(_) => None,
+        });
+
+    for manifest in manifests {
+        let claims = manifest
+            .child_boxes
+            .iter()
+            .filter_map(|child_box| match child_box {
+                ChildBox::SuperBox(sbox)
+                    if matches!(sbox.desc.label, Some("c2pa.claim" | "c2pa.claim.v2")) =>
+                {
+                    Some(sbox)
+                }
+                _ => None,
+            });
+
+        for claim_sbox in claims {
+            for child_box in &claim_sbox.child_boxes {
+                let ChildBox::DataBox(claim) = child_box else {
+                    continue;
+                };
+
+                for (offset, uri) in find_jumbf_uris(claim.data) {
+                    if let Err(kind) = resolve(store, manifest, uri) {
+                        issues.push(ReferenceIssue {
+                            manifest,
+                            claim,
+                            uri,
+                            offset,
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+const JUMBF_URI_PREFIX: &[u8] = b"self#jumbf=";
+
+// Find JUMBF URIs which are encoded as JSON or CBOR text strings in `data`.
+// Returns the offset and text of each URI.
+fn find_jumbf_uris(data: &[u8]) -> Vec<(usize, &str)> {
+    let mut uris = vec![];
+    let mut start = 0;
+
+    while let Some(pos) = data[start..]
+        .windows(JUMBF_URI_PREFIX.len())
+        .position(|window| window == JUMBF_URI_PREFIX)
+    {
+        let offset = start + pos;
+        start = offset + JUMBF_URI_PREFIX.len();
+
+        let len = if offset > 0 && data[offset - 1] == b'"' {
+            // JSON: The string ends at the next quote.
+            data[offset..].iter().position(|b| *b == b'"')
+        } else {
+            cbor_text_len(&data[..offset])
+        };
+
+        let uri = len
+            .and_then(|len| data.get(offset..offset + len))
+            .filter(|uri| uri.len() >= JUMBF_URI_PREFIX.len())
+            .and_then(|uri| from_utf8(uri).ok());
+
+        if let Some(uri) = uri {
+            uris.push((offset, uri));
+        }
+    }
+
+    uris
+}
+
+// If `before` ends with a CBOR text string header, return the length of the
+// string that follows it.
+fn cbor_text_len(before: &[u8]) -> Option<usize> {
+    match *before {
+        [.., 0x79, hi, lo] if u16::from_be_bytes([hi, lo]) > 0xff => {
+            Some(u16::from_be_bytes([hi, lo]) as usize)
+        }
+        [.., 0x78, len] if len >= 24 => Some(len as usize),
+        [.., header @ 0x60..=0x77] => Some((header - 0x60) as usize),
+        _ => None,
+    }
+}
+
+// Resolve a JUMBF URI to exactly one superbox.
+fn resolve(store: &SuperBox, manifest: &SuperBox, uri: &str) -> Result<(), ReferenceIssueKind> {
+    let path = &uri[JUMBF_URI_PREFIX.len()..];
+    let path = path.split_once('?').map_or(path, |(path, _query)| path);
+
+    let (sbox, path) = match path.strip_prefix('/') {
+        Some(path) => {
+            // The first component names the manifest store itself.
+            let (first, rest) = path.split_once('/').unwrap_or((path, ""));
+            if store.desc.label != Some(first) {
+                return Err(ReferenceIssueKind::Dangling);
+            }
+            (store, rest)
+        }
+        None => (manifest, path),
+    };
+
+    if path.is_empty() {
+        return Ok(());
+    }
+
+    let label_match = LabelMatch::default();
+    LabelPath::from(path)
+        .components()
+        .try_fold(sbox, |sbox, label| {
+            let mut matches = sbox
+                .child_boxes
+                .iter()
+                .filter_map(|child_box| match child_box {
+                    ChildBox::SuperBox(child)
+                        if child.desc.requestable
+                            && child.desc.label.is_some_and(|child_label| {
+                                label_match.matches(child_label, label)
+                            }) =>
+                    {
+                        Some(child)
+                    }
+                    _ => None,
+                });
+
+            let child = matches.next().ok_or(ReferenceIssueKind::Dangling)?;
+            if matches.next().is_some() {
+                return Err(ReferenceIssueKind::Ambiguous);
+            }
+            Ok(child)
+        })
+        .map(|_| ())
+}
@@ -0,0 +1,431 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Checks raw JUMBF data against the normative requirements of
+//! [ISO/IEC 19566-5:2019].
+//!
+//! [`SuperBox::from_slice()`] stops at the first problem it can't work
+//! around, and [`SuperBox::verify_all()`] only sees what the parser kept.
+//! [`check()`] instead walks the box structure byte by byte and records
+//! every [`Rule`] that is violated, together with the offset at which the
+//! violation was found.
+//!
+//! Each rule carries a reference to the part of the standard that states
+//! the requirement. References name the relevant definition rather than a
+//! clause number, since clause numbering differs between editions.
+//!
+//! The only reserved values checked are reserved box lengths and reserved
+//! toggle bits. Box types are not checked against any list of reserved
+//! values.
+//!
+//! ## Example
+//!
+//! ```
+//! use hex_literal::hex;
+//! use jumbf::parser::conformance::{self, Rule};
+//!
+//! let jumbf = hex!(
+//!     "00000021" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "00000019" // box size
+//!         "6a756d64" // box type = 'jumd'
+//!         "00000000000000000000000000000000" // UUID
+//!         "01" // toggles = requestable, but no label
+//! );
+//!
+//! let report = conformance::check(&jumbf);
+//! assert!(!report.is_conforming());
+//! assert!(report.passed(Rule::DescriptionBoxFirst));
+//! assert!(!report.passed(Rule::RequestableHasLabel));
+//! assert_eq!(report.findings[0].offset, 8);
+//! ```
+//!
+//! [ISO/IEC 19566-5:2019]: https://www.iso.org/standard/73604.html
+//! [`SuperBox::from_slice()`]: crate::parser::SuperBox::from_slice
+//! [`SuperBox::verify_all()`]: crate::parser::SuperBox::verify_all
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    parser::{DataBox, Error, ParseOptions},
+    Toggles,
+};
+
+/// Check `jumbf`, which should contain a single top-level superbox, against
+/// every [`Rule`].
+///
+/// Superboxes nested more deeply than
+/// [`ParseOptions::DEFAULT_DEPTH_LIMIT`] are not examined. Any data
+/// following the top-level box is ignored.
+pub fn check(jumbf: &[u8]) -> ConformanceReport {
+    let mut checker = Checker {
+        root: jumbf,
+        report: ConformanceReport::default(),
+    };
+
+    match DataBox::from_slice(jumbf) {
+        Ok((_, dbox)) if dbox.tbox == SUPER_BOX_TYPE => checker.check_super_box(&dbox, 0),
+        Ok((_, dbox)) => checker.push(
+            Rule::SuperBoxType,
+            jumbf,
+            format!("top-level box type is {:?}", dbox.tbox),
+        ),
+        Err(err) => checker.push_error(jumbf, err),
+    }
+
+    checker.report
+}
+
+/// A normative requirement of the JUMBF standard checked by [`check()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Rule {
+    /// Every box must fit within its enclosing box (or, for the top-level
+    /// box, within the data).
+    BoxLength,
+
+    /// Box lengths must not use the reserved values 2 through 7 (or an
+    /// extended length below 16).
+    ReservedBoxLength,
+
+    /// A JUMBF data structure must be a superbox (box type `jumb`).
+    SuperBoxType,
+
+    /// Every superbox must start with a description box (box type `jumd`).
+    DescriptionBoxFirst,
+
+    /// A superbox must not contain more than one description box.
+    SingleDescriptionBox,
+
+    /// The fields of a description box must be exactly those indicated by
+    /// its toggles: a null-terminated UTF-8 label, a 4-byte ID, a 32-byte
+    /// hash, and a private box, each only when the matching toggle is set.
+    DescriptionBoxFields,
+
+    /// A superbox whose description box is requestable must also have a
+    /// label.
+    RequestableHasLabel,
+
+    /// Reserved toggle bits (0xe0) must be zero.
+    ReservedToggleBits,
+}
+
+impl Rule {
+    /// All rules, in the order in which reports list them.
+    pub const ALL: [Self; 8] = [
+        Self::BoxLength,
+        Self::ReservedBoxLength,
+        Self::SuperBoxType,
+        Self::DescriptionBoxFirst,
+        Self::SingleDescriptionBox,
+        Self::DescriptionBoxFields,
+        Self::RequestableHasLabel,
+        Self::ReservedToggleBits,
+    ];
+
+    /// Returns a short, stable identifier for this rule.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::BoxLength => "box-length",
+            Self::ReservedBoxLength => "reserved-box-length",
+            Self::SuperBoxType => "superbox-type",
+            Self::DescriptionBoxFirst => "description-box-first",
+            Self::SingleDescriptionBox => "single-description-box",
+            Self::DescriptionBoxFields => "description-box-fields",
+            Self::RequestableHasLabel => "requestable-has-label",
+            Self::ReservedToggleBits => "reserved-toggle-bits",
+        }
+    }
+
+    /// Returns the part of the standard which states this requirement.
+    pub fn reference(self) -> &'static str {
+        match self {
+            Self::BoxLength | Self::ReservedBoxLength => {
+                "ISO/IEC 15444-1, Annex I, box definition (LBox, XLBox)"
+            }
+            Self::SuperBoxType | Self::DescriptionBoxFirst | Self::SingleDescriptionBox => {
+                "ISO/IEC 19566-5, JUMBF superbox definition"
+            }
+            Self::DescriptionBoxFields | Self::RequestableHasLabel => {
+                "ISO/IEC 19566-5, JUMBF description box definition"
+            }
+            Self::ReservedToggleBits => "ISO/IEC 19566-5, description box toggles field",
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.id())
+    }
+}
+
+/// A single violation of a [`Rule`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Finding {
+    /// The rule that was violated.
+    pub rule: Rule,
+
+    /// Offset of the offending box (or field) from the start of the data
+    /// passed to [`check()`].
+    pub offset: usize,
+
+    /// Human-readable description of what was found.
+    pub detail: String,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "{} at offset {}: {} ({})",
+            self.rule,
+            self.offset,
+            self.detail,
+            self.rule.reference()
+        )
+    }
+}
+
+/// The result of [`check()`].
+///
+/// Formatting a report with `{}` lists every rule as `PASS` or `FAIL`,
+/// followed by the findings for each failed rule.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConformanceReport {
+    /// Violations found, in the order in which they appear in the data.
+    pub findings: Vec<Finding>,
+}
+
+impl ConformanceReport {
+    /// Returns `true` if no rule was violated.
+    pub fn is_conforming(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns `true` if `rule` was not violated.
+    pub fn passed(&self, rule: Rule) -> bool {
+        self.findings_for(rule).next().is_none()
+    }
+
+    /// Returns the findings for `rule`.
+    pub fn findings_for(&self, rule: Rule) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(move |f| f.rule == rule)
+    }
+
+    /// Returns each rule in [`Rule::ALL`] along with whether it passed.
+    pub fn results(&self) -> impl Iterator<Item = (Rule, bool)> + '_ {
+        Rule::ALL.into_iter().map(|rule| (rule, self.passed(rule)))
+    }
+}
+
+impl Display for ConformanceReport {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for (rule, passed) in self.results() {
+            let status = if passed { "PASS" } else { "FAIL" };
+            writeln!(f, "{status} {rule} ({})", rule.reference())?;
+
+            for finding in self.findings_for(rule) {
+                writeln!(f, "    offset {}: {}", finding.offset, finding.detail)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Checker<'a> {
+    root: &'a [u8],
+    report: ConformanceReport,
+}
+
+impl<'a> Checker<'a> {
+    fn push(&mut self, rule: Rule, at: &[u8], detail: String) {
+        let offset = (at.as_ptr() as usize).wrapping_sub(self.root.as_ptr() as usize);
+        self.report.findings.push(Finding {
+            rule,
+            offset,
+            detail,
+        });
+    }
+
+    // Report a box at `at` which couldn't be parsed.
+    fn push_error(&mut self, at: &[u8], err: nom::Err<Error>) {
+        let rule = match err {
+            nom::Err::Error(Error::InvalidBoxLength(_))
+            | nom::Err::Failure(Error::InvalidBoxLength(_)) => Rule::ReservedBoxLength,
+            _ => Rule::BoxLength,
+        };
+        self.push(rule, at, error_detail(err));
+    }
+
+    fn check_super_box(&mut self, sbox: &DataBox<'a>, depth: usize) {
+        let mut i = sbox.data;
+        let mut index = 0;
+        let mut desc_count = 0;
+
+        while !i.is_empty() {
+            let child = match DataBox::from_bounded_slice(i) {
+                Ok((rem, child)) => {
+                    i = rem;
+                    child
+                }
+                Err(err) => {
+                    self.push_error(i, err);
+                    return;
+                }
+            };
+
+            if child.tbox == DESCRIPTION_BOX_TYPE {
+                desc_count += 1;
+                if index != 0 {
+                    self.push(
+                        Rule::DescriptionBoxFirst,
+                        child.original,
+                        format!("description box is child box {index}"),
+                    );
+                }
+
+                if desc_count == 2 {
+                    self.push(
+                        Rule::SingleDescriptionBox,
+                        child.original,
+                        "second description box in superbox".to_owned(),
+                    );
+                }
+
+                self.check_description_box(&child);
+            } else if index == 0 {
+                self.push(
+                    Rule::DescriptionBoxFirst,
+                    child.original,
+                    format!("first child box has type {:?}", child.tbox),
+                );
+            }
+
+            if child.tbox == SUPER_BOX_TYPE && depth < ParseOptions::DEFAULT_DEPTH_LIMIT {
+                self.check_super_box(&child, depth + 1);
+            }
+
+            index += 1;
+        }
+
+        if index == 0 {
+            self.push(
+                Rule::DescriptionBoxFirst,
+                sbox.original,
+                "superbox is empty".to_owned(),
+            );
+        }
+    }
+
+    fn check_description_box(&mut self, desc: &DataBox) {
+        let data = desc.data;
+        if data.len() < 17 {
+            self.push(
+                Rule::DescriptionBoxFields,
+                desc.original,
+                format!(
+                    "payload is {} bytes; UUID and toggles require 17",
+                    data.len()
+                ),
+            );
+            return;
+        }
+
+        let toggles = Toggles::from_bits(data[16]);
+        let reserved = data[16] & Toggles::RESERVED.bits();
+        if reserved != 0 {
+            self.push(
+                Rule::ReservedToggleBits,
+                &data[16..],
+                format!("reserved toggle bits {reserved:#04x} are set"),
+            );
+        }
+
+        if toggles.contains(Toggles::REQUESTABLE) && !toggles.contains(Toggles::HAS_LABEL) {
+            self.push(
+                Rule::RequestableHasLabel,
+                desc.original,
+                "requestable description box has no label".to_owned(),
+            );
+        }
+
+        let mut i = &data[17..];
+
+        if toggles.contains(Toggles::HAS_LABEL) {
+            let Some(len) = i.iter().position(|b| *b == 0) else {
+                self.push(
+                    Rule::DescriptionBoxFields,
+                    i,
+                    "label is not null-terminated".to_owned(),
+                );
+                return;
+            };
+
+            if std::str::from_utf8(&i[..len]).is_err() {
+                self.push(
+                    Rule::DescriptionBoxFields,
+                    i,
+                    "label is not valid UTF-8".to_owned(),
+                );
+            }
+            i = &i[len + 1..];
+        }
+
+        for (toggle, name, len) in [(Toggles::HAS_ID, "ID", 4), (Toggles::HAS_HASH, "hash", 32)] {
+            if toggles.contains(toggle) {
+                if i.len() < len {
+                    self.push(
+                        Rule::DescriptionBoxFields,
+                        i,
+                        format!("{name} requires {len} bytes, but {} remain", i.len()),
+                    );
+                    return;
+                }
+                i = &i[len..];
+            }
+        }
+
+        if toggles.contains(Toggles::HAS_PRIVATE_BOX) {
+            match DataBox::from_bounded_slice(i) {
+                Ok((rem, _)) => i = rem,
+                Err(err) => {
+                    self.push(
+                        Rule::DescriptionBoxFields,
+                        i,
+                        format!("private box: {}", error_detail(err)),
+                    );
+                    return;
+                }
+            }
+        }
+
+        if !i.is_empty() {
+            self.push(
+                Rule::DescriptionBoxFields,
+                i,
+                format!(
+                    "{} bytes follow the fields indicated by the toggles",
+                    i.len()
+                ),
+            );
+        }
+    }
+}
+
+fn error_detail(err: nom::Err<Error>) -> String {
+    match err {
+        nom::Err::Error(err) | nom::Err::Failure(err) => err.to_string(),
+        nom::Err::Incomplete(_) => "box is truncated".to_owned(),
+    }
+}
@@ -11,16 +11,19 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::fmt::{Debug, Formatter};
-
-use nom::{
-    number::complete::{be_u32, be_u64},
-    Needed,
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Formatter},
+    io::{Result as IoResult, Write},
+    str::from_utf8,
 };
 
+use nom::number::complete::{be_u32, be_u64};
+
 use crate::{
     debug::*,
-    parser::{Error, ParseResult, SuperBox},
+    digest::Digest,
+    parser::{boxes_from_slice, ChildBox, Error, ParseResult, SuperBox},
     BoxType,
 };
 
@@ -70,24 +73,32 @@ impl<'a> DataBox<'a> {
     /// The returned object uses zero-copy, and so has the same lifetime as the
     /// input.
     pub fn from_slice(original: &'a [u8]) -> ParseResult<'a, Self> {
-        let (i, len) = be_u32(original)?;
+        let truncated = |needed: usize| nom::Err::Error(Error::Truncated { needed, offset: 0 });
 
-        let (i, tbox): (&'a [u8], BoxType) = if i.len() >= 4 {
-            let (tbox, i) = i.split_at(4);
-            (i, tbox.into())
-        } else {
-            return Err(nom::Err::Error(Error::Incomplete(Needed::new(4))));
-        };
+        if original.len() < 8 {
+            return Err(truncated(8 - original.len()));
+        }
+
+        let (i, len) = be_u32(original)?;
+        let (tbox, i) = i.split_at(4);
+        let tbox: BoxType = tbox.into();
 
         let (i, len, original_len) = match len {
             0 => (i, i.len(), original.len()),
             1 => {
+                if original.len() < 16 {
+                    return Err(truncated(16 - original.len()));
+                }
+
                 let (i, len) = be_u64(i)?;
-                if len >= 16 {
-                    (i, len as usize - 16, len as usize)
-                } else {
+                if len < 16 {
                     return Err(nom::Err::Error(Error::InvalidBoxLength(len as u32)));
                 }
+
+                // On 32-bit targets, a 64-bit length may not fit in `usize`.
+                let len = usize::try_from(len)
+                    .map_err(|_| nom::Err::Error(Error::BoxLengthOverflow(len)))?;
+                (i, len - 16, len)
             }
             2..=7 => {
                 return Err(nom::Err::Error(Error::InvalidBoxLength(len)));
@@ -106,10 +117,58 @@ impl<'a> DataBox<'a> {
                 },
             ))
         } else {
-            Err(nom::Err::Error(Error::Incomplete(Needed::new(len))))
+            Err(truncated(len - i.len()))
         }
     }
 
+    // Parse a box which must fit within `i`, the remainder of its enclosing
+    // box. Running out of input then means that the box is malformed, not
+    // that the input is incomplete, so `Truncated` errors are reported as
+    // `CorruptHeader` (if the header itself doesn't fit) or `SizeMismatch`.
+    pub(crate) fn from_bounded_slice(i: &'a [u8]) -> ParseResult<'a, Self> {
+        Self::from_slice(i).map_err(|err| match err {
+            nom::Err::Error(Error::Truncated { needed, offset }) => {
+                let header_len = if i.starts_with(&[0, 0, 0, 1]) { 16 } else { 8 };
+                nom::Err::Error(if i.len() < header_len {
+                    Error::CorruptHeader { offset }
+                } else {
+                    Error::SizeMismatch {
+                        offset,
+                        declared: (i.len() + needed) as u64,
+                        available: i.len(),
+                    }
+                })
+            }
+            err => err,
+        })
+    }
+
+    /// Returns the length of this box's header in [`original`].
+    ///
+    /// This is 16 if the box uses an extended (XLBox) length field and 8
+    /// otherwise. The payload begins this many bytes after the start of
+    /// [`original`].
+    ///
+    /// [`original`]: Self::original
+    pub fn header_len(&self) -> usize {
+        HeaderEncoding::of(self.original).header_len()
+    }
+
+    /// Returns the length of this box's payload.
+    ///
+    /// This is the same as `self.data.len()`.
+    pub fn payload_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if this box's header in [`original`] uses an extended
+    /// (XLBox) length field.
+    ///
+    /// [`original`]: Self::original
+    pub fn uses_extended_length(&self) -> bool {
+        HeaderEncoding::of(self.original) == HeaderEncoding::Extended
+    }
+
     /// Returns the offset of the *data* portion of this box within its
     /// enclosing [`SuperBox`].
     ///
@@ -156,6 +215,330 @@ impl<'a> DataBox<'a> {
             Some(offset)
         }
     }
+
+    /// Serialize this box to a JUMBF stream.
+    ///
+    /// If the box is unmodified since it was parsed (i.e. [`tbox`] and
+    /// [`data`] still refer to the content of [`original`]), the original
+    /// bytes are written as is. Otherwise, the box is re-serialized using the
+    /// same header encoding (32-bit length, XLBox length, or length 0 meaning
+    /// "to end of data") as the original box, where possible.
+    ///
+    /// [`tbox`]: Self::tbox
+    /// [`data`]: Self::data
+    /// [`original`]: Self::original
+    pub fn write_to(&self, to_stream: &mut dyn Write) -> IoResult<()> {
+        if self.is_unmodified() {
+            return to_stream.write_all(self.original);
+        }
+
+        write_header_like(self.original, self.tbox, self.data.len(), to_stream)?;
+        to_stream.write_all(self.data)
+    }
+
+    /// Write the [`original`] bytes of this box to `to_stream`.
+    ///
+    /// Unlike [`write_to()`], this ignores any changes made to the box since
+    /// it was parsed, which makes it suitable for extracting a box exactly
+    /// as it appeared in the source data.
+    ///
+    /// [`original`]: Self::original
+    /// [`write_to()`]: Self::write_to
+    pub fn copy_original_to(&self, to_stream: &mut dyn Write) -> IoResult<()> {
+        to_stream.write_all(self.original)
+    }
+
+    /// Compute the digest of this box exactly as it appeared in the input,
+    /// including its header.
+    ///
+    /// Like [`copy_original_to()`], this uses [`original`], so any changes
+    /// made to this struct after parsing are not reflected. To compute the
+    /// digest of the box as it would be serialized now, use
+    /// `digest.digest(&dbox.to_vec())`.
+    ///
+    /// [`copy_original_to()`]: Self::copy_original_to
+    /// [`original`]: Self::original
+    pub fn digest(&self, digest: &dyn Digest) -> Vec<u8> {
+        digest.digest(self.original)
+    }
+
+    /// Serialize this box to a new byte vector.
+    ///
+    /// See [`write_to()`] for a description of how the box is serialized.
+    ///
+    /// [`write_to()`]: Self::write_to
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+
+        // Writing to a Vec can't fail.
+        #[allow(clippy::unwrap_used)]
+        self.write_to(&mut result).unwrap();
+
+        result
+    }
+
+    /// Returns the number of bytes this box occupies when serialized.
+    ///
+    /// If the box has not been modified since it was parsed, this is the
+    /// length of [`original`]. Otherwise, it is the number of bytes that
+    /// [`write_to()`] will write.
+    ///
+    /// [`original`]: Self::original
+    /// [`write_to()`]: Self::write_to
+    pub fn serialized_len(&self) -> usize {
+        if self.is_unmodified() {
+            self.original.len()
+        } else {
+            header_len_like(self.original, self.data.len()) + self.data.len()
+        }
+    }
+
+    // Returns true if `tbox` and `data` still describe `original`.
+    pub(crate) fn is_unmodified(&self) -> bool {
+        let header_len = HeaderEncoding::of(self.original).header_len();
+
+        self.original.len() >= header_len
+            && self.original[4..8] == self.tbox.0
+            && self.data.len() == self.original.len() - header_len
+            && self.data.as_ptr() == self.original[header_len..].as_ptr()
+    }
+
+    /// Parse this box's payload as a sequence of JUMBF boxes.
+    ///
+    /// This is useful for box types whose payload contains other boxes. See
+    /// [`boxes_from_slice()`] for details.
+    ///
+    /// [`boxes_from_slice()`]: crate::parser::boxes_from_slice
+    pub fn parse_children(&self) -> Result<Vec<ChildBox<'a>>, Error> {
+        boxes_from_slice(self.data)
+    }
+
+    /// Split this box's payload into a leading 16-byte UUID and the data
+    /// which follows it.
+    ///
+    /// This layout is used by JUMBF `uuid` boxes and by ISO BMFF `uuid`
+    /// boxes. The box type is not checked, so this can be used for any box
+    /// which follows the same convention. The returned data is borrowed
+    /// from the input; nothing is copied except the UUID.
+    ///
+    /// Returns `None` if the payload is shorter than 16 bytes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::DataBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000001b" // box size
+    ///     "75756964" // box type = 'uuid'
+    ///     "6332637300110010800000aa00389b71" // UUID
+    ///     "616263" // data
+    /// );
+    ///
+    /// let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    /// let (uuid, data) = dbox.split_uuid_payload().unwrap();
+    ///
+    /// assert_eq!(uuid, hex!("6332637300110010800000aa00389b71"));
+    /// assert_eq!(data, b"abc");
+    /// ```
+    pub fn split_uuid_payload(&self) -> Option<([u8; 16], &'a [u8])> {
+        let uuid = self.data.get(..16)?.try_into().ok()?;
+        Some((uuid, &self.data[16..]))
+    }
+
+    /// Interpret this box's payload as UTF-8 text.
+    ///
+    /// If `strip_bom` is `true`, a leading UTF-8 byte order mark
+    /// (`EF BB BF`) is removed before decoding.
+    ///
+    /// Returns [`Error::Utf8Error`] if the payload is not valid UTF-8.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::DataBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "00000012" // box size
+    ///     "6a736f6e" // box type = 'json'
+    ///     "efbbbf" // byte order mark
+    ///     "7b2261223a317d" // payload = {"a":1}
+    /// );
+    ///
+    /// let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    /// assert_eq!(dbox.as_utf8(true).unwrap(), "{\"a\":1}");
+    /// assert_eq!(dbox.as_utf8(false).unwrap(), "\u{feff}{\"a\":1}");
+    /// ```
+    ///
+    /// [`Error::Utf8Error`]: crate::parser::Error::Utf8Error
+    pub fn as_utf8(&self, strip_bom: bool) -> Result<Cow<'a, str>, Error> {
+        let data = match self.data.strip_prefix(UTF8_BOM) {
+            Some(data) if strip_bom => data,
+            _ => self.data,
+        };
+
+        // The payload is always borrowed from the input, so no copy is
+        // needed.
+        from_utf8(data).map(Cow::Borrowed).map_err(Error::Utf8Error)
+    }
+
+    /// Guess the media type of this box's payload by inspecting its first
+    /// few bytes.
+    ///
+    /// Recognizes JPEG (`image/jpeg`), PNG (`image/png`), CBOR
+    /// (`application/cbor`), JSON (`application/json`), and XML
+    /// (`application/xml`). Returns `None` if the payload doesn't look like
+    /// any of these.
+    ///
+    /// This is a heuristic intended for use when a box's type or content-type
+    /// UUID is missing or unreliable. In particular, CBOR is recognized only
+    /// by a leading self-describe tag or a top-level map or array, and JSON
+    /// and XML only by their first non-whitespace character.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::DataBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000000f" // box size
+    ///     "6a736f6e" // box type = 'json'
+    ///     "7b2261223a317d" // payload = {"a":1}
+    /// );
+    ///
+    /// let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    /// assert_eq!(dbox.sniff_media_type(), Some("application/json"));
+    /// ```
+    pub fn sniff_media_type(&self) -> Option<&'static str> {
+        let data = self.data;
+
+        if data.starts_with(&[0xff, 0xd8, 0xff]) {
+            return Some("image/jpeg");
+        }
+
+        if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]) {
+            return Some("image/png");
+        }
+
+        // CBOR self-describe tag (55799), or a top-level map (major type 5)
+        // or array (major type 4) with a definite or indefinite length.
+        match data.first() {
+            _ if data.starts_with(&[0xd9, 0xd9, 0xf7]) => return Some("application/cbor"),
+            Some(0x80..=0x9b | 0x9f | 0xa0..=0xbb | 0xbf) => return Some("application/cbor"),
+            _ => (),
+        }
+
+        let text = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+        match text.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{' | b'[') => Some("application/json"),
+            Some(b'<') => Some("application/xml"),
+            _ => None,
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+
+// Describes how the length of a box was encoded in its header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum HeaderEncoding {
+    // 32-bit length field.
+    Compact,
+
+    // 32-bit length field of 1 followed by 64-bit XLBox length field.
+    Extended,
+
+    // 32-bit length field of 0, meaning the box extends to end of data.
+    ToEnd,
+}
+
+impl HeaderEncoding {
+    // Determine the header encoding used by an original box. Boxes that
+    // weren't parsed (i.e. have no original data) are treated as compact.
+    pub(crate) fn of(original: &[u8]) -> Self {
+        if original.len() < 8 {
+            return Self::Compact;
+        }
+
+        match original[0..4] {
+            [0, 0, 0, 0] => Self::ToEnd,
+            [0, 0, 0, 1] => Self::Extended,
+            _ => Self::Compact,
+        }
+    }
+
+    pub(crate) fn header_len(self) -> usize {
+        match self {
+            Self::Extended => 16,
+            _ => 8,
+        }
+    }
+}
+
+// Returns the 32-bit length field for a compact header describing a payload
+// of `payload_len` bytes, if such a header is possible.
+fn compact_len(payload_len: usize) -> Option<u32> {
+    payload_len
+        .checked_add(8)
+        .and_then(|len| u32::try_from(len).ok())
+}
+
+// Returns the length of the header that `write_header_like()` will write.
+pub(crate) fn header_len_like(original: &[u8], payload_len: usize) -> usize {
+    match (HeaderEncoding::of(original), compact_len(payload_len)) {
+        (HeaderEncoding::ToEnd, _) | (HeaderEncoding::Compact, Some(_)) => 8,
+        _ => 16,
+    }
+}
+
+// Write a box header for a payload of `payload_len` bytes, using the same
+// header encoding as `original` when possible.
+pub(crate) fn write_header_like(
+    original: &[u8],
+    tbox: BoxType,
+    payload_len: usize,
+    to_stream: &mut dyn Write,
+) -> IoResult<()> {
+    match (HeaderEncoding::of(original), compact_len(payload_len)) {
+        (HeaderEncoding::ToEnd, _) => {
+            to_stream.write_all(&0u32.to_be_bytes())?;
+            to_stream.write_all(&tbox.0)
+        }
+
+        (HeaderEncoding::Compact, Some(len)) => {
+            to_stream.write_all(&len.to_be_bytes())?;
+            to_stream.write_all(&tbox.0)
+        }
+
+        _ => {
+            to_stream.write_all(&1u32.to_be_bytes())?;
+            to_stream.write_all(&tbox.0)?;
+            to_stream.write_all(&(payload_len as u64 + 16).to_be_bytes())
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for DataBox<'a> {
+    type Error = Error;
+
+    /// Parse a byte-slice which contains exactly one JUMBF box.
+    ///
+    /// Unlike [`DataBox::from_slice()`], an error is returned if any data
+    /// follows the box.
+    fn try_from(i: &'a [u8]) -> Result<Self, Self::Error> {
+        let (rem, dbox) = Self::from_slice(i)?;
+        if rem.is_empty() {
+            Ok(dbox)
+        } else {
+            Err(Error::TrailingData {
+                offset: i.len() - rem.len(),
+                len: rem.len(),
+            })
+        }
+    }
 }
 
 impl<'a> Debug for DataBox<'a> {
@@ -13,19 +13,24 @@
 
 use std::{
     fmt::{Debug, Formatter},
+    io::{Result as IoResult, Write},
     str::from_utf8,
 };
 
 use nom::{
     bytes::complete::take_until,
     number::complete::{be_u32, be_u8},
-    Needed,
 };
 
 use crate::{
     box_type::DESCRIPTION_BOX_TYPE,
+    content_type::ContentType,
     debug::*,
-    parser::{DataBox, Error, ParseResult},
+    parser::{
+        data_box::{header_len_like, write_header_like, HeaderEncoding},
+        DataBox, Error, ParseOptions, ParseResult,
+    },
+    toggles::Toggles,
 };
 
 /// A JUMBF description box describes the contents of its superbox.
@@ -75,6 +80,74 @@ impl<'a> DescriptionBox<'a> {
         Ok((i, desc))
     }
 
+    // Locate the description box within the payload of a superbox.
+    //
+    // Returns the portion of the payload from which child boxes should be
+    // parsed and the description box. When the description box is
+    // misplaced and `options` allows it, that is the entire payload and
+    // the caller must skip the description box itself.
+    pub(crate) fn from_super_box_payload(
+        payload: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<(&'a [u8], Self), Error> {
+        if payload.is_empty() {
+            return Self::missing(payload, options);
+        }
+
+        let (i, first) = DataBox::from_bounded_slice(payload)?;
+        if first.tbox == DESCRIPTION_BOX_TYPE {
+            let (_, desc) = Self::from_box_with_options(first, options)?;
+            return Ok((i, desc));
+        }
+
+        let mut i = i;
+        while !i.is_empty() {
+            let (x, d) = match DataBox::from_bounded_slice(i) {
+                Ok(result) => result,
+                Err(_) if options.lenient_description_box => break,
+                Err(err) => {
+                    // The description box may be beyond the corrupt box, so
+                    // report that rather than a missing description box.
+                    let offset = i.as_ptr() as usize - payload.as_ptr() as usize;
+                    return Err(Error::from(err).offset_by(offset));
+                }
+            };
+
+            if d.tbox == DESCRIPTION_BOX_TYPE {
+                if !options.lenient_description_box {
+                    return Err(Error::MisplacedDescriptionBox);
+                }
+
+                let offset = d.original.as_ptr() as usize - payload.as_ptr() as usize;
+                let (_, desc) = Self::from_box_with_options(d, options)
+                    .map_err(|err| Error::from(err).offset_by(offset))?;
+                return Ok((payload, desc));
+            }
+            i = x;
+        }
+
+        Self::missing(payload, options)
+    }
+
+    fn missing(payload: &'a [u8], options: &ParseOptions) -> Result<(&'a [u8], Self), Error> {
+        if !options.lenient_description_box {
+            return Err(Error::MissingDescriptionBox);
+        }
+
+        Ok((
+            payload,
+            Self {
+                uuid: &[0; 16],
+                label: None,
+                requestable: false,
+                id: None,
+                hash: None,
+                private: None,
+                original: &[],
+            },
+        ))
+    }
+
     /// Convert an existing JUMBF box to a JUMBF description box.
     ///
     /// This consumes the existing [`DataBox`] object and will return an
@@ -84,41 +157,70 @@ impl<'a> DescriptionBox<'a> {
     /// Returns a tuple of the remainder of the input from the box (which should
     /// typically be empty) and the new [`DescriptionBox`] object.
     pub fn from_box(boxx: DataBox<'a>) -> ParseResult<'a, Self> {
-        use crate::toggles;
+        Self::from_box_with_options(boxx, &ParseOptions::default())
+    }
 
+    /// Convert an existing JUMBF box to a JUMBF description box, following
+    /// the limits described by `options`.
+    ///
+    /// Otherwise behaves like [`from_box()`].
+    ///
+    /// [`from_box()`]: Self::from_box
+    pub fn from_box_with_options(
+        boxx: DataBox<'a>,
+        options: &ParseOptions,
+    ) -> ParseResult<'a, Self> {
         if boxx.tbox != DESCRIPTION_BOX_TYPE {
             return Err(nom::Err::Error(Error::InvalidDescriptionBoxType(boxx.tbox)));
         }
 
+        // The box itself is complete, so any field that doesn't fit within
+        // it means that the box is malformed.
+        let corrupt = || nom::Err::Error(Error::CorruptHeader { offset: 0 });
+
         let (i, uuid): (&'a [u8], &'a [u8; 16]) = if boxx.data.len() >= 16 {
             let (uuid, i) = boxx.data.split_at(16);
-            let uuid = uuid[0..16]
-                .try_into()
-                .map_err(|_| nom::Err::Error(Error::Incomplete(Needed::new(16))))?;
+            let uuid = uuid[0..16].try_into().map_err(|_| corrupt())?;
             (i, uuid)
         } else {
-            return Err(nom::Err::Error(Error::Incomplete(Needed::new(16))));
+            return Err(corrupt());
         };
 
-        let (i, toggles) = be_u8(i)?;
+        let (i, toggles) = be_u8(i).map_err(|_: nom::Err<Error>| corrupt())?;
+        let toggles = Toggles::from_bits(toggles);
 
         // Toggle bit 0 (0x01) indicates that this superbox can be requested
         // via URI requests.
-        let requestable = toggles & toggles::REQUESTABLE != 0;
+        let requestable = toggles.contains(Toggles::REQUESTABLE);
 
         // Toggle bit 1 (0x02) indicates that the label has an optional textual label.
-        let (i, label) = if toggles & toggles::HAS_LABEL != 0 {
-            let (i, label) = take_until("\0")(i)?;
-            let label = from_utf8(label).map_err(Error::Utf8Error)?;
-            (&i[1..], Some(label))
+        let (i, label) = if toggles.contains(Toggles::HAS_LABEL) {
+            // Don't scan beyond the maximum label length for the terminator.
+            let max_label_len = options.max_label_len.unwrap_or(usize::MAX);
+            let search = &i[..i.len().min(max_label_len.saturating_add(1))];
+            let (_, label) = take_until("\0")(search).map_err(|_: nom::Err<Error>| {
+                if search.len() < i.len() {
+                    nom::Err::Error(Error::LabelTooLong(max_label_len))
+                } else {
+                    corrupt()
+                }
+            })?;
+            let i = &i[label.len()..];
+            let label = match from_utf8(label) {
+                Ok(label) => Some(label),
+                // The raw label remains available via `raw_label()`.
+                Err(_) if options.lenient_label_encoding => None,
+                Err(err) => return Err(nom::Err::Error(Error::Utf8Error(err))),
+            };
+            (&i[1..], label)
         } else {
             (i, None)
         };
 
         // Toggle bit 2 (0x04) indicates that the label has an optional
         // application-specific 32-bit identifier.
-        let (i, id) = if toggles & toggles::HAS_ID != 0 {
-            let (i, id) = be_u32(i)?;
+        let (i, id) = if toggles.contains(Toggles::HAS_ID) {
+            let (i, id) = be_u32(i).map_err(|_: nom::Err<Error>| corrupt())?;
             (i, Some(id))
         } else {
             (i, None)
@@ -126,15 +228,13 @@ impl<'a> DescriptionBox<'a> {
 
         // Toggle bit 3 (0x08) indicates that a SHA-256 hash of the superbox's
         // data box is present.
-        let (i, hash) = if toggles & toggles::HAS_HASH != 0 {
+        let (i, hash) = if toggles.contains(Toggles::HAS_HASH) {
             let (x, sig): (&'a [u8], &'a [u8; 32]) = if i.len() >= 32 {
                 let (sig, x) = i.split_at(32);
-                let sig = sig[0..32]
-                    .try_into()
-                    .map_err(|_| nom::Err::Error(Error::Incomplete(Needed::new(32))))?;
+                let sig = sig[0..32].try_into().map_err(|_| corrupt())?;
                 (x, sig)
             } else {
-                return Err(nom::Err::Error(Error::Incomplete(Needed::new(32))));
+                return Err(corrupt());
             };
 
             (x, Some(sig))
@@ -144,8 +244,10 @@ impl<'a> DescriptionBox<'a> {
 
         // Toggle bit 4 (0x10) indicates that an application-specific "private"
         // box is contained within the description box.
-        let (i, private) = if toggles & toggles::HAS_PRIVATE_BOX != 0 {
-            let (i, private) = DataBox::from_slice(i)?;
+        let (i, private) = if toggles.contains(Toggles::HAS_PRIVATE_BOX) {
+            let offset = i.as_ptr() as usize - boxx.original.as_ptr() as usize;
+            let (i, private) = DataBox::from_bounded_slice(i)
+                .map_err(|err| nom::Err::Error(Error::from(err).offset_by(offset)))?;
             (i, Some(private))
         } else {
             (i, None)
@@ -166,6 +268,269 @@ impl<'a> DescriptionBox<'a> {
     }
 }
 
+impl<'a> DescriptionBox<'a> {
+    /// Serialize this description box to a JUMBF stream.
+    ///
+    /// If the box is unmodified since it was parsed, the [`original`] bytes
+    /// are written as is. Otherwise, the box is re-serialized using the same
+    /// header encoding as the original box, with toggle bits computed from
+    /// the fields of this struct as described for [`toggles()`]. Any
+    /// reserved toggle bits that were set in the original box are
+    /// preserved.
+    ///
+    /// This allows a label, ID, or hash to be changed without rebuilding
+    /// the box using the [`builder`] module. A label which isn't valid UTF-8
+    /// (see [`raw_label()`]) is written back unchanged unless [`label`] is
+    /// set to replace it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::DescriptionBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000001d" // box size
+    ///     "6a756d64" // box type = 'jumd'
+    ///     "00000000000000000000000000000000" // UUID
+    ///     "03" // toggles
+    ///     "6f6c6400" // label = "old"
+    /// );
+    ///
+    /// let (_, mut desc) = DescriptionBox::from_slice(&jumbf).unwrap();
+    /// desc.label = Some("new.label");
+    /// desc.id = Some(7);
+    ///
+    /// assert_eq!(
+    ///     desc.to_vec(),
+    ///     hex!(
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "07" // toggles
+    ///         "6e65772e6c6162656c00" // label = "new.label"
+    ///         "00000007" // ID
+    ///     )
+    /// );
+    /// ```
+    ///
+    /// [`original`]: Self::original
+    /// [`toggles()`]: Self::toggles
+    /// [`builder`]: crate::builder
+    /// [`raw_label()`]: Self::raw_label
+    /// [`label`]: Self::label
+    pub fn write_to(&self, to_stream: &mut dyn Write) -> IoResult<()> {
+        if self.is_unmodified() {
+            return to_stream.write_all(self.original);
+        }
+
+        let payload = self.payload();
+        write_header_like(
+            self.original,
+            DESCRIPTION_BOX_TYPE,
+            payload.len(),
+            to_stream,
+        )?;
+        to_stream.write_all(&payload)
+    }
+
+    /// Serialize this description box to a new byte vector.
+    ///
+    /// See [`write_to()`] for a description of how the box is serialized.
+    ///
+    /// [`write_to()`]: Self::write_to
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+
+        // Writing to a Vec can't fail.
+        #[allow(clippy::unwrap_used)]
+        self.write_to(&mut result).unwrap();
+
+        result
+    }
+
+    /// Returns the number of bytes this description box occupies when
+    /// serialized.
+    ///
+    /// If the box has not been modified since it was parsed, this is the
+    /// length of [`original`]. Otherwise, it is the number of bytes that
+    /// [`write_to()`] will write.
+    ///
+    /// [`original`]: Self::original
+    /// [`write_to()`]: Self::write_to
+    pub fn serialized_len(&self) -> usize {
+        if self.is_unmodified() {
+            self.original.len()
+        } else {
+            let payload_len = self.payload().len();
+            header_len_like(self.original, payload_len) + payload_len
+        }
+    }
+
+    // Returns true if the fields of this struct still describe `original`.
+    //
+    // Fields which borrow from `original` are compared by address, as for
+    // `DataBox`, so `original` doesn't have to be parsed again.
+    fn is_unmodified(&self) -> bool {
+        let header_len = HeaderEncoding::of(self.original).header_len();
+        let Some((uuid, toggles, mut rest)) = self
+            .original
+            .get(header_len..)
+            .filter(|payload| payload.len() > 16)
+            .map(|payload| (&payload[..16], payload[16], &payload[17..]))
+        else {
+            return false;
+        };
+
+        if self.uuid.as_ptr() != uuid.as_ptr() || self.toggles().bits() != toggles {
+            return false;
+        }
+
+        if let Some(label) = self.raw_label() {
+            if label.as_ptr() != rest.as_ptr() || rest.get(label.len()) != Some(&0) {
+                return false;
+            }
+            rest = &rest[label.len() + 1..];
+        }
+
+        if let Some(id) = self.id {
+            if rest.get(..4) != Some(&id.to_be_bytes()[..]) {
+                return false;
+            }
+            rest = &rest[4..];
+        }
+
+        if let Some(hash) = self.hash {
+            if hash.as_ptr() != rest.as_ptr() || rest.len() < 32 {
+                return false;
+            }
+            rest = &rest[32..];
+        }
+
+        match self.private.as_ref() {
+            Some(private) => {
+                private.is_unmodified()
+                    && private.original.as_ptr() == rest.as_ptr()
+                    && private.original.len() <= rest.len()
+            }
+            None => true,
+        }
+    }
+
+    /// Returns the label of this description box as raw bytes.
+    ///
+    /// This is the same as [`label`] when the label is valid UTF-8. If the
+    /// label isn't valid UTF-8 and was parsed with
+    /// [`ParseOptions::set_lenient_label_encoding()`], [`label`] will be
+    /// `None` and the original bytes of the label (without the NUL
+    /// terminator) are returned here instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{DataBox, DescriptionBox, ParseOptions};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000001e" // box size
+    ///     "6a756d64" // box type = 'jumd'
+    ///     "00000000000000000000000000000000" // UUID
+    ///     "03" // toggles
+    ///     "6162ff6400" // label (not valid UTF-8)
+    /// );
+    ///
+    /// let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+    /// let options = ParseOptions::default().set_lenient_label_encoding(true);
+    /// let (_, desc) = DescriptionBox::from_box_with_options(boxx, &options).unwrap();
+    ///
+    /// assert_eq!(desc.label, None);
+    /// assert_eq!(desc.raw_label(), Some(&b"ab\xffd"[..]));
+    /// ```
+    ///
+    /// [`label`]: Self::label
+    /// [`ParseOptions::set_lenient_label_encoding()`]: crate::parser::ParseOptions::set_lenient_label_encoding
+    pub fn raw_label(&self) -> Option<&'a [u8]> {
+        if let Some(label) = self.label {
+            return Some(label.as_bytes());
+        }
+
+        // Only report the original label if it couldn't be decoded. Otherwise,
+        // the label has been removed since this box was parsed.
+        let raw = self.original_label()?;
+        from_utf8(raw).is_err().then_some(raw)
+    }
+
+    // Find the label bytes within `original`, if any.
+    fn original_label(&self) -> Option<&'a [u8]> {
+        let toggles_offset = HeaderEncoding::of(self.original).header_len() + 16;
+        let toggles = Toggles::from_bits(*self.original.get(toggles_offset)?);
+        if !toggles.contains(Toggles::HAS_LABEL) {
+            return None;
+        }
+
+        let rest = self.original.get(toggles_offset + 1..)?;
+        let len = rest.iter().position(|b| *b == 0)?;
+        Some(&rest[..len])
+    }
+
+    /// Returns the well-known content type identified by [`uuid`], if any.
+    ///
+    /// [`uuid`]: Self::uuid
+    pub fn content_type(&self) -> Option<&'static ContentType> {
+        ContentType::from_uuid(self.uuid)
+    }
+
+    /// Returns the toggles which describe this description box.
+    ///
+    /// These are computed from the fields of this struct. Any reserved
+    /// toggle bits in [`original`] are also included, so this is the toggles
+    /// byte that will be written if this box is re-serialized.
+    ///
+    /// [`original`]: Self::original
+    pub fn toggles(&self) -> Toggles {
+        // Preserve any reserved bits from the original box.
+        let toggles_offset = HeaderEncoding::of(self.original).header_len() + 16;
+        let mut t = self
+            .original
+            .get(toggles_offset)
+            .map_or(Toggles::empty(), |t| {
+                Toggles::from_bits(*t) & Toggles::RESERVED
+            });
+
+        t.set(Toggles::REQUESTABLE, self.requestable);
+        t.set(Toggles::HAS_LABEL, self.raw_label().is_some());
+        t.set(Toggles::HAS_ID, self.id.is_some());
+        t.set(Toggles::HAS_HASH, self.hash.is_some());
+        t.set(Toggles::HAS_PRIVATE_BOX, self.private.is_some());
+
+        t
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut payload: Vec<u8> = self.uuid.to_vec();
+        payload.push(self.toggles().bits());
+
+        if let Some(label) = self.raw_label() {
+            payload.extend_from_slice(label);
+            payload.push(0);
+        }
+
+        if let Some(id) = self.id {
+            payload.extend_from_slice(&id.to_be_bytes());
+        }
+
+        if let Some(hash) = self.hash {
+            payload.extend_from_slice(hash);
+        }
+
+        if let Some(private) = self.private.as_ref() {
+            payload.extend_from_slice(&private.to_vec());
+        }
+
+        payload
+    }
+}
+
 impl<'a> Debug for DescriptionBox<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.debug_struct("DescriptionBox")
@@ -0,0 +1,487 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! An alternate JUMBF parser which produces detailed diagnostics.
+//!
+//! The parser in this module produces the same [`SuperBox`] and [`DataBox`]
+//! structures as the default parser, but when it encounters malformed data,
+//! it returns a [`Diagnostic`] describing what was expected, what was found,
+//! where the problem was found, and which boxes enclose that location. This
+//! is intended for tools which need to explain problems in JUMBF data to
+//! people.
+//!
+//! The default parser (i.e. [`SuperBox::from_slice()`]) is faster and
+//! should be preferred when detailed error reports aren't needed.
+//!
+//! This module is built on the [winnow] parser combinator framework and is
+//! available when the `diagnostics` crate feature is enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! use hex_literal::hex;
+//! use jumbf::parser::diagnostics;
+//!
+//! let jumbf = hex!(
+//!     "0000002f" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "00000027" // box size
+//!         "6a756d63" // box type = 'jumc' (INCORRECT)
+//!         "00000000000000000000000000000000" // UUID
+//!         "03" // toggles
+//!         "746573742e7375706572626f7800" // label
+//! );
+//!
+//! let err = diagnostics::parse_super_box(&jumbf).unwrap_err();
+//! assert_eq!(err.offset, 12);
+//!
+//! assert_eq!(
+//!     err.to_string(),
+//!     "at offset 12: expected box type b\"jumd\", found b\"jumc\"\n  \
+//!      in description box at offset 8\n  \
+//!      in superbox at offset 0"
+//! );
+//! ```
+//!
+//! [`SuperBox::from_slice()`]: crate::parser::SuperBox::from_slice
+//! [winnow]: https://docs.rs/winnow
+
+use std::{
+    fmt::{Display, Formatter},
+    str::from_utf8,
+};
+
+use winnow::{
+    binary::{be_u32, be_u64, u8 as be_u8},
+    error::{AddContext, ParserError},
+    stream::Stream,
+    token::{take, take_till},
+    Parser,
+};
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    parser::{ChildBox, DataBox, DescriptionBox, ParseOptions, SuperBox},
+    BoxType, Toggles,
+};
+
+/// Describes a problem found while parsing JUMBF data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// Offset within the input at which the problem was found.
+    pub offset: usize,
+
+    /// Description of what was expected at that offset.
+    pub expected: String,
+
+    /// Description of what was found instead.
+    pub found: String,
+
+    /// The boxes which enclose the problem, innermost first.
+    pub context: Vec<DiagnosticContext>,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "at offset {}: expected {}, found {}",
+            self.offset, self.expected, self.found
+        )?;
+
+        for context in &self.context {
+            write!(
+                f,
+                "\n  in {} at offset {}",
+                context.description, context.offset
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Describes a box which encloses the location of a [`Diagnostic`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiagnosticContext {
+    /// Offset within the input at which the box begins.
+    pub offset: usize,
+
+    /// Description of the box (i.e. `superbox "c2pa"`).
+    pub description: String,
+}
+
+/// Parse a JUMBF superbox and all of its descendants.
+///
+/// Returns a tuple of the remainder of the input and the parsed superbox,
+/// or a [`Diagnostic`] describing the first problem found.
+///
+/// Child superboxes are parsed recursively to a limit of
+/// [`DEFAULT_DEPTH_LIMIT`] nested boxes, as by [`SuperBox::from_slice()`].
+///
+/// [`DEFAULT_DEPTH_LIMIT`]: ParseOptions::DEFAULT_DEPTH_LIMIT
+/// [`SuperBox::from_slice()`]: crate::parser::SuperBox::from_slice
+pub fn parse_super_box(input: &[u8]) -> Result<(&[u8], SuperBox<'_>), Diagnostic> {
+    parse_super_box_with_options(input, &ParseOptions::default())
+}
+
+/// Parse a JUMBF superbox and its descendants as described by `options`.
+///
+/// Only the depth limit (see [`ParseOptions::set_depth_limit()`]) and the
+/// maximum label length (see [`ParseOptions::set_max_label_len()`]) are
+/// used. Child superboxes beyond the depth limit are returned as plain
+/// [`DataBox`] structs.
+///
+/// ## Example
+///
+/// ```
+/// use hex_literal::hex;
+/// use jumbf::parser::{diagnostics, ParseOptions};
+///
+/// let jumbf = hex!(
+///     "0000002f" // box size
+///     "6a756d62" // box type = 'jumb'
+///         "00000027" // box size
+///         "6a756d64" // box type = 'jumd'
+///         "00000000000000000000000000000000" // UUID
+///         "03" // toggles
+///         "746573742e7375706572626f7800" // label
+/// );
+///
+/// let options = ParseOptions::default().set_max_label_len(8);
+/// let err = diagnostics::parse_super_box_with_options(&jumbf, &options).unwrap_err();
+///
+/// assert_eq!(err.offset, 33);
+/// assert_eq!(err.expected, "label of at most 8 bytes");
+/// ```
+pub fn parse_super_box_with_options<'a>(
+    input: &'a [u8],
+    options: &ParseOptions,
+) -> Result<(&'a [u8], SuperBox<'a>), Diagnostic> {
+    let mut i = input;
+    let sbox = super_box(&mut i, options, 0).map_err(|trace| trace.into_diagnostic(input))?;
+    Ok((i, sbox))
+}
+
+/// Parse a single JUMBF box without interpreting its contents.
+///
+/// Returns a tuple of the remainder of the input and the parsed box, or a
+/// [`Diagnostic`] describing the problem found.
+pub fn parse_data_box(input: &[u8]) -> Result<(&[u8], DataBox<'_>), Diagnostic> {
+    let mut i = input;
+    let dbox = data_box(&mut i).map_err(|trace| trace.into_diagnostic(input))?;
+    Ok((i, dbox))
+}
+
+// Error type used while parsing. Locations are recorded as addresses and
+// converted to offsets once the start of the input is known.
+#[derive(Debug)]
+struct Trace {
+    at: usize,
+    expected: Option<String>,
+    found: String,
+    context: Vec<(usize, String)>,
+}
+
+impl Trace {
+    fn new(at: &[u8], expected: impl Into<String>, found: impl Into<String>) -> Self {
+        Self {
+            at: at.as_ptr() as usize,
+            expected: Some(expected.into()),
+            found: found.into(),
+            context: vec![],
+        }
+    }
+
+    fn within(mut self, start: &[u8], description: impl Into<String>) -> Self {
+        self.context
+            .push((start.as_ptr() as usize, description.into()));
+        self
+    }
+
+    fn into_diagnostic(self, input: &[u8]) -> Diagnostic {
+        let base = input.as_ptr() as usize;
+
+        Diagnostic {
+            offset: self.at.saturating_sub(base),
+            expected: self.expected.unwrap_or_else(|| "more data".to_owned()),
+            found: self.found,
+            context: self
+                .context
+                .into_iter()
+                .map(|(at, description)| DiagnosticContext {
+                    offset: at.saturating_sub(base),
+                    description,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<'a> ParserError<&'a [u8]> for Trace {
+    type Inner = Self;
+
+    fn from_input(input: &&'a [u8]) -> Self {
+        Self {
+            at: input.as_ptr() as usize,
+            expected: None,
+            found: remaining(input),
+            context: vec![],
+        }
+    }
+
+    fn into_inner(self) -> Result<Self, Self> {
+        Ok(self)
+    }
+}
+
+impl<'a> AddContext<&'a [u8], &'static str> for Trace {
+    fn add_context(
+        mut self,
+        _input: &&'a [u8],
+        _token_start: &<&'a [u8] as Stream>::Checkpoint,
+        context: &'static str,
+    ) -> Self {
+        if self.expected.is_none() {
+            self.expected = Some(context.to_owned());
+        }
+        self
+    }
+}
+
+type PResult<T> = Result<T, Trace>;
+
+fn remaining(i: &[u8]) -> String {
+    match i.len() {
+        0 => "end of data".to_owned(),
+        1 => "only 1 byte".to_owned(),
+        n => format!("only {n} bytes"),
+    }
+}
+
+fn data_box<'a>(i: &mut &'a [u8]) -> PResult<DataBox<'a>> {
+    let start = *i;
+
+    let len = be_u32.context("32-bit box length").parse_next(i)?;
+    let tbox: &[u8] = take(4usize).context("4-byte box type").parse_next(i)?;
+
+    let (header_len, payload_len) = match len {
+        0 => (8, i.len()),
+        1 => {
+            let xl_start = *i;
+            let xl_len = be_u64.context("64-bit extended box length").parse_next(i)?;
+            if xl_len < 16 {
+                return Err(Trace::new(
+                    xl_start,
+                    "extended box length of at least 16",
+                    xl_len.to_string(),
+                ));
+            }
+
+            let payload_len = usize::try_from(xl_len - 16).map_err(|_| {
+                Trace::new(
+                    xl_start,
+                    "extended box length that fits in memory",
+                    xl_len.to_string(),
+                )
+            })?;
+            (16, payload_len)
+        }
+        2..=7 => {
+            return Err(Trace::new(
+                start,
+                "box length of 0, 1, or at least 8",
+                len.to_string(),
+            ));
+        }
+        len => (8, len as usize - 8),
+    };
+
+    if i.len() < payload_len {
+        return Err(Trace::new(
+            i,
+            format!("{payload_len} bytes of box payload"),
+            remaining(i),
+        ));
+    }
+
+    let data: &[u8] = take(payload_len).parse_next(i)?;
+
+    Ok(DataBox {
+        tbox: tbox.into(),
+        data,
+        original: &start[0..header_len + payload_len],
+    })
+}
+
+fn expect_box_type(start: &[u8], dbox: &DataBox, tbox: BoxType) -> PResult<()> {
+    if dbox.tbox == tbox {
+        Ok(())
+    } else {
+        Err(Trace::new(
+            &start[4..],
+            format!("box type {tbox:?}"),
+            format!("{:?}", dbox.tbox),
+        ))
+    }
+}
+
+fn description_box<'a>(i: &mut &'a [u8], options: &ParseOptions) -> PResult<DescriptionBox<'a>> {
+    let start = *i;
+
+    description_box_inner(i, options).map_err(|trace| trace.within(start, "description box"))
+}
+
+fn description_box_inner<'a>(
+    i: &mut &'a [u8],
+    options: &ParseOptions,
+) -> PResult<DescriptionBox<'a>> {
+    let start = *i;
+    let dbox = data_box(i)?;
+    expect_box_type(start, &dbox, DESCRIPTION_BOX_TYPE)?;
+
+    let mut data = dbox.data;
+    let d = &mut data;
+
+    let uuid: &[u8] = take(16usize).context("16-byte UUID").parse_next(d)?;
+    let uuid: &[u8; 16] = uuid
+        .try_into()
+        .map_err(|_| Trace::new(uuid, "16-byte UUID", remaining(uuid)))?;
+
+    let toggles = Toggles::from_bits(be_u8.context("toggles byte").parse_next(d)?);
+
+    let label = if toggles.contains(Toggles::HAS_LABEL) {
+        let label_start = *d;
+
+        // Don't scan beyond the maximum label length for the terminator.
+        if let Some(max_label_len) = options.max_label_len {
+            let search = &d[..d.len().min(max_label_len.saturating_add(1))];
+            if search.len() < d.len() && !search.contains(&0) {
+                return Err(Trace::new(
+                    label_start,
+                    format!("label of at most {max_label_len} bytes"),
+                    "a longer label",
+                ));
+            }
+        }
+
+        let label: &[u8] = take_till(0.., 0u8).parse_next(d)?;
+        if d.is_empty() {
+            return Err(Trace::new(d, "NUL terminator for label", "end of data"));
+        }
+        *d = &d[1..];
+
+        let label = from_utf8(label).map_err(|e| {
+            Trace::new(
+                &label_start[e.valid_up_to()..],
+                "UTF-8 label",
+                "invalid UTF-8 sequence",
+            )
+        })?;
+        Some(label)
+    } else {
+        None
+    };
+
+    let id = if toggles.contains(Toggles::HAS_ID) {
+        Some(be_u32.context("32-bit ID").parse_next(d)?)
+    } else {
+        None
+    };
+
+    let hash = if toggles.contains(Toggles::HAS_HASH) {
+        let hash: &[u8] = take(32usize).context("32-byte hash").parse_next(d)?;
+        Some(
+            hash.try_into()
+                .map_err(|_| Trace::new(hash, "32-byte hash", remaining(hash)))?,
+        )
+    } else {
+        None
+    };
+
+    let private = if toggles.contains(Toggles::HAS_PRIVATE_BOX) {
+        let private_start = *d;
+        Some(data_box(d).map_err(|trace| trace.within(private_start, "private box"))?)
+    } else {
+        None
+    };
+
+    Ok(DescriptionBox {
+        uuid,
+        label,
+        requestable: toggles.contains(Toggles::REQUESTABLE),
+        id,
+        hash,
+        private,
+        original: dbox.original,
+    })
+}
+
+fn super_box<'a>(i: &mut &'a [u8], options: &ParseOptions, depth: usize) -> PResult<SuperBox<'a>> {
+    let start = *i;
+    let mut description = "superbox".to_owned();
+
+    super_box_inner(i, options, depth, &mut description)
+        .map_err(|trace| trace.within(start, description))
+}
+
+fn super_box_inner<'a>(
+    i: &mut &'a [u8],
+    options: &ParseOptions,
+    depth: usize,
+    description: &mut String,
+) -> PResult<SuperBox<'a>> {
+    let start = *i;
+    let dbox = data_box(i)?;
+    expect_box_type(start, &dbox, SUPER_BOX_TYPE)?;
+
+    let mut data = dbox.data;
+    let payload = &mut data;
+    let desc = description_box(payload, options)?;
+
+    if let Some(label) = desc.label {
+        *description = format!("superbox {label:?}");
+    }
+
+    let mut child_boxes: Vec<ChildBox<'a>> = vec![];
+
+    while !payload.is_empty() {
+        let child_start = *payload;
+        let child = data_box(payload)?;
+
+        if child.tbox == SUPER_BOX_TYPE && depth < options.depth_limit {
+            let mut child_i = child_start;
+            child_boxes.push(ChildBox::SuperBox(super_box(
+                &mut child_i,
+                options,
+                depth + 1,
+            )?));
+        } else if child.tbox == DESCRIPTION_BOX_TYPE {
+            return Err(Trace::new(
+                child_start,
+                "a single description box",
+                "another description box",
+            ));
+        } else {
+            child_boxes.push(ChildBox::DataBox(child));
+        }
+    }
+
+    Ok(SuperBox {
+        desc,
+        child_boxes,
+        original: dbox.original,
+    })
+}
@@ -0,0 +1,190 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::fmt::{Debug, Formatter};
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    debug::*,
+    parser::{ChildBox, LabelPath, SuperBox},
+    BoxType,
+};
+
+/// Describes one box which differs between two superboxes, as returned by
+/// [`SuperBox::diff()`].
+///
+/// [`SuperBox::diff()`]: crate::parser::SuperBox::diff
+#[derive(Clone, Eq, PartialEq)]
+pub struct BoxDiff<'a> {
+    /// Path to the box.
+    ///
+    /// For a superbox, or for the description box of a superbox, this is the
+    /// path to that superbox. For any other box, this is the path to the
+    /// superbox which contains it. Unlabeled superboxes contribute an empty
+    /// component.
+    pub path: LabelPath,
+
+    /// Box type of the box.
+    ///
+    /// A changed description box is reported with the `jumd` box type. A
+    /// superbox is only reported (with the `jumb` box type) if it was added
+    /// or removed; otherwise, the changes within it are reported.
+    pub tbox: BoxType,
+
+    /// Distinguishes this box from earlier siblings with the same box type
+    /// (or, for superboxes, the same label). The first such box is `0`.
+    pub index: usize,
+
+    /// Original bytes of the box in the old superbox, if it exists there.
+    pub old: Option<&'a [u8]>,
+
+    /// Original bytes of the box in the new superbox, if it exists there.
+    pub new: Option<&'a [u8]>,
+}
+
+impl<'a> BoxDiff<'a> {
+    /// Returns the kind of change this describes.
+    pub fn kind(&self) -> DiffKind {
+        match (self.old, self.new) {
+            (None, _) => DiffKind::Added,
+            (_, None) => DiffKind::Removed,
+            _ => DiffKind::Changed,
+        }
+    }
+}
+
+impl<'a> Debug for BoxDiff<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("BoxDiff")
+            .field("path", &self.path)
+            .field("tbox", &self.tbox)
+            .field("index", &self.index)
+            .field("old", &self.old.map(DebugByteSlice))
+            .field("new", &self.new.map(DebugByteSlice))
+            .finish()
+    }
+}
+
+/// The kind of change described by a [`BoxDiff`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffKind {
+    /// The box exists only in the new superbox.
+    Added,
+
+    /// The box exists only in the old superbox.
+    Removed,
+
+    /// The box exists in both superboxes, but its content differs.
+    Changed,
+}
+
+// Identifies a child box among its siblings.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum ChildKey<'a> {
+    SuperBox(Option<&'a str>),
+    DataBox(BoxType),
+}
+
+fn keyed_children<'s, 'a>(sbox: &'s SuperBox<'a>) -> Vec<(ChildKey<'a>, usize, &'s ChildBox<'a>)> {
+    let mut keyed: Vec<(ChildKey<'a>, usize, &'s ChildBox<'a>)> = vec![];
+
+    for child in &sbox.child_boxes {
+        let key = match child {
+            ChildBox::SuperBox(sbox) => ChildKey::SuperBox(sbox.desc.label),
+            ChildBox::DataBox(dbox) => ChildKey::DataBox(dbox.tbox),
+        };
+        let index = keyed.iter().filter(|(k, _, _)| *k == key).count();
+        keyed.push((key, index, child));
+    }
+
+    keyed
+}
+
+fn child_path(path: &LabelPath, sbox: &SuperBox) -> LabelPath {
+    let mut path = path.clone();
+    path.push(sbox.desc.label.unwrap_or_default());
+    path
+}
+
+pub(crate) fn diff_super_boxes<'a>(
+    path: &LabelPath,
+    old: &SuperBox<'a>,
+    new: &SuperBox<'a>,
+    diffs: &mut Vec<BoxDiff<'a>>,
+) {
+    if old.desc.original != new.desc.original {
+        diffs.push(BoxDiff {
+            path: path.clone(),
+            tbox: DESCRIPTION_BOX_TYPE,
+            index: 0,
+            old: Some(old.desc.original),
+            new: Some(new.desc.original),
+        });
+    }
+
+    let old_children = keyed_children(old);
+    let new_children = keyed_children(new);
+
+    for (key, index, old_child) in &old_children {
+        let new_child = new_children
+            .iter()
+            .find(|(k, i, _)| k == key && i == index)
+            .map(|(_, _, child)| *child);
+
+        match (old_child, new_child) {
+            (ChildBox::SuperBox(old_sbox), Some(ChildBox::SuperBox(new_sbox))) => {
+                if old_sbox.original != new_sbox.original {
+                    diff_super_boxes(&child_path(path, old_sbox), old_sbox, new_sbox, diffs);
+                }
+            }
+            (ChildBox::DataBox(old_dbox), Some(ChildBox::DataBox(new_dbox))) => {
+                if old_dbox.original != new_dbox.original {
+                    diffs.push(BoxDiff {
+                        path: path.clone(),
+                        tbox: old_dbox.tbox,
+                        index: *index,
+                        old: Some(old_dbox.original),
+                        new: Some(new_dbox.original),
+                    });
+                }
+            }
+            _ => diffs.push(removed_or_added(path, *index, old_child, true)),
+        }
+    }
+
+    for (key, index, new_child) in &new_children {
+        if !old_children.iter().any(|(k, i, _)| k == key && i == index) {
+            diffs.push(removed_or_added(path, *index, new_child, false));
+        }
+    }
+}
+
+fn removed_or_added<'a>(
+    path: &LabelPath,
+    index: usize,
+    child: &ChildBox<'a>,
+    removed: bool,
+) -> BoxDiff<'a> {
+    let (path, tbox, original) = match child {
+        ChildBox::SuperBox(sbox) => (child_path(path, sbox), SUPER_BOX_TYPE, sbox.original),
+        ChildBox::DataBox(dbox) => (path.clone(), dbox.tbox, dbox.original),
+    };
+
+    BoxDiff {
+        path,
+        tbox,
+        index,
+        old: removed.then_some(original),
+        new: (!removed).then_some(original),
+    }
+}
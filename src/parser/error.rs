@@ -27,6 +27,10 @@ pub enum Error {
     #[error("Box length value {0} is reserved")]
     InvalidBoxLength(u32),
 
+    /// Box length value is too large to be addressed on this platform.
+    #[error("Box length value {0} is too large")]
+    BoxLengthOverflow(u64),
+
     /// Not a description box.
     #[error("Superbox box type should be 'jumb', was '{0:#?}'")]
     InvalidSuperBoxType(BoxType),
@@ -35,19 +39,164 @@ pub enum Error {
     #[error("Description box type should be 'jumd', was '{0:#?}'")]
     InvalidDescriptionBoxType(BoxType),
 
+    /// Superbox does not contain a description box.
+    #[error("Superbox does not contain a description box")]
+    MissingDescriptionBox,
+
+    /// Superbox contains a description box, but it is not the first child
+    /// box.
+    #[error("Description box is not the first box in the superbox")]
+    MisplacedDescriptionBox,
+
+    /// Superbox contains more than one description box.
+    #[error("Superbox contains more than one description box")]
+    DuplicateDescriptionBox,
+
+    /// Unexpected data follows the top-level superbox.
+    ///
+    /// Only reported when [`ParseOptions::set_reject_trailing_data()`] is
+    /// enabled.
+    ///
+    /// [`ParseOptions::set_reject_trailing_data()`]: crate::parser::ParseOptions::set_reject_trailing_data
+    #[error("{len} bytes of unexpected data follow the superbox at offset {offset}")]
+    TrailingData {
+        /// Offset of the first byte after the top-level superbox.
+        offset: usize,
+
+        /// Number of bytes after the top-level superbox.
+        len: usize,
+    },
+
+    /// Description box label is longer than the limit set by
+    /// [`ParseOptions::set_max_label_len()`].
+    ///
+    /// [`ParseOptions::set_max_label_len()`]: crate::parser::ParseOptions::set_max_label_len
+    #[error("Description box label exceeds the maximum length of {0} bytes")]
+    LabelTooLong(usize),
+
     /// UTF-8 decoding error.
+    ///
+    /// Use [`ParseOptions::set_lenient_label_encoding()`] to tolerate labels
+    /// which aren't valid UTF-8.
+    ///
+    /// [`ParseOptions::set_lenient_label_encoding()`]: crate::parser::ParseOptions::set_lenient_label_encoding
     #[error("Unable to decode description box as UTF-8: {0:?}")]
     Utf8Error(Utf8Error),
 
-    /// JUMBF data was incomplete.
-    #[error("Incomplete data, missing: {0:?}")]
-    Incomplete(nom::Needed),
+    /// The input ended before the end of a box.
+    ///
+    /// Parsing may succeed if more input is provided. This is only reported
+    /// for a box which extends past the end of the input; a box which
+    /// extends past the end of its enclosing superbox is reported as
+    /// [`SizeMismatch`] instead.
+    ///
+    /// [`SizeMismatch`]: Self::SizeMismatch
+    #[error("Input ends {needed} bytes before the end of the box at offset {offset}")]
+    Truncated {
+        /// Number of additional bytes needed to complete the box.
+        needed: usize,
+
+        /// Offset of the start of the incomplete box within the input.
+        offset: usize,
+    },
+
+    /// A box header, or the fixed fields of a description box, could not
+    /// be read even though the enclosing box is complete.
+    ///
+    /// Providing more input will not help.
+    #[error("Corrupt box header at offset {offset}")]
+    CorruptHeader {
+        /// Offset of the start of the corrupt box within the input.
+        offset: usize,
+    },
+
+    /// A box declares a size larger than the space remaining in the
+    /// superbox (or description box) which contains it.
+    ///
+    /// Providing more input will not help.
+    #[error(
+        "Box at offset {offset} declares {declared} bytes, but only {available} bytes are available"
+    )]
+    SizeMismatch {
+        /// Offset of the start of the oversized box within the input.
+        offset: usize,
+
+        /// Size of the box, including its header, as declared in the header.
+        declared: u64,
+
+        /// Number of bytes from the start of the box to the end of the
+        /// enclosing box.
+        available: usize,
+    },
+
+    /// Parsing was cancelled by the callback provided to
+    /// [`ParseOptions::set_cancel_callback()`].
+    ///
+    /// [`ParseOptions::set_cancel_callback()`]: crate::parser::ParseOptions::set_cancel_callback
+    #[error("Parsing was cancelled")]
+    Cancelled,
 
     /// Error from nom parsing framework.
     #[error("nom error: {0:?}")]
     NomError(ErrorKind),
 }
 
+impl Error {
+    /// Returns `true` if this error indicates that the input ended early, so
+    /// that parsing may succeed if more input is provided.
+    ///
+    /// Errors for which this returns `false` indicate malformed JUMBF which
+    /// should be rejected.
+    pub fn is_truncated(&self) -> bool {
+        matches!(self, Self::Truncated { .. })
+    }
+
+    // Returns this error with any offset it contains moved `base` bytes
+    // later. Used when the error was reported for a box which starts `base`
+    // bytes into a larger input.
+    pub(crate) fn offset_by(self, base: usize) -> Self {
+        match self {
+            Self::Truncated { needed, offset } => Self::Truncated {
+                needed,
+                offset: offset + base,
+            },
+            Self::CorruptHeader { offset } => Self::CorruptHeader {
+                offset: offset + base,
+            },
+            Self::SizeMismatch {
+                offset,
+                declared,
+                available,
+            } => Self::SizeMismatch {
+                offset: offset + base,
+                declared,
+                available,
+            },
+            err => err,
+        }
+    }
+}
+
+/// Describes an error which was skipped over by
+/// [`SuperBox::from_slice_with_recovery()`].
+///
+/// [`SuperBox::from_slice_with_recovery()`]: crate::parser::SuperBox::from_slice_with_recovery
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveredError {
+    /// Offset within the input of the box that could not be parsed.
+    pub offset: usize,
+
+    /// Type of the box that could not be parsed, if its header was
+    /// readable.
+    ///
+    /// If this is `None`, the box header itself was malformed, so the
+    /// remainder of the enclosing superbox was skipped.
+    pub tbox: Option<BoxType>,
+
+    /// The error that occurred.
+    pub error: Error,
+}
+
 impl<'a> ParseError<&'a [u8]> for Error {
     fn from_error_kind(_input: &'a [u8], kind: ErrorKind) -> Self {
         Error::NomError(kind)
@@ -67,7 +216,13 @@ impl From<Error> for nom::Err<Error> {
 impl From<nom::Err<Error>> for Error {
     fn from(e: nom::Err<Error>) -> Self {
         match e {
-            nom::Err::Incomplete(n) => Self::Incomplete(n),
+            nom::Err::Incomplete(needed) => Self::Truncated {
+                needed: match needed {
+                    nom::Needed::Size(size) => size.get(),
+                    nom::Needed::Unknown => 0,
+                },
+                offset: 0,
+            },
             nom::Err::Error(e) | nom::Err::Failure(e) => e,
         }
     }
@@ -0,0 +1,66 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::fmt::{Display, Formatter};
+
+/// A reference to a box within a JUMBF data structure together with a
+/// digest of that box's content, as used for C2PA assertion references.
+///
+/// Use [`SuperBox::hashed_uri()`] to create one.
+///
+/// [`SuperBox::hashed_uri()`]: crate::parser::SuperBox::hashed_uri
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HashedUri {
+    /// JUMBF URI of the referenced box (i.e. `self#jumbf=/c2pa/...`).
+    pub url: String,
+
+    /// Name of the digest algorithm, as reported by [`Digest::algorithm()`].
+    ///
+    /// [`Digest::algorithm()`]: crate::digest::Digest::algorithm
+    pub alg: String,
+
+    /// Digest of the referenced box.
+    pub hash: Vec<u8>,
+}
+
+impl Display for HashedUri {
+    /// Formats the URI with the digest appended as an `hl` query parameter
+    /// encoded as unpadded URL-safe base64 (i.e.
+    /// `self#jumbf=/c2pa/...?hl=...`).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}?hl={}", self.url, base64url(&self.hash))
+    }
+}
+
+// Encode as URL-safe base64 without padding (RFC 4648, section 5).
+fn base64url(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..=chunk.len() {
+            let index = (n >> (18 - 6 * i)) & 0x3f;
+            result.push(ALPHABET[index as usize] as char);
+        }
+    }
+
+    result
+}
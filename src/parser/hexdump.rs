@@ -0,0 +1,290 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Annotated hex dumps of parsed JUMBF data structures.
+//!
+//! Use [`HexDump`] to render a superbox (including all of its descendants)
+//! or a single data box as text. Each box header field, description box
+//! field, and payload is listed with its offset and a short description,
+//! which is useful when inspecting unfamiliar or damaged files. Description
+//! box UUIDs which match a [known content type] are labeled with its name.
+//!
+//! ## Example
+//!
+//! ```
+//! use hex_literal::hex;
+//! use jumbf::parser::{hexdump::HexDump, SuperBox};
+//!
+//! let jumbf = hex!(
+//!     "0000002f" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "00000027" // box size
+//!         "6a756d64" // box type = 'jumd'
+//!         "00000000000000000000000000000000" // UUID
+//!         "03" // toggles
+//!         "746573742e7375706572626f7800" // label
+//! );
+//!
+//! let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+//! print!("{}", HexDump::default().super_box(&sbox));
+//! ```
+//!
+//! prints:
+//!
+//! ```text
+//! 00000000  00 00 00 2f                                      box size = 47
+//! 00000004  6a 75 6d 62                                      box type = b"jumb"
+//! 00000008  00 00 00 27                                        box size = 39
+//! 0000000c  6a 75 6d 64                                        box type = b"jumd"
+//! 00000010  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00      uuid
+//! 00000020  03                                                   toggles = REQUESTABLE | HAS_LABEL
+//! 00000021  74 65 73 74 2e 73 75 70 65 72 62 6f 78 00            label = "test.superbox"
+//! ```
+//!
+//! [known content type]: crate::content_type
+
+use std::fmt::Write;
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    parser::{data_box::HeaderEncoding, ChildBox, DataBox, DescriptionBox, SuperBox},
+    toggles::Toggles,
+    BoxType,
+};
+
+/// Renders JUMBF boxes as annotated hex dumps.
+///
+/// Construct one with [`HexDump::default()`] and then call one or more of
+/// the `set_*` methods to adjust it.
+///
+/// Offsets in the dump are relative to the start of the box being dumped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HexDump {
+    max_payload_len: usize,
+}
+
+impl Default for HexDump {
+    fn default() -> Self {
+        Self {
+            max_payload_len: usize::MAX,
+        }
+    }
+}
+
+impl HexDump {
+    /// Show at most `max_payload_len` bytes of each data box payload.
+    ///
+    /// Any remaining payload bytes are summarized on a single line. By
+    /// default, payloads are shown in full.
+    pub fn set_max_payload_len(mut self, max_payload_len: usize) -> Self {
+        self.max_payload_len = max_payload_len;
+        self
+    }
+
+    /// Render a superbox and all of its descendants.
+    pub fn super_box(&self, sbox: &SuperBox) -> String {
+        let mut w = DumpWriter::new(self, sbox.original);
+        w.super_box(0, sbox);
+        w.out
+    }
+
+    /// Render a single data box.
+    pub fn data_box(&self, dbox: &DataBox) -> String {
+        let mut w = DumpWriter::new(self, dbox.original);
+        w.data_box(0, dbox);
+        w.out
+    }
+}
+
+const BYTES_PER_ROW: usize = 16;
+const HEX_WIDTH: usize = BYTES_PER_ROW * 3 - 1;
+
+struct DumpWriter {
+    base: usize,
+    max_payload_len: usize,
+    out: String,
+}
+
+impl DumpWriter {
+    fn new(options: &HexDump, original: &[u8]) -> Self {
+        Self {
+            base: original.as_ptr() as usize,
+            max_payload_len: options.max_payload_len,
+            out: String::new(),
+        }
+    }
+
+    fn super_box(&mut self, depth: usize, sbox: &SuperBox) {
+        self.header(depth, sbox.original, SUPER_BOX_TYPE);
+        self.description_box(depth + 1, &sbox.desc);
+
+        for child_box in &sbox.child_boxes {
+            match child_box {
+                ChildBox::SuperBox(sbox) => self.super_box(depth + 1, sbox),
+                ChildBox::DataBox(dbox) => self.data_box(depth + 1, dbox),
+            }
+        }
+    }
+
+    fn data_box(&mut self, depth: usize, dbox: &DataBox) {
+        self.header(depth, dbox.original, dbox.tbox);
+        self.payload(depth + 1, dbox.data);
+    }
+
+    fn description_box(&mut self, depth: usize, desc: &DescriptionBox) {
+        if desc.original.is_empty() {
+            self.line(depth, "(no description box)");
+            return;
+        }
+
+        let header_len = self.header(depth, desc.original, DESCRIPTION_BOX_TYPE);
+        let depth = depth + 1;
+
+        let mut rest = desc.original.get(header_len..).unwrap_or_default();
+        let uuid_note = match desc.content_type() {
+            Some(content_type) => format!("uuid ({content_type})"),
+            None => "uuid".to_owned(),
+        };
+        self.field(depth, take(&mut rest, 16), &uuid_note);
+
+        let toggles = take(&mut rest, 1);
+        let Some(bits) = toggles.first() else {
+            return;
+        };
+        let toggles_value = Toggles::from_bits(*bits);
+        self.field(depth, toggles, &format!("toggles = {toggles_value}"));
+
+        if toggles_value.contains(Toggles::HAS_LABEL) {
+            let len = rest
+                .iter()
+                .position(|b| *b == 0)
+                .map_or(rest.len(), |n| n + 1);
+            let label = take(&mut rest, len);
+            let text = String::from_utf8_lossy(label.strip_suffix(&[0]).unwrap_or(label));
+            self.field(depth, label, &format!("label = {text:?}"));
+        }
+
+        if let Some(id) = desc.id {
+            self.field(depth, take(&mut rest, 4), &format!("id = {id}"));
+        }
+
+        if desc.hash.is_some() {
+            self.field(depth, take(&mut rest, 32), "hash");
+        }
+
+        if let Some(private) = desc.private.as_ref() {
+            self.data_box(depth, private);
+            take(&mut rest, private.original.len());
+        }
+
+        if !rest.is_empty() {
+            self.field(depth, rest, "unparsed data");
+        }
+    }
+
+    // Describe a box header and return its length.
+    fn header(&mut self, depth: usize, original: &[u8], tbox: BoxType) -> usize {
+        if original.len() < 8 {
+            return 0;
+        }
+
+        let lbox = u32::from_be_bytes([original[0], original[1], original[2], original[3]]);
+        let note = match lbox {
+            0 => "box size = 0 (extends to end of data)".to_owned(),
+            1 => "box size = 1 (extended size follows)".to_owned(),
+            n => format!("box size = {n}"),
+        };
+        self.field(depth, &original[0..4], &note);
+        self.field(depth, &original[4..8], &format!("box type = {tbox:?}"));
+
+        let header_len = HeaderEncoding::of(original).header_len();
+        if header_len > 8 {
+            if let Some(xlbox) = original.get(8..header_len) {
+                let mut len = [0u8; 8];
+                len.copy_from_slice(xlbox);
+                let note = format!("extended box size = {}", u64::from_be_bytes(len));
+                self.field(depth, xlbox, &note);
+            }
+        }
+
+        header_len
+    }
+
+    fn payload(&mut self, depth: usize, data: &[u8]) {
+        let shown = &data[..data.len().min(self.max_payload_len)];
+
+        for chunk in shown.chunks(BYTES_PER_ROW) {
+            let ascii: String = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            self.row(depth, chunk, &format!("|{ascii}|"));
+        }
+
+        let omitted = &data[shown.len()..];
+        if !omitted.is_empty() {
+            let note = format!("... {} more bytes", omitted.len());
+            self.annotate(self.offset(omitted), "", depth, &note);
+        }
+    }
+
+    fn field(&mut self, depth: usize, bytes: &[u8], note: &str) {
+        for (i, chunk) in bytes.chunks(BYTES_PER_ROW).enumerate() {
+            self.row(depth, chunk, if i == 0 { note } else { "" });
+        }
+    }
+
+    fn row(&mut self, depth: usize, bytes: &[u8], note: &str) {
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        self.annotate(self.offset(bytes), &hex.join(" "), depth, note);
+    }
+
+    fn line(&mut self, depth: usize, note: &str) {
+        let _ = writeln!(
+            self.out,
+            "{:8}  {:w$}  {:indent$}{note}",
+            "",
+            "",
+            "",
+            w = HEX_WIDTH,
+            indent = depth * 2
+        );
+    }
+
+    // Write one line of output. Annotations are indented to show nesting.
+    fn annotate(&mut self, offset: usize, hex: &str, depth: usize, note: &str) {
+        let line = format!(
+            "{offset:08x}  {hex:HEX_WIDTH$}  {:indent$}{note}",
+            "",
+            indent = depth * 2,
+        );
+        let _ = writeln!(self.out, "{}", line.trim_end());
+    }
+
+    fn offset(&self, bytes: &[u8]) -> usize {
+        (bytes.as_ptr() as usize).wrapping_sub(self.base)
+    }
+}
+
+// Split up to `n` bytes off the front of `rest`.
+fn take<'a>(rest: &mut &'a [u8], n: usize) -> &'a [u8] {
+    let (head, tail) = rest.split_at(n.min(rest.len()));
+    *rest = tail;
+    head
+}
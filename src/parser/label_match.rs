@@ -0,0 +1,81 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+/// A `LabelMatch` struct describes how labels are compared when searching
+/// for a superbox with [`SuperBox::find_by_label_with()`].
+///
+/// By default, labels must match exactly. Construct one with
+/// [`LabelMatch::default()`] and then call one or more of the `set_*` methods
+/// to relax the comparison.
+///
+/// [`SuperBox::find_by_label_with()`]: crate::parser::SuperBox::find_by_label_with
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LabelMatch {
+    case_insensitive: bool,
+
+    #[cfg(feature = "unicode-normalization")]
+    nfc: bool,
+}
+
+impl LabelMatch {
+    /// Ignore differences in letter case when comparing labels.
+    ///
+    /// Case is compared using Unicode lowercase mappings, so this applies to
+    /// non-ASCII letters as well.
+    pub fn set_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Convert labels to Unicode Normalization Form C (NFC) before
+    /// comparing them.
+    ///
+    /// This allows a label which uses combining characters (i.e. `e` followed
+    /// by U+0301) to match one which uses precomposed characters (i.e.
+    /// U+00E9).
+    ///
+    /// This function is available when the `unicode-normalization` crate
+    /// feature is enabled.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn set_nfc(mut self, nfc: bool) -> Self {
+        self.nfc = nfc;
+        self
+    }
+
+    /// Returns `true` if `label` matches `pattern` under these options.
+    pub fn matches(&self, label: &str, pattern: &str) -> bool {
+        if *self == Self::default() {
+            return label == pattern;
+        }
+
+        self.canonical_chars(label)
+            .eq(self.canonical_chars(pattern))
+    }
+
+    fn canonical_chars<'s>(&self, s: &'s str) -> Box<dyn Iterator<Item = char> + 's> {
+        let chars: Box<dyn Iterator<Item = char> + 's> = Box::new(s.chars());
+
+        #[cfg(feature = "unicode-normalization")]
+        let chars: Box<dyn Iterator<Item = char> + 's> =
+            if self.nfc { Box::new(s.nfc()) } else { chars };
+
+        if self.case_insensitive {
+            Box::new(chars.flat_map(char::to_lowercase))
+        } else {
+            chars
+        }
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt::{Display, Formatter, Write},
+    str::FromStr,
+};
+
+/// A `LabelPath` identifies a superbox by the labels of the superboxes that
+/// lead to it from some starting superbox.
+///
+/// Each component is stored unescaped, so labels may contain any character,
+/// including the `/` path separator. Use [`Display`] to produce the escaped
+/// string form (i.e. `manifest/a%2Fb`) and [`FromStr`] or `From<&str>` to
+/// parse one.
+///
+/// ## Example
+///
+/// ```
+/// use jumbf::parser::LabelPath;
+///
+/// let mut path = LabelPath::new();
+/// path.push("manifest");
+/// path.push("a/b");
+/// assert_eq!(path.to_string(), "manifest/a%2Fb");
+///
+/// let parsed: LabelPath = "manifest/a%2Fb".parse().unwrap();
+/// assert_eq!(parsed, path);
+/// assert_eq!(parsed.components().collect::<Vec<_>>(), ["manifest", "a/b"]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LabelPath {
+    components: Vec<String>,
+}
+
+impl LabelPath {
+    /// Create an empty label path, which refers to the starting superbox.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a label to the end of this path.
+    ///
+    /// `label` is not escaped; it should be the label exactly as it appears
+    /// in the description box.
+    pub fn push(&mut self, label: impl Into<String>) {
+        self.components.push(label.into());
+    }
+
+    /// Remove and return the last label in this path, if any.
+    pub fn pop(&mut self) -> Option<String> {
+        self.components.pop()
+    }
+
+    /// Return an iterator over the (unescaped) labels in this path.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        self.components.iter().map(String::as_str)
+    }
+
+    /// Returns the number of labels in this path.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns `true` if this path has no labels.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl Display for LabelPath {
+    /// Formats the path as its labels, each escaped with [`escape_label()`],
+    /// separated by `/`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, label) in self.components.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(&escape_label(label))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for LabelPath {
+    /// Parses a path of `/`-separated, percent-encoded labels.
+    ///
+    /// Any string is accepted. A `%` that doesn't begin a valid escape
+    /// sequence is taken literally. An empty string is a path consisting of
+    /// one empty label.
+    fn from(path: &str) -> Self {
+        Self {
+            components: path
+                .split('/')
+                .map(|label| unescape_label(label).into_owned())
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for LabelPath {
+    type Err = Infallible;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(path))
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for LabelPath {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            components: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// Characters which must be percent-encoded within a label path component.
+fn is_reserved(c: char) -> bool {
+    matches!(c, '%' | '/' | '?' | '#') || c.is_ascii_control()
+}
+
+/// Escape a single label so that it can be used as one component of a label
+/// path, as accepted by [`SuperBox::find_by_label()`].
+///
+/// The path separator (`/`), percent sign (`%`), URI delimiters (`?` and `#`),
+/// and ASCII control characters are percent-encoded. Other characters are
+/// passed through unchanged.
+///
+/// ## Example
+///
+/// ```
+/// use jumbf::parser::escape_label;
+///
+/// assert_eq!(escape_label("c2pa.claim"), "c2pa.claim");
+/// assert_eq!(escape_label("a/b"), "a%2Fb");
+/// assert_eq!(escape_label("100%"), "100%25");
+///
+/// let path = format!("{}/{}", escape_label("manifest"), escape_label("a/b"));
+/// assert_eq!(path, "manifest/a%2Fb");
+/// ```
+///
+/// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+pub fn escape_label(label: &str) -> Cow<'_, str> {
+    if !label.chars().any(is_reserved) {
+        return Cow::Borrowed(label);
+    }
+
+    let mut escaped = String::with_capacity(label.len() + 8);
+    for c in label.chars() {
+        if is_reserved(c) {
+            // Reserved characters are all ASCII, so one byte each.
+            // Writing to a String can't fail.
+            #[allow(clippy::unwrap_used)]
+            write!(escaped, "%{:02X}", c as u32).unwrap();
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+// Decode percent-encoded sequences in a single label path component.
+//
+// A `%` which isn't followed by two hex digits is taken literally, as is any
+// component which doesn't decode to valid UTF-8.
+pub(crate) fn unescape_label(component: &str) -> Cow<'_, str> {
+    if !component.contains('%') {
+        return Cow::Borrowed(component);
+    }
+
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            // Check the digits first: `from_str_radix` would also accept a
+            // leading `+`.
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+            if let Some(b) = hex {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(_) => Cow::Borrowed(component),
+    }
+}
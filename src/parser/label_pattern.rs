@@ -0,0 +1,123 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::parser::label_path::unescape_label;
+
+// A pattern for matching label paths, as accepted by
+// `SuperBox::find_by_pattern()`.
+//
+// Matching is performed one label at a time. The matcher state is the set
+// of pattern components which could match the next label.
+pub(crate) struct LabelPattern {
+    components: Vec<PatternComponent>,
+}
+
+enum PatternComponent {
+    // `**`: Any number of labels, including none.
+    AnyDepth,
+
+    // A single label. Matches if the label consists of these literal
+    // pieces, in order, separated by any (possibly empty) strings.
+    Glob(Vec<String>),
+}
+
+impl LabelPattern {
+    // Returns the matcher state before any labels have been matched.
+    pub(crate) fn start(&self) -> Vec<usize> {
+        self.closure(vec![0])
+    }
+
+    // Returns `true` if the labels matched so far match the entire pattern.
+    pub(crate) fn is_match(&self, state: &[usize]) -> bool {
+        state.contains(&self.components.len())
+    }
+
+    // Returns the matcher state after matching `label`. An empty state
+    // means that no path with this prefix can match.
+    pub(crate) fn advance(&self, state: &[usize], label: &str) -> Vec<usize> {
+        let next = state
+            .iter()
+            .filter_map(|&index| match self.components.get(index)? {
+                PatternComponent::AnyDepth => Some(index),
+                PatternComponent::Glob(pieces) => glob_matches(pieces, label).then_some(index + 1),
+            })
+            .collect();
+
+        self.closure(next)
+    }
+
+    // `**` may match zero labels, so any state positioned at `**` may also
+    // skip past it.
+    fn closure(&self, mut state: Vec<usize>) -> Vec<usize> {
+        let mut i = 0;
+        while i < state.len() {
+            let index = state[i];
+            if let Some(PatternComponent::AnyDepth) = self.components.get(index) {
+                state.push(index + 1);
+            }
+            i += 1;
+        }
+
+        state.sort_unstable();
+        state.dedup();
+        state
+    }
+}
+
+impl From<&str> for LabelPattern {
+    fn from(pattern: &str) -> Self {
+        Self {
+            components: pattern
+                .split('/')
+                .map(|component| {
+                    if component == "**" {
+                        PatternComponent::AnyDepth
+                    } else {
+                        PatternComponent::Glob(
+                            component
+                                .split('*')
+                                .map(|piece| unescape_label(piece).into_owned())
+                                .collect(),
+                        )
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+// Returns `true` if `label` consists of `pieces`, in order, separated by
+// any (possibly empty) strings.
+fn glob_matches(pieces: &[String], label: &str) -> bool {
+    let Some((first, rest)) = pieces.split_first() else {
+        return label.is_empty();
+    };
+
+    let Some(mut label) = label.strip_prefix(first.as_str()) else {
+        return false;
+    };
+
+    let Some((last, middle)) = rest.split_last() else {
+        // No wildcards.
+        return label.is_empty();
+    };
+
+    for piece in middle {
+        match label.find(piece.as_str()) {
+            Some(index) => label = &label[index + piece.len()..],
+            None => return false,
+        }
+    }
+
+    label.ends_with(last.as_str())
+}
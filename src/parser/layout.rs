@@ -0,0 +1,973 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Offsets-only parsing of JUMBF data from a stream.
+//!
+//! [`BoxLayout::from_reader()`] reads a superbox from any [`Read`] + [`Seek`]
+//! stream and records where each box is located, without retaining any
+//! payload data or any reference to the stream. Only box headers and
+//! description box labels are read; payloads are skipped over by seeking.
+//! Short payloads are instead read and discarded, so that a buffered reader
+//! such as [`BufReader`] can serve several boxes from one buffer.
+//!
+//! This is useful for indexing large files and for passing byte ranges to
+//! other subsystems.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! use hex_literal::hex;
+//! use jumbf::parser::layout::BoxLayout;
+//!
+//! let jumbf = hex!(
+//!     "00000039" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "00000027" // box size
+//!         "6a756d64" // box type = 'jumd'
+//!         "00000000000000000000000000000000" // UUID
+//!         "03" // toggles
+//!         "746573742e7375706572626f7800" // label
+//!         // ---
+//!         "0000000a" // box size
+//!         "6a736f6e" // box type = 'json'
+//!         "7b7d" // payload = {}
+//! );
+//!
+//! let layout = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap();
+//!
+//! assert_eq!(layout.label.as_deref(), Some("test.superbox"));
+//! assert_eq!(layout.range(), 0..57);
+//!
+//! let json = &layout.children[1];
+//! assert_eq!(json.tbox.0, *b"json");
+//! assert_eq!(json.payload_range(), 55..57);
+//! ```
+//!
+//! [`BufReader`]: std::io::BufReader
+
+use std::{
+    fs::OpenOptions,
+    io::{
+        copy, repeat, sink, Error as IoError, ErrorKind, Read, Result as IoResult, Seek, SeekFrom,
+        Write,
+    },
+    ops::Range,
+    path::Path,
+};
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    parser::{Error, LabelPath, ParseOptions},
+    toggles::Toggles,
+    BoxType,
+};
+
+/// The location of a JUMBF box within a stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoxLayout {
+    /// Box type.
+    pub tbox: BoxType,
+
+    /// Offset of the start of the box (its header) within the stream.
+    pub offset: u64,
+
+    /// Length of the box header, including any XLBox length.
+    pub header_len: u64,
+
+    /// Length of the entire box, including its header.
+    pub len: u64,
+
+    /// Label from the description box, if this is a superbox with a
+    /// labeled description box.
+    pub label: Option<String>,
+
+    /// Child boxes, if this box was parsed as a superbox. The description
+    /// box is included.
+    pub children: Vec<BoxLayout>,
+}
+
+impl BoxLayout {
+    /// Read the location of a JUMBF superbox and all of its descendants,
+    /// starting at the current position of `reader`.
+    ///
+    /// Children of this superbox which are also superboxes will be parsed
+    /// recursively to the default depth limit. When this returns
+    /// successfully, `reader` is positioned at the end of the superbox.
+    ///
+    /// I/O errors are returned as is. Malformed JUMBF is reported as an
+    /// [`ErrorKind::InvalidData`] error which wraps a parser [`Error`].
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> IoResult<Self> {
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Read the location of a JUMBF superbox and all of its descendants,
+    /// starting at the current position of `reader`. Parsing behavior is
+    /// controlled by `options`.
+    ///
+    /// The depth limit, cancellation callback, description box leniency,
+    /// and label options are honored. Other options are ignored.
+    pub fn from_reader_with_options<R: Read + Seek>(
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> IoResult<Self> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let mut reader = TrackedReader::new(reader, start);
+
+        let mut layout = read_header(&mut reader, end, false)?;
+        if layout.tbox != SUPER_BOX_TYPE {
+            return Err(invalid_data(Error::InvalidSuperBoxType(layout.tbox)));
+        }
+
+        read_super_box(&mut reader, &mut layout, options, 0)?;
+        reader.seek_to(layout.end_offset())?;
+        Ok(layout)
+    }
+
+    /// Read the descendants of this box from `reader`, if it is a superbox
+    /// whose children were not read because of a depth limit.
+    ///
+    /// `reader` must be the stream this layout was read from. Only this
+    /// box is re-read, so a `jumb` box which was skipped by an earlier
+    /// parse can be expanded later without reading the stream from the
+    /// top. Its descendants are read to the depth limit in `options`,
+    /// counted from this box. When this returns successfully, `reader` is
+    /// positioned at the end of this box.
+    ///
+    /// Does nothing if the children of this box have already been read.
+    /// Returns an error if this is not a superbox.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{layout::BoxLayout, ParseOptions};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let mut reader = Cursor::new(&jumbf);
+    /// let options = ParseOptions::default().set_depth_limit(0);
+    /// let mut layout = BoxLayout::from_reader_with_options(&mut reader, &options).unwrap();
+    ///
+    /// let claim = &mut layout.children[1];
+    /// assert_eq!(claim.label, None);
+    ///
+    /// claim.expand(&mut reader, &ParseOptions::default()).unwrap();
+    /// assert_eq!(claim.label.as_deref(), Some("C.Claim"));
+    /// ```
+    pub fn expand<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        options: &ParseOptions,
+    ) -> IoResult<()> {
+        if self.tbox != SUPER_BOX_TYPE {
+            return Err(invalid_data(Error::InvalidSuperBoxType(self.tbox)));
+        }
+
+        let pos = reader.stream_position()?;
+        let mut reader = TrackedReader::new(reader, pos);
+
+        if self.children.is_empty() {
+            if let Err(err) = read_super_box(&mut reader, self, options, 0) {
+                // Don't leave a partial list of children behind, or a later
+                // call would mistake it for a complete one.
+                self.children.clear();
+                self.label = None;
+                return Err(err);
+            }
+        }
+
+        reader.seek_to(self.end_offset())
+    }
+
+    /// Returns the offset of the first byte in the stream after this box.
+    ///
+    /// For a superbox read by [`from_reader()`], this is where parsing of
+    /// any surrounding container may continue.
+    ///
+    /// [`from_reader()`]: Self::from_reader
+    pub fn end_offset(&self) -> u64 {
+        self.offset + self.len
+    }
+
+    /// Returns the range of the stream occupied by this box.
+    pub fn range(&self) -> Range<u64> {
+        self.offset..self.end_offset()
+    }
+
+    /// Returns the range of the stream occupied by this box's payload.
+    pub fn payload_range(&self) -> Range<u64> {
+        self.offset + self.header_len..self.offset + self.len
+    }
+
+    /// Returns a table of the byte ranges which make up this box, in stream
+    /// order.
+    ///
+    /// The ranges are contiguous and together cover exactly [`range()`].
+    /// Each superbox contributes its header, its entire description box,
+    /// and then the ranges for each of its other children in turn. Every
+    /// other box contributes its header and its payload. A superbox whose
+    /// children were not read (because of a depth limit) is treated as a
+    /// box with an opaque payload.
+    ///
+    /// This is intended for hashing schemes (such as those used by C2PA)
+    /// which hash a JUMBF data structure while excluding some portions of
+    /// it. The caller can choose which ranges to exclude by path, role, or
+    /// box type.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use hex_literal::hex;
+    /// use jumbf::parser::layout::{BoxLayout, ByteRangeRole};
+    ///
+    /// let jumbf = hex!(
+    ///     "00000039" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    ///         // ---
+    ///         "0000000a" // box size
+    ///         "6a736f6e" // box type = 'json'
+    ///         "7b7d" // payload = {}
+    /// );
+    ///
+    /// let layout = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap();
+    /// let ranges = layout.byte_ranges();
+    ///
+    /// let table: Vec<_> = ranges
+    ///     .iter()
+    ///     .map(|r| (r.tbox.0, r.role, r.offset, r.len))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     table,
+    ///     [
+    ///         (*b"jumb", ByteRangeRole::Header, 0, 8),
+    ///         (*b"jumd", ByteRangeRole::DescriptionBox, 8, 39),
+    ///         (*b"json", ByteRangeRole::Header, 47, 8),
+    ///         (*b"json", ByteRangeRole::Payload, 55, 2),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`range()`]: Self::range
+    pub fn byte_ranges(&self) -> Vec<ByteRange> {
+        let mut ranges = vec![];
+        self.collect_byte_ranges(&LabelPath::new(), &mut ranges);
+        ranges
+    }
+
+    fn collect_byte_ranges(&self, path: &LabelPath, ranges: &mut Vec<ByteRange>) {
+        let range = |role: ByteRangeRole, offset: u64, len: u64| ByteRange {
+            path: path.clone(),
+            tbox: self.tbox,
+            role,
+            offset,
+            len,
+        };
+
+        ranges.push(range(ByteRangeRole::Header, self.offset, self.header_len));
+
+        if self.tbox != SUPER_BOX_TYPE || self.children.is_empty() {
+            ranges.push(range(
+                ByteRangeRole::Payload,
+                self.offset + self.header_len,
+                self.len - self.header_len,
+            ));
+            return;
+        }
+
+        let mut found_desc = false;
+
+        for child in &self.children {
+            if child.tbox == DESCRIPTION_BOX_TYPE && !found_desc {
+                found_desc = true;
+                ranges.push(ByteRange {
+                    path: path.clone(),
+                    tbox: child.tbox,
+                    role: ByteRangeRole::DescriptionBox,
+                    offset: child.offset,
+                    len: child.len,
+                });
+            } else if child.tbox == SUPER_BOX_TYPE && !child.children.is_empty() {
+                let mut child_path = path.clone();
+                child_path.push(child.label.as_deref().unwrap_or_default());
+                child.collect_byte_ranges(&child_path, ranges);
+            } else {
+                child.collect_byte_ranges(path, ranges);
+            }
+        }
+    }
+}
+
+/// One entry in the table returned by [`BoxLayout::byte_ranges()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ByteRange {
+    /// Path to the superbox described by this range (for a superbox header
+    /// or description box) or to the superbox which contains it (for any
+    /// other box). Unlabeled superboxes contribute an empty component.
+    pub path: LabelPath,
+
+    /// Box type of the box which contains this range.
+    pub tbox: BoxType,
+
+    /// Which part of the box this range covers.
+    pub role: ByteRangeRole,
+
+    /// Offset of the start of this range within the stream.
+    pub offset: u64,
+
+    /// Length of this range.
+    pub len: u64,
+}
+
+impl ByteRange {
+    /// Returns the range of the stream covered by this entry.
+    pub fn range(&self) -> Range<u64> {
+        self.offset..self.offset + self.len
+    }
+}
+
+/// The part of a box covered by a [`ByteRange`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ByteRangeRole {
+    /// A box header, including any XLBox length.
+    Header,
+
+    /// The payload of a box other than a superbox or description box.
+    /// Also used for the payload of a superbox whose children were not
+    /// read.
+    Payload,
+
+    /// An entire description box, including its header.
+    DescriptionBox,
+}
+
+/// Replace the payload of a data box within a JUMBF file, in place.
+///
+/// The file at `path` must begin with a JUMBF superbox. See [`patch()`] for
+/// details.
+pub fn patch_file<P: AsRef<Path>>(path: P, label: &str, new_payload: &[u8]) -> IoResult<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    patch(&mut file, label, new_payload)
+}
+
+/// Replace the payload of a data box within a JUMBF superbox, in place.
+///
+/// The superbox starts at the current position of `stream`. `label`
+/// identifies a descendant superbox in the same way as
+/// [`SuperBox::find_by_label()`], except that labels are compared exactly
+/// and the requestable toggle is not checked. The payload of the first data
+/// box in that superbox is replaced by `new_payload`.
+///
+/// Only the superboxes along the path to the data box are read, and only
+/// the data box itself (and any padding after it) is written. The sizes of
+/// the enclosing superboxes never change:
+///
+/// * If `new_payload` is the same size as the existing payload, it is written
+///   over the existing payload.
+/// * If it is smaller, the data box is shrunk and the space which is freed is
+///   filled with a zero-filled `free` box. At least 8 bytes must be freed to
+///   make room for the `free` box header, and less than 4 GiB may be freed.
+/// * If the data box is immediately followed by a `free` box, that space is
+///   also available, so the payload may grow into it. The `free` box is shrunk,
+///   or removed if the new payload uses all of its space.
+///
+/// Otherwise, an [`ErrorKind::InvalidInput`] error is returned and nothing
+/// is written.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use hex_literal::hex;
+/// use jumbf::parser::{layout::patch, SuperBox};
+///
+/// let jumbf = hex!(
+///     "0000005a" // box size
+///     "6a756d62" // box type = 'jumb'
+///         "00000019" // box size
+///         "6a756d64" // box type = 'jumd'
+///         "00000000000000000000000000000000" // UUID
+///         "00" // toggles
+///         // ---
+///         "00000039" // box size
+///         "6a756d62" // box type = 'jumb'
+///             "0000001d" // box size
+///             "6a756d64" // box type = 'jumd'
+///             "00000000000000000000000000000000" // UUID
+///             "03" // toggles
+///             "61626300" // label = "abc"
+///             // ---
+///             "00000014" // box size
+///             "6a736f6e" // box type = 'json'
+///             "7b2261223a3132333435367d" // payload = {"a":123456}
+/// );
+///
+/// let mut stream = Cursor::new(jumbf.to_vec());
+/// patch(&mut stream, "abc", b"{}").unwrap();
+///
+/// let jumbf = stream.into_inner();
+/// let sbox = SuperBox::parse(&jumbf).unwrap();
+/// let abc = sbox.find_by_label("abc").unwrap();
+///
+/// assert_eq!(abc.data_box().unwrap().data, b"{}");
+/// assert_eq!(abc.child_boxes[1].as_data_box().unwrap().tbox.0, *b"free");
+/// ```
+///
+/// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+pub fn patch<S: Read + Write + Seek>(
+    stream: &mut S,
+    label: &str,
+    new_payload: &[u8],
+) -> IoResult<()> {
+    let (sbox, i) = find_data_box(stream, label)?;
+    let dbox = &sbox.children[i];
+    let payload_len = dbox.len - dbox.header_len;
+    let new_len = new_payload.len() as u64;
+
+    if new_len == payload_len {
+        stream.seek(SeekFrom::Start(dbox.payload_range().start))?;
+        return stream.write_all(new_payload);
+    }
+
+    let available = match sbox.children.get(i + 1) {
+        Some(next) if next.tbox == FREE_BOX_TYPE => payload_len + next.len,
+        _ => payload_len,
+    };
+
+    // Any space left over must be large enough for a `free` box header.
+    let free_len = available
+        .checked_sub(new_len)
+        .filter(|n| *n == 0 || *n >= 8);
+    let fits_header = dbox.header_len == 16 || min_header_len(new_len) == 8;
+
+    let (Some(free_len), true) = (free_len, fits_header) else {
+        return Err(invalid_input(format!(
+            "new payload ({new_len} bytes) does not fit in the space available ({available} bytes)"
+        )));
+    };
+
+    // The `free` box has a 32-bit length field.
+    let Ok(free_len) = u32::try_from(free_len) else {
+        return Err(invalid_input(format!(
+            "new payload ({new_len} bytes) would free {free_len} bytes, more than a single free box can hold"
+        )));
+    };
+
+    stream.seek(SeekFrom::Start(dbox.offset))?;
+    write_box_header(stream, dbox.tbox, new_len, dbox.header_len)?;
+    stream.write_all(new_payload)?;
+
+    if free_len > 0 {
+        let free_len = free_len as u64 - 8;
+        write_box_header(stream, FREE_BOX_TYPE, free_len, 8)?;
+        copy(&mut repeat(0).take(free_len), stream)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the length of the smallest box header which can describe a
+/// payload of `payload_len` bytes.
+///
+/// This is 8 if the length of the box fits in the 32-bit length field and
+/// 16 if an XLBox length field is required.
+pub fn min_header_len(payload_len: u64) -> u64 {
+    match payload_len.checked_add(8) {
+        Some(len) if len <= u32::MAX as u64 => 8,
+        _ => 16,
+    }
+}
+
+/// Write a box header of exactly `header_len` bytes describing a box of
+/// type `tbox` with a payload of `payload_len` bytes.
+///
+/// `header_len` must be 8 (a 32-bit length field) or 16 (an XLBox length
+/// field). A 16-byte header may be used for a box of any size, but an
+/// 8-byte header can only describe a box of less than 4 GiB (see
+/// [`min_header_len()`]). Otherwise, an [`ErrorKind::InvalidInput`] error is
+/// returned and nothing is written.
+pub fn write_box_header<W: Write + ?Sized>(
+    writer: &mut W,
+    tbox: BoxType,
+    payload_len: u64,
+    header_len: u64,
+) -> IoResult<()> {
+    let box_len = payload_len.checked_add(header_len);
+
+    match (header_len, box_len) {
+        (8, Some(len)) if min_header_len(payload_len) == 8 => {
+            writer.write_all(&(len as u32).to_be_bytes())?;
+            writer.write_all(&tbox.0)
+        }
+
+        (16, Some(len)) => {
+            writer.write_all(&1u32.to_be_bytes())?;
+            writer.write_all(&tbox.0)?;
+            writer.write_all(&len.to_be_bytes())
+        }
+
+        _ => Err(invalid_input(format!(
+            "a {header_len}-byte header can't describe a payload of {payload_len} bytes"
+        ))),
+    }
+}
+
+/// Rewrite the length field of the box whose header starts at `offset`
+/// within `stream` so that its payload is `payload_len` bytes long.
+///
+/// Only the length field is written; the box type, the payload, and any
+/// surrounding boxes are left as they are. The header keeps its size, so
+/// nothing needs to be shifted:
+///
+/// * A header with a 32-bit length field is rewritten with a 32-bit length
+///   field. If the new length requires an XLBox length field, the payload would
+///   have to move 8 bytes later, so an [`ErrorKind::InvalidInput`] error is
+///   returned instead.
+/// * A header with an XLBox length field keeps it, even if the new length would
+///   fit in 32 bits.
+/// * A header with a length field of 0 (box extends to the end of the data) is
+///   given an explicit 32-bit length.
+///
+/// When this returns successfully, `stream` is positioned at the start of
+/// the payload.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use hex_literal::hex;
+/// use jumbf::parser::layout::rewrite_box_len;
+///
+/// let mut stream = Cursor::new(hex!("0000000a 6a736f6e 7b7d").to_vec());
+/// rewrite_box_len(&mut stream, 0, 1).unwrap();
+/// assert_eq!(stream.into_inner(), hex!("00000009 6a736f6e 7b7d"));
+///
+/// let mut stream = Cursor::new(hex!("0000000a 6a736f6e 7b7d").to_vec());
+/// assert!(rewrite_box_len(&mut stream, 0, 1 << 32).is_err());
+/// ```
+pub fn rewrite_box_len<S: Read + Write + Seek>(
+    stream: &mut S,
+    offset: u64,
+    payload_len: u64,
+) -> IoResult<()> {
+    let mut header = [0u8; 8];
+    stream.seek(SeekFrom::Start(offset))?;
+    stream.read_exact(&mut header)?;
+
+    let header_len = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+        1 => 16,
+        len @ 2..=7 => return Err(invalid_data(Error::InvalidBoxLength(len))),
+        _ => 8,
+    };
+    let tbox = BoxType::from(&header[4..8]);
+
+    if header_len == 8 && min_header_len(payload_len) == 16 {
+        return Err(invalid_input(format!(
+            "a payload of {payload_len} bytes requires an XLBox header, which would shift the payload"
+        )));
+    }
+
+    stream.seek(SeekFrom::Start(offset))?;
+    write_box_header(stream, tbox, payload_len, header_len)
+}
+
+/// Copy the payload of a data box within a JUMBF superbox to `writer`.
+///
+/// The superbox starts at the current position of `reader`. `label`
+/// identifies a descendant superbox in the same way as for [`patch()`], and
+/// the payload of the first data box in that superbox is copied. To extract
+/// from a byte slice, wrap it in a [`Cursor`].
+///
+/// Only the superboxes along the path to the data box are read; the
+/// payloads of other boxes are skipped. This is much cheaper than parsing
+/// the entire superbox when only one box is needed from a large file.
+///
+/// Returns the number of bytes copied. If no single matching superbox with
+/// a data box is found, an [`ErrorKind::InvalidInput`] error is returned
+/// and nothing is written.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use hex_literal::hex;
+/// use jumbf::parser::layout::extract_box_to;
+///
+/// let jumbf = hex!(
+///     "0000005a" // box size
+///     "6a756d62" // box type = 'jumb'
+///         "00000019" // box size
+///         "6a756d64" // box type = 'jumd'
+///         "00000000000000000000000000000000" // UUID
+///         "00" // toggles
+///         // ---
+///         "00000039" // box size
+///         "6a756d62" // box type = 'jumb'
+///             "0000001d" // box size
+///             "6a756d64" // box type = 'jumd'
+///             "00000000000000000000000000000000" // UUID
+///             "03" // toggles
+///             "61626300" // label = "abc"
+///             // ---
+///             "00000014" // box size
+///             "6a736f6e" // box type = 'json'
+///             "7b2261223a3132333435367d" // payload = {"a":123456}
+/// );
+///
+/// let mut json = Vec::new();
+/// let len = extract_box_to(&mut Cursor::new(&jumbf), "abc", &mut json).unwrap();
+///
+/// assert_eq!(len, 12);
+/// assert_eq!(json, br#"{"a":123456}"#);
+/// ```
+///
+/// [`Cursor`]: std::io::Cursor
+pub fn extract_box_to<R: Read + Seek, W: Write + ?Sized>(
+    reader: &mut R,
+    label: &str,
+    writer: &mut W,
+) -> IoResult<u64> {
+    let (sbox, i) = find_data_box(reader, label)?;
+    let payload = sbox.children[i].payload_range();
+
+    reader.seek(SeekFrom::Start(payload.start))?;
+    let len = copy(&mut reader.take(payload.end - payload.start), writer)?;
+
+    if len < payload.end - payload.start {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(len)
+}
+
+// Locate the superbox identified by `label`, reading only one level at a
+// time and only along the path to it. Returns the superbox's layout and the
+// index of its first data box.
+fn find_data_box<R: Read + Seek>(reader: &mut R, label: &str) -> IoResult<(BoxLayout, usize)> {
+    let options = ParseOptions::default().set_depth_limit(0);
+    let mut sbox = BoxLayout::from_reader_with_options(reader, &options)?;
+
+    for component in LabelPath::from(label).components() {
+        let mut found = None;
+        for (i, child) in sbox.children.iter_mut().enumerate() {
+            if child.tbox == SUPER_BOX_TYPE {
+                child.expand(reader, &options)?;
+                if child.label.as_deref() == Some(component) {
+                    if found.is_some() {
+                        return Err(invalid_input(format!(
+                            "more than one superbox with label {label:?}"
+                        )));
+                    }
+                    found = Some(i);
+                }
+            }
+        }
+
+        let Some(i) = found else {
+            return Err(invalid_input(format!("no superbox with label {label:?}")));
+        };
+        sbox = sbox.children.swap_remove(i);
+    }
+
+    let Some(i) = sbox
+        .children
+        .iter()
+        .position(|c| c.tbox != DESCRIPTION_BOX_TYPE && c.tbox != SUPER_BOX_TYPE)
+    else {
+        return Err(invalid_input(format!(
+            "superbox with label {label:?} has no data box"
+        )));
+    };
+
+    Ok((sbox, i))
+}
+
+const FREE_BOX_TYPE: BoxType = BoxType(*b"free");
+
+fn invalid_input(message: String) -> IoError {
+    IoError::new(ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(err: Error) -> IoError {
+    IoError::new(ErrorKind::InvalidData, err)
+}
+
+// Offsets and lengths in errors are reported as `usize`, which may be
+// narrower than a stream offset on 32-bit targets.
+fn to_usize(n: u64) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+// Read a box header at the current position. The box must end at or before
+// `end`, which is either the end of the stream or, if `within_parent` is
+// true, the end of the enclosing superbox.
+fn read_header<R: Read + Seek>(
+    reader: &mut TrackedReader<R>,
+    end: u64,
+    within_parent: bool,
+) -> IoResult<BoxLayout> {
+    let offset = reader.pos;
+    let available = end.saturating_sub(offset);
+
+    // Report a box which doesn't fit in the space available as truncated
+    // input at the top level, or as a malformed box within a superbox.
+    let doesnt_fit = |declared: Option<u64>, needed: u64| {
+        let offset = to_usize(offset);
+        invalid_data(match (within_parent, declared) {
+            (false, _) => Error::Truncated {
+                needed: to_usize(needed),
+                offset,
+            },
+            (true, None) => Error::CorruptHeader { offset },
+            (true, Some(declared)) => Error::SizeMismatch {
+                offset,
+                declared,
+                available: to_usize(available),
+            },
+        })
+    };
+
+    if available < 8 {
+        return Err(doesnt_fit(None, 8 - available));
+    }
+
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+
+    let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let tbox = BoxType::from(&header[4..8]);
+
+    let (header_len, len) = match size {
+        0 => (8, available),
+        1 => {
+            if available < 16 {
+                return Err(doesnt_fit(None, 16 - available));
+            }
+
+            let mut xlbox = [0u8; 8];
+            reader.read_exact(&mut xlbox)?;
+            let len = u64::from_be_bytes(xlbox);
+            if len < 16 {
+                // `len` is less than 16 here, so the conversion never clamps.
+                let len = u32::try_from(len).unwrap_or(u32::MAX);
+                return Err(invalid_data(Error::InvalidBoxLength(len)));
+            }
+            (16, len)
+        }
+        2..=7 => {
+            return Err(invalid_data(Error::InvalidBoxLength(size)));
+        }
+        _ => (8, size as u64),
+    };
+
+    if len > available {
+        return Err(doesnt_fit(Some(len), len - available));
+    }
+
+    Ok(BoxLayout {
+        tbox,
+        offset,
+        header_len,
+        len,
+        label: None,
+        children: vec![],
+    })
+}
+
+fn read_super_box<R: Read + Seek>(
+    reader: &mut TrackedReader<R>,
+    layout: &mut BoxLayout,
+    options: &ParseOptions,
+    depth: usize,
+) -> IoResult<()> {
+    check_cancelled(options)?;
+
+    let end = layout.range().end;
+    let mut pos = layout.payload_range().start;
+    let mut found_desc = false;
+
+    while pos < end {
+        reader.seek_to(pos)?;
+        let mut child = read_header(reader, end, true)?;
+
+        if child.tbox == DESCRIPTION_BOX_TYPE {
+            if !found_desc {
+                // As for `SuperBox`, the description box must come first.
+                if !layout.children.is_empty() && !options.lenient_description_box {
+                    return Err(invalid_data(Error::MisplacedDescriptionBox));
+                }
+                layout.label = read_label(reader, &child, options)?;
+                found_desc = true;
+            } else if !options.lenient_description_box {
+                return Err(invalid_data(Error::DuplicateDescriptionBox));
+            }
+        } else if child.tbox == SUPER_BOX_TYPE && depth < options.depth_limit {
+            read_super_box(reader, &mut child, options, depth + 1)?;
+        }
+
+        pos = child.range().end;
+        layout.children.push(child);
+    }
+
+    if !found_desc && !options.lenient_description_box {
+        return Err(invalid_data(Error::MissingDescriptionBox));
+    }
+
+    Ok(())
+}
+
+// Read the label, if any, from a description box whose header has just been
+// read.
+fn read_label<R: Read>(
+    reader: &mut R,
+    desc: &BoxLayout,
+    options: &ParseOptions,
+) -> IoResult<Option<String>> {
+    let payload_len = desc.len - desc.header_len;
+    let corrupt = || {
+        invalid_data(Error::CorruptHeader {
+            offset: to_usize(desc.offset),
+        })
+    };
+
+    if payload_len < 17 {
+        return Err(corrupt());
+    }
+
+    let mut uuid_and_toggles = [0u8; 17];
+    reader.read_exact(&mut uuid_and_toggles)?;
+
+    let toggles = Toggles::from_bits(uuid_and_toggles[16]);
+    if !toggles.contains(Toggles::HAS_LABEL) {
+        return Ok(None);
+    }
+
+    let mut remaining = payload_len - 17;
+    let mut label: Vec<u8> = vec![];
+    let mut chunk = [0u8; 256];
+
+    loop {
+        if remaining == 0 {
+            return Err(corrupt());
+        }
+
+        let chunk_len = chunk
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let chunk = &mut chunk[..chunk_len];
+        reader.read_exact(chunk)?;
+        remaining -= chunk_len as u64;
+
+        let nul = chunk.iter().position(|b| *b == 0);
+        label.extend_from_slice(&chunk[..nul.unwrap_or(chunk_len)]);
+
+        if let Some(max_label_len) = options.max_label_len {
+            if label.len() > max_label_len {
+                return Err(invalid_data(Error::LabelTooLong(max_label_len)));
+            }
+        }
+
+        if nul.is_some() {
+            break;
+        }
+    }
+
+    match String::from_utf8(label) {
+        Ok(label) => Ok(Some(label)),
+        Err(_) if options.lenient_label_encoding => Ok(None),
+        Err(err) => Err(invalid_data(Error::Utf8Error(err.utf8_error()))),
+    }
+}
+
+// Skips of up to this many bytes are made by reading rather than seeking.
+const MAX_READ_AHEAD: u64 = 4096;
+
+// Wraps the reader passed to `BoxLayout` and keeps track of the stream
+// position, so it isn't necessary to ask the reader for it.
+//
+// Short forward skips (typically over small payloads) are made by reading
+// and discarding data. Seeking discards the buffer of a reader such as
+// `BufReader`, so seeking past every payload would otherwise defeat
+// buffering entirely.
+struct TrackedReader<'r, R> {
+    inner: &'r mut R,
+    pos: u64,
+}
+
+impl<'r, R: Read + Seek> TrackedReader<'r, R> {
+    fn new(inner: &'r mut R, pos: u64) -> Self {
+        Self { inner, pos }
+    }
+
+    fn seek_to(&mut self, pos: u64) -> IoResult<()> {
+        if pos == self.pos {
+            return Ok(());
+        }
+
+        if pos > self.pos && pos - self.pos <= MAX_READ_AHEAD {
+            let len = pos - self.pos;
+            if copy(&mut self.by_ref().take(len), &mut sink())? == len {
+                return Ok(());
+            }
+        }
+
+        self.pos = self.inner.seek(SeekFrom::Start(pos))?;
+        Ok(())
+    }
+}
+
+impl<'r, R: Read> Read for TrackedReader<'r, R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let len = self.inner.read(buf)?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+fn check_cancelled(options: &ParseOptions) -> IoResult<()> {
+    match options.cancel.as_ref() {
+        Some(cancel) if cancel() => Err(invalid_data(Error::Cancelled)),
+        _ => Ok(()),
+    }
+}
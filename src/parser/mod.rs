@@ -14,14 +14,80 @@
 //! An efficient (zero-copy) parser for [JUMBF (ISO/IEC 19566-5:2019)]
 //! data structures.
 //!
+//! ## Return values
+//!
+//! Every parsing function in this module follows the [nom] convention of
+//! returning a [`ParseResult`] containing a tuple of the _remainder_ of the
+//! input followed by the parsed value:
+//!
+//! ```
+//! use hex_literal::hex;
+//! use jumbf::parser::{DataBox, DescriptionBox, SuperBox};
+//!
+//! let jumbf = hex!(
+//!     "0000002f" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "00000027" // box size
+//!         "6a756d64" // box type = 'jumd'
+//!         "00000000000000000000000000000000" // UUID
+//!         "03" // toggles
+//!         "746573742e7375706572626f7800" // label
+//!     "ffff" // trailing data
+//! );
+//!
+//! let (rem, dbox) = DataBox::from_slice(&jumbf).unwrap();
+//! assert_eq!(rem, [0xff, 0xff]);
+//!
+//! let (rem, desc) = DescriptionBox::from_slice(dbox.data).unwrap();
+//! assert!(rem.is_empty());
+//! assert_eq!(desc.label, Some("test.superbox"));
+//!
+//! let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+//! assert_eq!(rem, [0xff, 0xff]);
+//! assert_eq!(sbox.desc, desc);
+//! ```
+//!
+//! The remainder is typically empty when parsing a complete JUMBF data
+//! structure, but may contain additional boxes when parsing a sequence of
+//! boxes.
+//!
 //! [JUMBF (ISO/IEC 19566-5:2019)]: https://www.iso.org/standard/73604.html
+//! [nom]: https://docs.rs/nom
+
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+
+#[cfg(feature = "c2pa")]
+pub mod c2pa;
 
-mod data_box;
+pub mod conformance;
+
+pub(crate) mod data_box;
 mod description_box;
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+
+mod diff;
 mod error;
+mod hashed_uri;
+pub mod hexdump;
+mod label_match;
+pub(crate) mod label_path;
+mod label_pattern;
+pub mod layout;
+mod options;
+mod summary;
 mod super_box;
+mod validation;
 
 pub use data_box::DataBox;
 pub use description_box::DescriptionBox;
-pub use error::{Error, ParseResult};
-pub use super_box::{ChildBox, SuperBox};
+pub use diff::{BoxDiff, DiffKind};
+pub use error::{Error, ParseResult, RecoveredError};
+pub use hashed_uri::HashedUri;
+pub use label_match::LabelMatch;
+pub use label_path::{escape_label, LabelPath};
+pub use options::{ParseMetrics, ParseOptions};
+pub use super_box::{boxes_from_slice, ChildBox, DataMatch, SuperBox};
+pub use validation::{ValidationIssue, ValidationReport};
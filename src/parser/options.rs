@@ -0,0 +1,591 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    parser::{DataBox, Error, RecoveredError},
+    BoxType,
+};
+
+/// A `ParseOptions` struct describes how [`SuperBox`] parsing should behave.
+///
+/// Construct one with [`ParseOptions::default()`] and then call one or more
+/// of the `set_*` methods to adjust it.
+///
+/// [`SuperBox`]: crate::parser::SuperBox
+#[derive(Clone)]
+pub struct ParseOptions {
+    pub(crate) depth_limit: usize,
+    pub(crate) progress: Option<ProgressCallback>,
+    pub(crate) cancel: Option<CancelCallback>,
+    pub(crate) lenient_description_box: bool,
+    pub(crate) demote_malformed_super_boxes: bool,
+    pub(crate) reject_trailing_data: bool,
+    pub(crate) max_label_len: Option<usize>,
+    pub(crate) lenient_label_encoding: bool,
+    pub(crate) box_type_filter: Option<BoxTypeFilter>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum BoxTypeFilter {
+    Skip(Vec<BoxType>),
+    Only(Vec<BoxType>),
+}
+
+type ProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+type CancelCallback = Arc<dyn Fn() -> bool + Send + Sync>;
+
+impl ParseOptions {
+    /// The maximum number of nested superboxes that will be parsed unless
+    /// [`set_depth_limit()`] is called.
+    ///
+    /// Superboxes nested more deeply than this are returned as plain
+    /// [`DataBox`] structs, which protects against stack exhaustion when
+    /// parsing untrusted input.
+    ///
+    /// [`set_depth_limit()`]: Self::set_depth_limit
+    /// [`DataBox`]: crate::parser::DataBox
+    pub const DEFAULT_DEPTH_LIMIT: usize = 64;
+
+    /// Limit recursion into child superboxes to `depth_limit` nested boxes.
+    /// The default is [`DEFAULT_DEPTH_LIMIT`].
+    ///
+    /// If `depth_limit` is 0, any child superboxes that are found will be
+    /// returned as plain [`DataBox`] structs instead. Pass `usize::MAX` to
+    /// recurse without limit, as earlier versions of this crate did.
+    ///
+    /// [`DEFAULT_DEPTH_LIMIT`]: Self::DEFAULT_DEPTH_LIMIT
+    /// [`DataBox`]: crate::parser::DataBox
+    pub fn set_depth_limit(mut self, depth_limit: usize) -> Self {
+        self.depth_limit = depth_limit;
+        self
+    }
+
+    /// Provide a callback which will be invoked as parsing progresses.
+    ///
+    /// The callback receives two arguments: the number of bytes consumed
+    /// so far and the total number of bytes in the input. It is invoked
+    /// once after each box is parsed, so it may be called many times for a
+    /// large JUMBF data structure.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let consumed = Arc::new(AtomicUsize::new(0));
+    /// let consumed_cb = consumed.clone();
+    ///
+    /// let options = ParseOptions::default().set_progress_callback(move |done, total| {
+    ///     assert_eq!(total, 47);
+    ///     consumed_cb.store(done, Ordering::Relaxed);
+    /// });
+    ///
+    /// SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    /// assert_eq!(consumed.load(Ordering::Relaxed), 47);
+    /// ```
+    pub fn set_progress_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(usize, usize) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Provide a callback which will be checked between boxes to determine
+    /// whether parsing should be abandoned.
+    ///
+    /// If the callback returns `true`, parsing stops and
+    /// [`Error::Cancelled`] is returned.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicBool, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{Error, ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let cancelled = Arc::new(AtomicBool::new(true));
+    /// let cancelled_cb = cancelled.clone();
+    ///
+    /// let options =
+    ///     ParseOptions::default().set_cancel_callback(move || cancelled_cb.load(Ordering::Relaxed));
+    ///
+    /// assert_eq!(
+    ///     SuperBox::from_slice_with_options(&jumbf, &options).unwrap_err(),
+    ///     nom::Err::Error(Error::Cancelled)
+    /// );
+    /// ```
+    ///
+    /// [`Error::Cancelled`]: crate::parser::Error::Cancelled
+    pub fn set_cancel_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.cancel = Some(Arc::new(callback));
+        self
+    }
+
+    /// Tolerate superboxes whose description box is missing, misplaced, or
+    /// duplicated.
+    ///
+    /// By default, a superbox must begin with exactly one description box.
+    /// Otherwise, [`Error::MissingDescriptionBox`],
+    /// [`Error::MisplacedDescriptionBox`], or
+    /// [`Error::DuplicateDescriptionBox`] is returned.
+    ///
+    /// When `lenient` is `true`, parsing continues so that the child boxes
+    /// remain available:
+    ///
+    /// * If the description box is not the first box, it is used as the
+    ///   superbox's description box and the boxes around it become child boxes.
+    /// * If there is more than one description box, the first one is used and
+    ///   the others become [`DataBox`] children.
+    /// * If there is no description box, the superbox is given an empty
+    ///   description box with a zero UUID and no label. Its [`original`] field
+    ///   will be empty.
+    ///
+    /// A superbox parsed this way is not re-serialized to its original bytes:
+    /// [`SuperBox::write_to()`] always writes the description box first, so a
+    /// misplaced description box is moved to the front and a missing one is
+    /// replaced by an empty description box.
+    ///
+    /// [`Error::MissingDescriptionBox`]: crate::parser::Error::MissingDescriptionBox
+    /// [`Error::MisplacedDescriptionBox`]: crate::parser::Error::MisplacedDescriptionBox
+    /// [`Error::DuplicateDescriptionBox`]: crate::parser::Error::DuplicateDescriptionBox
+    /// [`DataBox`]: crate::parser::DataBox
+    /// [`original`]: crate::parser::DescriptionBox::original
+    /// [`SuperBox::write_to()`]: crate::parser::SuperBox::write_to
+    pub fn set_lenient_description_box(mut self, lenient: bool) -> Self {
+        self.lenient_description_box = lenient;
+        self
+    }
+
+    /// Return child superboxes which can't be parsed as plain [`DataBox`]
+    /// structs instead of failing to parse the parent superbox.
+    ///
+    /// This is similar to the behavior of [`set_depth_limit()`] for
+    /// superboxes nested beyond the depth limit. The demoted box's header
+    /// must still be valid, since its declared size is used to find the next
+    /// box.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{ChildBox, ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000003b" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    ///         // ---
+    ///         "0000000c" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///         "00000004" // (INVALID) child box
+    /// );
+    ///
+    /// assert!(SuperBox::from_slice(&jumbf).is_err());
+    ///
+    /// let options = ParseOptions::default().set_demote_malformed_super_boxes(true);
+    /// let (_, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    /// assert!(matches!(sbox.child_boxes[0], ChildBox::DataBox(_)));
+    /// ```
+    ///
+    /// [`DataBox`]: crate::parser::DataBox
+    /// [`set_depth_limit()`]: Self::set_depth_limit
+    pub fn set_demote_malformed_super_boxes(mut self, demote: bool) -> Self {
+        self.demote_malformed_super_boxes = demote;
+        self
+    }
+
+    /// Reject input which has unexpected data after the top-level superbox.
+    ///
+    /// By default, any such data is returned as the remainder of the input
+    /// and its length is reported in [`ParseMetrics::trailing_bytes`]. When
+    /// `reject` is `true`, [`Error::TrailingData`] is returned instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{Error, ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    ///     "ffff" // trailing data
+    /// );
+    ///
+    /// let options = ParseOptions::default().set_reject_trailing_data(true);
+    ///
+    /// assert_eq!(
+    ///     SuperBox::from_slice_with_options(&jumbf, &options).unwrap_err(),
+    ///     nom::Err::Error(Error::TrailingData { offset: 47, len: 2 })
+    /// );
+    /// ```
+    ///
+    /// [`Error::TrailingData`]: crate::parser::Error::TrailingData
+    pub fn set_reject_trailing_data(mut self, reject: bool) -> Self {
+        self.reject_trailing_data = reject;
+        self
+    }
+
+    /// Limit description box labels to `max_label_len` bytes, not including
+    /// the NUL terminator.
+    ///
+    /// The parser stops searching for the end of a label once this many
+    /// bytes have been examined and returns [`Error::LabelTooLong`]. This
+    /// bounds the work done for hostile input which declares a very large
+    /// description box. There is no limit by default.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{Error, ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label = "test.superbox"
+    /// );
+    ///
+    /// let options = ParseOptions::default().set_max_label_len(8);
+    ///
+    /// assert_eq!(
+    ///     SuperBox::from_slice_with_options(&jumbf, &options).unwrap_err(),
+    ///     nom::Err::Error(Error::LabelTooLong(8))
+    /// );
+    /// ```
+    ///
+    /// [`Error::LabelTooLong`]: crate::parser::Error::LabelTooLong
+    pub fn set_max_label_len(mut self, max_label_len: usize) -> Self {
+        self.max_label_len = Some(max_label_len);
+        self
+    }
+
+    /// Tolerate description box labels which aren't valid UTF-8.
+    ///
+    /// By default, [`Error::Utf8Error`] is returned for such labels. When
+    /// `lenient` is `true`, the description box's [`label`] is `None` and the
+    /// undecoded bytes are available via [`DescriptionBox::raw_label()`] so
+    /// that tools can inspect and repair the file.
+    ///
+    /// [`Error::Utf8Error`]: crate::parser::Error::Utf8Error
+    /// [`label`]: crate::parser::DescriptionBox::label
+    /// [`DescriptionBox::raw_label()`]: crate::parser::DescriptionBox::raw_label
+    pub fn set_lenient_label_encoding(mut self, lenient: bool) -> Self {
+        self.lenient_label_encoding = lenient;
+        self
+    }
+
+    /// Skip child boxes whose box type is one of `box_types`.
+    ///
+    /// A skipped box is returned as a plain [`DataBox`] with its payload
+    /// intact, so it is serialized exactly as it was read. A skipped
+    /// superbox is not parsed, so its descendants aren't examined and can't
+    /// be found by label. This is useful for avoiding the cost of parsing
+    /// parts of a large data structure which aren't of interest.
+    ///
+    /// Description boxes are never skipped.
+    ///
+    /// This replaces any filter set by [`set_only_box_types()`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::{
+    ///     parser::{ChildBox, ParseOptions, SuperBox},
+    ///     BoxType,
+    /// };
+    ///
+    /// let jumbf = hex!(
+    ///     "00000047" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    ///         // ---
+    ///         "00000018" // box size
+    ///         "62696462" // box type = 'bidb'
+    ///         "00000000000000000000000000000000" // payload
+    /// );
+    ///
+    /// let options = ParseOptions::default().set_skip_box_types([BoxType(*b"bidb")]);
+    /// let (_, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    ///
+    /// let ChildBox::DataBox(dbox) = &sbox.child_boxes[0] else {
+    ///     panic!("expected a data box");
+    /// };
+    /// assert_eq!(dbox.tbox, BoxType(*b"bidb"));
+    /// assert_eq!(dbox.data.len(), 16);
+    /// assert_eq!(dbox.original.len(), 24);
+    /// ```
+    ///
+    /// [`DataBox`]: crate::parser::DataBox
+    /// [`set_only_box_types()`]: Self::set_only_box_types
+    pub fn set_skip_box_types(mut self, box_types: impl IntoIterator<Item = BoxType>) -> Self {
+        self.box_type_filter = Some(BoxTypeFilter::Skip(box_types.into_iter().collect()));
+        self
+    }
+
+    /// Skip child boxes whose box type is _not_ one of `box_types`.
+    ///
+    /// Skipped boxes are handled as described for [`set_skip_box_types()`].
+    /// Description boxes are never skipped. Include `BoxType(*b"jumb")` in
+    /// `box_types` to parse child superboxes.
+    ///
+    /// This replaces any filter set by [`set_skip_box_types()`].
+    ///
+    /// [`set_skip_box_types()`]: Self::set_skip_box_types
+    pub fn set_only_box_types(mut self, box_types: impl IntoIterator<Item = BoxType>) -> Self {
+        self.box_type_filter = Some(BoxTypeFilter::Only(box_types.into_iter().collect()));
+        self
+    }
+
+    // Returns `true` if child boxes of type `tbox` should be skipped.
+    pub(crate) fn skips_box_type(&self, tbox: BoxType) -> bool {
+        match self.box_type_filter.as_ref() {
+            None => false,
+            Some(BoxTypeFilter::Skip(box_types)) => box_types.contains(&tbox),
+            Some(BoxTypeFilter::Only(box_types)) => !box_types.contains(&tbox),
+        }
+    }
+}
+
+impl Debug for ParseOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("ParseOptions")
+            .field("depth_limit", &self.depth_limit)
+            .field("progress", &self.progress.as_ref().map(|_| "callback"))
+            .field("cancel", &self.cancel.as_ref().map(|_| "callback"))
+            .field("lenient_description_box", &self.lenient_description_box)
+            .field(
+                "demote_malformed_super_boxes",
+                &self.demote_malformed_super_boxes,
+            )
+            .field("reject_trailing_data", &self.reject_trailing_data)
+            .field("max_label_len", &self.max_label_len)
+            .field("lenient_label_encoding", &self.lenient_label_encoding)
+            .field("box_type_filter", &self.box_type_filter)
+            .finish()
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            depth_limit: Self::DEFAULT_DEPTH_LIMIT,
+            progress: None,
+            cancel: None,
+            lenient_description_box: false,
+            demote_malformed_super_boxes: false,
+            reject_trailing_data: false,
+            max_label_len: None,
+            lenient_label_encoding: false,
+            box_type_filter: None,
+        }
+    }
+}
+
+/// Describes the work done while parsing a JUMBF superbox.
+///
+/// This is intended for monitoring pipelines that process untrusted input.
+/// Use [`SuperBox::from_slice_with_metrics()`] to obtain it.
+///
+/// [`SuperBox::from_slice_with_metrics()`]: crate::parser::SuperBox::from_slice_with_metrics
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParseMetrics {
+    /// Number of boxes parsed, including superboxes, description boxes,
+    /// private boxes, and data boxes.
+    pub boxes_parsed: usize,
+
+    /// Number of bytes of input consumed.
+    pub bytes_consumed: usize,
+
+    /// Deepest level of superbox nesting encountered. A superbox which has
+    /// no child superboxes has a depth of 1.
+    pub max_depth: usize,
+
+    /// Number of bytes of input following the top-level superbox.
+    pub trailing_bytes: usize,
+
+    /// Time spent parsing.
+    ///
+    /// Will be `None` on platforms where [`std::time::Instant`] is not
+    /// available.
+    pub elapsed: Option<Duration>,
+}
+
+// Carries options and running state through a recursive parse.
+pub(crate) struct ParseContext<'o> {
+    pub(crate) options: &'o ParseOptions,
+    pub(crate) metrics: ParseMetrics,
+
+    // Errors which were recovered from, if recovery is enabled.
+    pub(crate) recovered: Option<Vec<RecoveredError>>,
+
+    // Address and length of the input, used for progress reporting.
+    input_start: usize,
+    input_len: usize,
+}
+
+impl<'o> ParseContext<'o> {
+    pub(crate) fn new(options: &'o ParseOptions, input: &[u8]) -> Self {
+        Self {
+            options,
+            metrics: ParseMetrics::default(),
+            recovered: None,
+            input_start: input.as_ptr() as usize,
+            input_len: input.len(),
+        }
+    }
+
+    // Report that the box ending at the end of `original` has been parsed.
+    pub(crate) fn report_progress(&self, original: &[u8]) {
+        if let Some(progress) = self.options.progress.as_ref() {
+            let end = (original.as_ptr() as usize + original.len())
+                .saturating_sub(self.input_start)
+                .min(self.input_len);
+            progress(end, self.input_len);
+        }
+    }
+
+    // Returns the offset of `at` within the input.
+    pub(crate) fn offset_of(&self, at: &[u8]) -> usize {
+        (at.as_ptr() as usize).saturating_sub(self.input_start)
+    }
+
+    // Returns `error`, which was reported relative to the start of `at`,
+    // with its offset (if any) made relative to the start of the input.
+    pub(crate) fn locate(&self, at: &[u8], error: Error) -> Error {
+        error.offset_by(self.offset_of(at))
+    }
+
+    // Return an error if the caller has asked to cancel parsing.
+    pub(crate) fn check_cancelled(&self) -> Result<(), Error> {
+        match self.options.cancel.as_ref() {
+            Some(cancel) if cancel() => Err(Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    // If recovery is enabled, record that `error` occurred while parsing the
+    // box which starts at `at` and return `Ok` so that parsing can continue.
+    // Otherwise, return the error.
+    pub(crate) fn recover(
+        &mut self,
+        at: &[u8],
+        tbox: Option<BoxType>,
+        error: Error,
+    ) -> Result<(), Error> {
+        let offset = self.offset_of(at);
+        match self.recovered.as_mut() {
+            Some(recovered) if error != Error::Cancelled => {
+                recovered.push(RecoveredError {
+                    offset,
+                    tbox,
+                    error,
+                });
+                Ok(())
+            }
+            _ => Err(error),
+        }
+    }
+
+    // Decide what to do when the child superbox `sbox` fails to parse.
+    // Returns `Ok` if it should be kept as a plain data box instead.
+    pub(crate) fn demote_super_box(&mut self, sbox: &DataBox, error: Error) -> Result<(), Error> {
+        match self.recover(sbox.original, Some(sbox.tbox), error) {
+            Err(error)
+                if self.options.demote_malformed_super_boxes && error != Error::Cancelled =>
+            {
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    // Record how much input follows the top-level box and reject it if
+    // the options ask for that.
+    pub(crate) fn check_trailing_data(&mut self, rem: &[u8]) -> Result<(), Error> {
+        self.metrics.trailing_bytes = rem.len();
+
+        if self.options.reject_trailing_data && !rem.is_empty() {
+            return Err(Error::TrailingData {
+                offset: self.input_len - rem.len(),
+                len: rem.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn enter_super_box(&mut self, depth: usize) {
+        // Count the superbox and its description box.
+        self.metrics.boxes_parsed += 2;
+        self.metrics.max_depth = self.metrics.max_depth.max(depth + 1);
+    }
+}
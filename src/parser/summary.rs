@@ -0,0 +1,106 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::fmt::Write;
+
+use crate::{
+    parser::{ChildBox, DataBox, SuperBox},
+    BoxType,
+};
+
+pub(crate) fn summary_json(sbox: &SuperBox) -> String {
+    let mut json = String::new();
+    write_super_box(&mut json, sbox, true);
+    json
+}
+
+// Write `{"label":...,"uuid":...,"size":...,"children":...}` for `sbox`,
+// with `"content_type":...` after the UUID if the content type is known.
+// If `expand` is false, only the number of children is reported.
+fn write_super_box(json: &mut String, sbox: &SuperBox, expand: bool) {
+    json.push_str("{\"label\":");
+    match sbox.desc.label {
+        Some(label) => write_string(json, label),
+        None => json.push_str("null"),
+    }
+
+    json.push_str(",\"uuid\":\"");
+    for b in sbox.desc.uuid {
+        let _ = write!(json, "{b:02x}");
+    }
+    json.push('"');
+
+    if let Some(ct) = sbox.desc.content_type() {
+        json.push_str(",\"content_type\":");
+        write_string(json, ct.name);
+    }
+
+    let _ = write!(json, ",\"size\":{},\"children\":", sbox.serialized_len());
+
+    if !expand {
+        let _ = write!(json, "{}", sbox.child_boxes.len());
+        json.push('}');
+        return;
+    }
+
+    json.push('[');
+    for (i, child) in sbox.child_boxes.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        match child {
+            ChildBox::SuperBox(sbox) => write_super_box(json, sbox, false),
+            ChildBox::DataBox(dbox) => write_data_box(json, dbox),
+        }
+    }
+    json.push_str("]}");
+}
+
+fn write_data_box(json: &mut String, dbox: &DataBox) {
+    json.push_str("{\"box\":");
+    write_box_type(json, dbox.tbox);
+    let _ = write!(json, ",\"size\":{}}}", dbox.serialized_len());
+}
+
+// Box types are usually printable ASCII; anything else is written as
+// hex so the output remains readable.
+fn write_box_type(json: &mut String, tbox: BoxType) {
+    if tbox.0.iter().all(|c| (0x20..=0x7e).contains(c)) {
+        let s: String = tbox.0.iter().map(|c| *c as char).collect();
+        write_string(json, &s);
+    } else {
+        let _ = write!(
+            json,
+            "\"0x{:02x}{:02x}{:02x}{:02x}\"",
+            tbox.0[0], tbox.0[1], tbox.0[2], tbox.0[3]
+        );
+    }
+}
+
+fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
@@ -11,12 +11,27 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::fmt::{Debug, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Formatter},
+    io::{Result as IoResult, Write},
+};
 
 use crate::{
-    box_type::SUPER_BOX_TYPE,
+    box_type::{C2PA_SALT_BOX_TYPE, DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
     debug::*,
-    parser::{DataBox, DescriptionBox, Error, ParseResult},
+    digest::Digest,
+    parser::{
+        data_box::{header_len_like, write_header_like},
+        diff::diff_super_boxes,
+        escape_label,
+        label_pattern::LabelPattern,
+        options::ParseContext,
+        summary::summary_json,
+        BoxDiff, DataBox, DescriptionBox, Error, HashedUri, LabelMatch, LabelPath, ParseMetrics,
+        ParseOptions, ParseResult, RecoveredError, ValidationReport,
+    },
+    BoxType,
 };
 
 /// A JUMBF superbox contains a description box and zero or more
@@ -41,13 +56,151 @@ pub struct SuperBox<'a> {
 impl<'a> SuperBox<'a> {
     /// Parse a byte-slice as a JUMBF superbox, and return a tuple of the
     /// remainder of the input and the parsed super box. Children of this
-    /// superbox which are also superboxes will be parsed recursively without
-    /// limit.
+    /// superbox which are also superboxes will be parsed recursively, to a
+    /// limit of [`DEFAULT_DEPTH_LIMIT`] nested boxes.
     ///
     /// The returned object uses zero-copy, and so has the same lifetime as the
     /// input.
+    ///
+    /// [`DEFAULT_DEPTH_LIMIT`]: ParseOptions::DEFAULT_DEPTH_LIMIT
     pub fn from_slice(i: &'a [u8]) -> ParseResult<'a, Self> {
-        Self::from_slice_with_depth_limit(i, usize::MAX)
+        Self::from_slice_with_options(i, &ParseOptions::default())
+    }
+
+    /// Parse a byte-slice which contains exactly one JUMBF superbox.
+    ///
+    /// Children of this superbox which are also superboxes will be parsed
+    /// recursively, to a limit of [`DEFAULT_DEPTH_LIMIT`] nested boxes. Unlike
+    /// [`from_slice()`], an error is returned if any data follows the
+    /// superbox.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let sbox = SuperBox::parse(&jumbf).unwrap();
+    /// assert_eq!(sbox.desc.label, Some("test.superbox"));
+    /// ```
+    ///
+    /// [`DEFAULT_DEPTH_LIMIT`]: ParseOptions::DEFAULT_DEPTH_LIMIT
+    /// [`from_slice()`]: Self::from_slice
+    pub fn parse(i: &'a [u8]) -> Result<Self, Error> {
+        let options = ParseOptions::default().set_reject_trailing_data(true);
+        let (_, sbox) = Self::from_slice_with_options(i, &options)?;
+        Ok(sbox)
+    }
+
+    /// Parse only the descendant superbox at `path` from a byte-slice which
+    /// contains exactly one JUMBF superbox.
+    ///
+    /// `path` is interpreted as for [`find_by_label()`]. Only the
+    /// description boxes of the superboxes along the path (and of their
+    /// sibling superboxes) are parsed; the contents of unrelated siblings
+    /// are skipped entirely. The superbox at the end of the path is then
+    /// parsed in full, as by [`parse()`]. This is much cheaper than parsing
+    /// the entire tree when a single superbox is needed from a large input.
+    ///
+    /// Returns `Ok(None)` if no matching superbox is found _or_ if more than
+    /// one matching superbox is found at any step.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let claim = SuperBox::parse_path(&jumbf, "C.Claim").unwrap().unwrap();
+    /// assert_eq!(claim.desc.label, Some("C.Claim"));
+    ///
+    /// assert!(SuperBox::parse_path(&jumbf, "C.Other").unwrap().is_none());
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    /// [`parse()`]: Self::parse
+    pub fn parse_path(i: &'a [u8], path: &str) -> Result<Option<Self>, Error> {
+        let options = ParseOptions::default();
+        let label_match = LabelMatch::default();
+        let offset_of = |slice: &[u8]| slice.as_ptr() as usize - i.as_ptr() as usize;
+
+        let (rem, mut sbox) = DataBox::from_slice(i)?;
+        if !rem.is_empty() {
+            return Err(Error::TrailingData {
+                offset: offset_of(rem),
+                len: rem.len(),
+            });
+        }
+        if sbox.tbox != SUPER_BOX_TYPE {
+            return Err(Error::InvalidSuperBoxType(sbox.tbox));
+        }
+
+        for label in LabelPath::from(path).components() {
+            let (mut children, _) = DescriptionBox::from_super_box_payload(sbox.data, &options)
+                .map_err(|err| err.offset_by(offset_of(sbox.data)))?;
+
+            let mut found = None;
+            while !children.is_empty() {
+                let (rem, child) = DataBox::from_bounded_slice(children)
+                    .map_err(|err| Error::from(err).offset_by(offset_of(children)))?;
+                children = rem;
+
+                if child.tbox != SUPER_BOX_TYPE {
+                    continue;
+                }
+
+                let (_, desc) = DescriptionBox::from_super_box_payload(child.data, &options)
+                    .map_err(|err| err.offset_by(offset_of(child.data)))?;
+
+                let matches = desc.requestable
+                    && desc
+                        .label
+                        .is_some_and(|child_label| label_match.matches(child_label, label));
+
+                if matches {
+                    if found.is_some() {
+                        return Ok(None);
+                    }
+                    found = Some(child);
+                }
+            }
+
+            let Some(child) = found else {
+                return Ok(None);
+            };
+            sbox = child;
+        }
+
+        let (_, sbox) = Self::from_data_box_with_options(&sbox, &options)
+            .map_err(|err| Error::from(err).offset_by(offset_of(sbox.original)))?;
+        Ok(Some(sbox))
     }
 
     /// Parse a byte-slice as a JUMBF superbox, and return a tuple of the
@@ -61,22 +214,152 @@ impl<'a> SuperBox<'a> {
     /// The returned object uses zero-copy, and so has the same lifetime as the
     /// input.
     pub fn from_slice_with_depth_limit(i: &'a [u8], depth_limit: usize) -> ParseResult<'a, Self> {
+        Self::from_slice_with_options(i, &ParseOptions::default().set_depth_limit(depth_limit))
+    }
+
+    /// Parse a byte-slice as a JUMBF superbox, and return a tuple of the
+    /// remainder of the input and the parsed super box. Parsing behavior
+    /// is controlled by `options`.
+    ///
+    /// The returned object uses zero-copy, and so has the same lifetime as the
+    /// input.
+    pub fn from_slice_with_options(i: &'a [u8], options: &ParseOptions) -> ParseResult<'a, Self> {
+        let mut ctx = ParseContext::new(options, i);
+        Self::from_slice_with_context(i, &mut ctx)
+    }
+
+    /// Parse a byte-slice as a JUMBF superbox, and return a tuple of the
+    /// remainder of the input and a tuple of the parsed super box and a
+    /// [`ParseMetrics`] struct describing the work done to parse it.
+    ///
+    /// Parsing behavior is controlled by `options`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let (rem, (_sbox, metrics)) =
+    ///     SuperBox::from_slice_with_metrics(&jumbf, &ParseOptions::default()).unwrap();
+    /// assert!(rem.is_empty());
+    ///
+    /// assert_eq!(metrics.boxes_parsed, 2);
+    /// assert_eq!(metrics.bytes_consumed, 47);
+    /// assert_eq!(metrics.max_depth, 1);
+    /// ```
+    pub fn from_slice_with_metrics(
+        i: &'a [u8],
+        options: &ParseOptions,
+    ) -> ParseResult<'a, (Self, ParseMetrics)> {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        let start = std::time::Instant::now();
+
+        let mut ctx = ParseContext::new(options, i);
+        let (i, sbox) = Self::from_slice_with_context(i, &mut ctx)?;
+
+        let mut metrics = ctx.metrics;
+
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            metrics.elapsed = Some(start.elapsed());
+        }
+
+        Ok((i, (sbox, metrics)))
+    }
+
+    /// Parse a byte-slice as a JUMBF superbox, skipping over malformed child
+    /// boxes rather than failing.
+    ///
+    /// Returns a tuple of the remainder of the input and a tuple of the
+    /// parsed superbox and a list of the errors that were skipped over.
+    /// Parsing behavior is otherwise controlled by `options`.
+    ///
+    /// When a child box can't be parsed, an entry is added to the error list
+    /// and parsing continues:
+    ///
+    /// * If a child superbox is malformed, it is returned as a plain
+    ///   [`DataBox`] and parsing continues with the next box, as found using
+    ///   the child's declared size.
+    /// * If a child box's header is malformed, there is no way to find the next
+    ///   box, so the remainder of the enclosing superbox is skipped.
+    ///
+    /// An error is still returned if the outermost superbox can't be parsed
+    /// or if parsing is cancelled.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{ChildBox, Error, ParseOptions, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "00000047" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    ///         // ---
+    ///         "0000000c" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///         "00000004" // (INVALID) child box
+    ///         // ---
+    ///         "0000000c" // box size
+    ///         "61626364" // box type = 'abcd'
+    ///         "64617461" // payload
+    /// );
+    ///
+    /// let (_, (sbox, errors)) =
+    ///     SuperBox::from_slice_with_recovery(&jumbf, &ParseOptions::default()).unwrap();
+    ///
+    /// assert_eq!(sbox.child_boxes.len(), 2);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].offset, 47);
+    /// ```
+    pub fn from_slice_with_recovery(
+        i: &'a [u8],
+        options: &ParseOptions,
+    ) -> ParseResult<'a, (Self, Vec<RecoveredError>)> {
+        let mut ctx = ParseContext::new(options, i);
+        ctx.recovered = Some(vec![]);
+
+        let (i, sbox) = Self::from_slice_with_context(i, &mut ctx)?;
+        Ok((i, (sbox, ctx.recovered.unwrap_or_default())))
+    }
+
+    fn from_slice_with_context(i: &'a [u8], ctx: &mut ParseContext) -> ParseResult<'a, Self> {
         let (i, data_box): (&'a [u8], DataBox<'a>) = DataBox::from_slice(i)?;
-        let (_, sbox) = Self::from_data_box_with_depth_limit(&data_box, depth_limit)?;
+        let (_, sbox) = Self::from_data_box_with_context(&data_box, ctx, 0)?;
+        ctx.metrics.bytes_consumed += data_box.original.len();
+        ctx.check_trailing_data(i)?;
         Ok((i, sbox))
     }
 
     /// Re-parse a [`DataBox`] as a JUMBF superbox. Children of this
-    /// superbox which are also superboxes will be parsed recursively without
-    /// limit.
+    /// superbox which are also superboxes will be parsed recursively, to a
+    /// limit of [`DEFAULT_DEPTH_LIMIT`] nested boxes.
     ///
     /// If the box is of `jumb` type and has the correct structure, returns
     /// a tuple of the remainder of the input from the box (which should
     /// typically be empty) and the new [`SuperBox`] object.
     ///
     /// Will return an error if the box isn't of `jumb` type.
+    ///
+    /// [`DEFAULT_DEPTH_LIMIT`]: ParseOptions::DEFAULT_DEPTH_LIMIT
     pub fn from_data_box(data_box: &DataBox<'a>) -> ParseResult<'a, Self> {
-        Self::from_data_box_with_depth_limit(data_box, usize::MAX)
+        Self::from_data_box_with_options(data_box, &ParseOptions::default())
     }
 
     /// Re-parse a [`DataBox`] as a JUMBF superbox. Children of this superbox
@@ -93,25 +376,53 @@ impl<'a> SuperBox<'a> {
     pub fn from_data_box_with_depth_limit(
         data_box: &DataBox<'a>,
         depth_limit: usize,
+    ) -> ParseResult<'a, Self> {
+        Self::from_data_box_with_options(
+            data_box,
+            &ParseOptions::default().set_depth_limit(depth_limit),
+        )
+    }
+
+    /// Re-parse a [`DataBox`] as a JUMBF superbox. Parsing behavior is
+    /// controlled by `options`.
+    ///
+    /// If the box is of `jumb` type and has the correct structure, returns
+    /// a tuple of the remainder of the input from the box (which should
+    /// typically be empty) and the new [`SuperBox`] object.
+    ///
+    /// Will return an error if the box isn't of `jumb` type.
+    pub fn from_data_box_with_options(
+        data_box: &DataBox<'a>,
+        options: &ParseOptions,
+    ) -> ParseResult<'a, Self> {
+        let mut ctx = ParseContext::new(options, data_box.original);
+        Self::from_data_box_with_context(data_box, &mut ctx, 0)
+    }
+
+    fn from_data_box_with_context(
+        data_box: &DataBox<'a>,
+        ctx: &mut ParseContext,
+        depth: usize,
     ) -> ParseResult<'a, Self> {
         if data_box.tbox != SUPER_BOX_TYPE {
             return Err(nom::Err::Error(Error::InvalidSuperBoxType(data_box.tbox)));
         }
 
-        let (i, desc) = DescriptionBox::from_slice(data_box.data)?;
+        ctx.check_cancelled()?;
+        ctx.enter_super_box(depth);
 
-        let (i, child_boxes) = boxes_from_slice(i)?;
-        let child_boxes = child_boxes
-            .into_iter()
-            .map(|d| {
-                if d.tbox == SUPER_BOX_TYPE && depth_limit > 0 {
-                    let (_, sbox) = Self::from_data_box_with_depth_limit(&d, depth_limit - 1)?;
-                    Ok(ChildBox::SuperBox(sbox))
-                } else {
-                    Ok(ChildBox::DataBox(d))
-                }
-            })
-            .collect::<Result<Vec<ChildBox<'a>>, Error>>()?;
+        let (i, desc) = DescriptionBox::from_super_box_payload(data_box.data, ctx.options)
+            .map_err(|err| ctx.locate(data_box.data, err))?;
+        if desc.private.is_some() {
+            ctx.metrics.boxes_parsed += 1;
+        }
+
+        // Size the child list once, then parse each child directly into it
+        // rather than collecting and re-mapping an intermediate list.
+        let mut child_boxes: Vec<ChildBox<'a>> = Vec::with_capacity(count_boxes(i));
+        let i = parse_child_boxes(i, &desc, ctx, depth, &mut child_boxes)?;
+
+        ctx.report_progress(data_box.original);
 
         Ok((
             i,
@@ -130,21 +441,186 @@ impl<'a> SuperBox<'a> {
     /// will be treated as a hierarchical label and this function can then
     /// be used to traverse nested data structures.
     ///
+    /// Each component of a hierarchical label may be percent-encoded, which
+    /// allows labels that contain `/` or other reserved characters to be
+    /// found. Use [`escape_label()`] to construct such a component.
+    ///
     /// Will return `None` if no matching child superbox is found _or_ if
     /// more than one matching child superbox is found.
+    ///
+    /// [`escape_label()`]: crate::parser::escape_label
     pub fn find_by_label(&self, label: &str) -> Option<&Self> {
-        let (label, suffix) = match label.split_once('/') {
-            Some((label, suffix)) => (label, Some(suffix)),
-            None => (label, None),
-        };
+        self.find_by_label_with(label, &LabelMatch::default())
+    }
+
+    /// Find a child superbox of this superbox by label, comparing labels as
+    /// described by `label_match`.
+    ///
+    /// Otherwise behaves like [`find_by_label()`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{LabelMatch, SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// assert!(sbox.find_by_label("c.claim").is_none());
+    ///
+    /// let label_match = LabelMatch::default().set_case_insensitive(true);
+    /// assert!(sbox.find_by_label_with("c.claim", &label_match).is_some());
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find_by_label_with(&self, label: &str, label_match: &LabelMatch) -> Option<&Self> {
+        self.find_by_label_path_with(&LabelPath::from(label), label_match)
+    }
+
+    /// Find a descendant of this superbox by following `path`, verifying at
+    /// each step that exactly one matching child exists.
+    ///
+    /// This is equivalent to [`find_by_label()`] but avoids the need to
+    /// escape labels which contain reserved characters. An empty path refers
+    /// to this superbox.
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find_by_label_path(&self, path: &LabelPath) -> Option<&Self> {
+        self.find_by_label_path_with(path, &LabelMatch::default())
+    }
+
+    /// Find a descendant of this superbox by following `path`, comparing
+    /// labels as described by `label_match`.
+    ///
+    /// Otherwise behaves like [`find_by_label_path()`].
+    ///
+    /// [`find_by_label_path()`]: Self::find_by_label_path
+    pub fn find_by_label_path_with(
+        &self,
+        path: &LabelPath,
+        label_match: &LabelMatch,
+    ) -> Option<&Self> {
+        path.components().try_fold(self, |sbox, label| {
+            sbox.find_child_by_label(label, label_match)
+        })
+    }
+
+    /// Find a descendant of this superbox by label, re-parsing child boxes
+    /// of `jumb` type that were returned as plain [`DataBox`]es because of a
+    /// depth limit.
+    ///
+    /// This allows a tree that was parsed with a limited depth (see
+    /// [`ParseOptions::set_depth_limit()`]) to be queried as if it had been
+    /// parsed completely. Only the boxes along the path to the matching
+    /// superbox are re-parsed.
+    ///
+    /// Otherwise behaves like [`find_by_label()`]. The result is borrowed
+    /// from this superbox if no re-parsing was required.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice_with_depth_limit(&jumbf, 0).unwrap();
+    /// assert!(sbox.find_by_label("C.Claim").is_none());
+    ///
+    /// let claim = sbox.find_by_label_deep("C.Claim").unwrap();
+    /// assert_eq!(claim.desc.label, Some("C.Claim"));
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    /// [`ParseOptions::set_depth_limit()`]: crate::parser::ParseOptions::set_depth_limit
+    pub fn find_by_label_deep(&self, label: &str) -> Option<Cow<'_, Self>> {
+        let label_match = LabelMatch::default();
+
+        LabelPath::from(label).components().try_fold(
+            Cow::Borrowed(self),
+            |sbox, label| match sbox {
+                Cow::Borrowed(sbox) => sbox.find_child_by_label_deep(label, &label_match),
+                Cow::Owned(sbox) => sbox
+                    .find_child_by_label_deep(label, &label_match)
+                    .map(|child| Cow::Owned(child.into_owned())),
+            },
+        )
+    }
+
+    fn find_child_by_label_deep(
+        &self,
+        label: &str,
+        label_match: &LabelMatch,
+    ) -> Option<Cow<'_, Self>> {
+        // Re-parse only one level at a time; deeper levels are re-parsed
+        // only if the traversal reaches them.
+        let options = ParseOptions::default().set_depth_limit(0);
+
+        let mut matching_children = self.child_boxes.iter().filter_map(|child_box| {
+            let sbox = match child_box {
+                ChildBox::SuperBox(sbox) => Cow::Borrowed(sbox),
+                ChildBox::DataBox(dbox) if dbox.tbox == SUPER_BOX_TYPE => {
+                    Cow::Owned(Self::from_data_box_with_options(dbox, &options).ok()?.1)
+                }
+                ChildBox::DataBox(_) => return None,
+            };
+
+            let matches = sbox.desc.requestable
+                && sbox
+                    .desc
+                    .label
+                    .is_some_and(|sbox_label| label_match.matches(sbox_label, label));
 
+            matches.then_some(sbox)
+        });
+
+        let sbox = matching_children.next()?;
+        if matching_children.next().is_some() {
+            return None;
+        }
+        Some(sbox)
+    }
+
+    fn find_child_by_label(&self, label: &str, label_match: &LabelMatch) -> Option<&Self> {
         let matching_children: Vec<&SuperBox> = self
             .child_boxes
             .iter()
             .filter_map(|child_box| match child_box {
                 ChildBox::SuperBox(sbox) => {
                     if let Some(sbox_label) = sbox.desc.label {
-                        if sbox_label == label && sbox.desc.requestable {
+                        if label_match.matches(sbox_label, label) && sbox.desc.requestable {
                             Some(sbox)
                         } else {
                             None
@@ -159,11 +635,7 @@ impl<'a> SuperBox<'a> {
 
         if let Some(sbox) = matching_children.first() {
             if matching_children.len() == 1 {
-                if let Some(suffix) = suffix {
-                    return sbox.find_by_label(suffix);
-                } else {
-                    Some(sbox)
-                }
+                Some(sbox)
             } else {
                 None
             }
@@ -172,6 +644,712 @@ impl<'a> SuperBox<'a> {
         }
     }
 
+    /// Find all descendants of this superbox whose label paths match
+    /// `pattern`.
+    ///
+    /// `pattern` is written like a label path for [`find_by_label()`], with
+    /// two kinds of wildcards:
+    ///
+    /// * `*` within a component matches any sequence of characters within a
+    ///   single label (e.g. `c2pa.*` matches `c2pa.assertions` and
+    ///   `c2pa.claim`).
+    /// * A component consisting only of `**` matches any number of labels,
+    ///   including none.
+    ///
+    /// A literal `*` in a label may be matched using the escape sequence
+    /// `%2A`. As for [`find_by_label()`], only requestable, labeled
+    /// superboxes are considered, but more than one match is permitted.
+    ///
+    /// Returns every matching superbox along with its path relative to this
+    /// superbox, in document order. A pattern which can match zero labels
+    /// (i.e. `**`) matches this superbox, with an empty path.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000006b" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000025" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "0000001d" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "632e3100" // label = "c.1"
+    ///         // ---
+    ///         "00000025" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "0000001d" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "632e3200" // label = "c.2"
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// let matches = sbox.find_by_pattern("c.*");
+    ///
+    /// assert_eq!(matches.len(), 2);
+    /// assert_eq!(matches[0].0.to_string(), "c.1");
+    /// assert_eq!(matches[1].1.desc.label, Some("c.2"));
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find_by_pattern(&self, pattern: &str) -> Vec<(LabelPath, &Self)> {
+        let pattern = LabelPattern::from(pattern);
+        let mut result = vec![];
+        self.find_by_pattern_into(
+            &pattern,
+            &pattern.start(),
+            &mut LabelPath::new(),
+            &mut result,
+        );
+        result
+    }
+
+    fn find_by_pattern_into<'s>(
+        &'s self,
+        pattern: &LabelPattern,
+        state: &[usize],
+        path: &mut LabelPath,
+        result: &mut Vec<(LabelPath, &'s Self)>,
+    ) {
+        if pattern.is_match(state) {
+            result.push((path.clone(), self));
+        }
+
+        for child_box in &self.child_boxes {
+            let ChildBox::SuperBox(sbox) = child_box else {
+                continue;
+            };
+
+            let Some(label) = sbox.desc.label.filter(|_| sbox.desc.requestable) else {
+                continue;
+            };
+
+            let next = pattern.advance(state, label);
+            if !next.is_empty() {
+                path.push(label);
+                sbox.find_by_pattern_into(pattern, &next, path, result);
+                path.pop();
+            }
+        }
+    }
+
+    /// Find a child superbox of this superbox by the application-specific
+    /// ID in its description box and verify that exactly one such child
+    /// exists.
+    ///
+    /// Unlike [`find_by_label()`], the child does not need to be marked as
+    /// requestable.
+    ///
+    /// Will return `None` if no matching child superbox is found _or_ if
+    /// more than one matching child superbox is found.
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find_by_id(&self, id: u32) -> Option<&Self> {
+        let mut matching_children =
+            self.child_boxes
+                .iter()
+                .filter_map(|child_box| match child_box {
+                    ChildBox::SuperBox(sbox) if sbox.desc.id == Some(id) => Some(sbox),
+                    _ => None,
+                });
+
+        let sbox = matching_children.next()?;
+        if matching_children.next().is_some() {
+            return None;
+        }
+        Some(sbox)
+    }
+
+    /// Find a descendant superbox of this superbox at any depth by the
+    /// application-specific ID in its description box and verify that
+    /// exactly one such descendant exists.
+    ///
+    /// Otherwise behaves like [`find_by_id()`].
+    ///
+    /// [`find_by_id()`]: Self::find_by_id
+    pub fn find_by_id_recursive(&self, id: u32) -> Option<&Self> {
+        let mut matches = vec![];
+        self.collect_by_id(id, &mut matches);
+
+        match matches.as_slice() {
+            [sbox] => Some(*sbox),
+            _ => None,
+        }
+    }
+
+    fn collect_by_id<'s>(&'s self, id: u32, matches: &mut Vec<&'s Self>) {
+        for child_box in &self.child_boxes {
+            if let ChildBox::SuperBox(sbox) = child_box {
+                if sbox.desc.id == Some(id) {
+                    matches.push(sbox);
+                }
+                sbox.collect_by_id(id, matches);
+            }
+        }
+    }
+
+    /// Find the first descendant superbox of this superbox, at any depth,
+    /// whose description box satisfies `predicate`.
+    ///
+    /// Descendants are visited in document order. This superbox itself is
+    /// not considered. Unlike [`find_by_label()`], descendants need not be
+    /// requestable or labeled, which allows searching by UUID, toggles, or
+    /// any other property of the description box.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "6332637300110010800000aa00389b71" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// let claim = sbox.find(|desc| desc.uuid.starts_with(b"c2cs")).unwrap();
+    ///
+    /// assert_eq!(claim.desc.label, Some("C.Claim"));
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find<F>(&self, mut predicate: F) -> Option<&Self>
+    where
+        F: FnMut(&DescriptionBox<'a>) -> bool,
+    {
+        let mut matches = vec![];
+        self.collect_matching(&mut predicate, true, &mut matches);
+        matches.into_iter().next()
+    }
+
+    /// Find all descendant superboxes of this superbox, at any depth, whose
+    /// description boxes satisfy `predicate`.
+    ///
+    /// Matches are returned in document order. Otherwise behaves like
+    /// [`find()`].
+    ///
+    /// [`find()`]: Self::find
+    pub fn find_all<F>(&self, mut predicate: F) -> Vec<&Self>
+    where
+        F: FnMut(&DescriptionBox<'a>) -> bool,
+    {
+        let mut matches = vec![];
+        self.collect_matching(&mut predicate, false, &mut matches);
+        matches
+    }
+
+    // Returns `true` if the search should stop.
+    fn collect_matching<'s>(
+        &'s self,
+        predicate: &mut dyn FnMut(&DescriptionBox<'a>) -> bool,
+        first_only: bool,
+        matches: &mut Vec<&'s Self>,
+    ) -> bool {
+        for child_box in &self.child_boxes {
+            if let ChildBox::SuperBox(sbox) = child_box {
+                if predicate(&sbox.desc) {
+                    matches.push(sbox);
+                    if first_only {
+                        return true;
+                    }
+                }
+                if sbox.collect_matching(predicate, first_only, matches) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Return the salt of this superbox, if its description box contains a
+    /// C2PA salt (`c2sh`) private box.
+    ///
+    /// The salt is part of the description box, so it is already included
+    /// in [`digest()`] and [`verify_hash()`]; no separate step is needed to
+    /// account for it when hashing the superbox.
+    ///
+    /// [`digest()`]: Self::digest
+    /// [`verify_hash()`]: Self::verify_hash
+    pub fn salt(&self) -> Option<&'a [u8]> {
+        self.desc
+            .private
+            .as_ref()
+            .filter(|private| private.tbox == C2PA_SALT_BOX_TYPE)
+            .map(|private| private.data)
+    }
+
+    /// Return the child superboxes of this superbox whose description box
+    /// contains a private box of type `tbox`, along with that private box.
+    ///
+    /// For example, C2PA assertions may carry a salt in a `c2sh` private
+    /// box, which must be taken into account when hashing the assertion.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::{parser::SuperBox, BoxType};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004b" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "0000002a" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000022" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "10" // toggles
+    ///                 "00000009" // box size
+    ///                 "63327368" // box type = 'c2sh'
+    ///                 "ff" // salt
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    ///
+    /// let salted: Vec<_> = sbox
+    ///     .children_with_private_box(BoxType(*b"c2sh"))
+    ///     .collect();
+    ///
+    /// assert_eq!(salted.len(), 1);
+    /// assert_eq!(salted[0].1.data, [0xff]);
+    /// ```
+    pub fn children_with_private_box(
+        &self,
+        tbox: BoxType,
+    ) -> impl Iterator<Item = (&SuperBox<'a>, &DataBox<'a>)> {
+        self.child_boxes
+            .iter()
+            .filter_map(move |child_box| match child_box {
+                ChildBox::SuperBox(sbox) => sbox
+                    .desc
+                    .private
+                    .as_ref()
+                    .filter(|private| private.tbox == tbox)
+                    .map(|private| (sbox, private)),
+                _ => None,
+            })
+    }
+
+    /// Create a C2PA-style hashed URI which refers to a descendant of this
+    /// superbox.
+    ///
+    /// `label` identifies the descendant and is interpreted as for
+    /// [`find_by_label()`]. `digest` is used to compute the digest of the
+    /// serialized form of that superbox (as produced by [`to_vec()`]).
+    ///
+    /// If this superbox has a label, the URI is absolute and begins with
+    /// this superbox's label (i.e. `self#jumbf=/c2pa/...`). Otherwise, the
+    /// URI is relative to this superbox.
+    ///
+    /// Returns `None` if `label` can not be resolved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::{digest::Digest, parser::SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "00000050" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "0000001e" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "6332706100" // label = "c2pa"
+    ///         // ---
+    ///         "0000002a" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000022" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "6d616e696665737400" // label = "manifest"
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    ///
+    /// // Use a real digest algorithm here.
+    /// struct Length;
+    ///
+    /// impl Digest for Length {
+    ///     fn algorithm(&self) -> &str {
+    ///         "length"
+    ///     }
+    ///
+    ///     fn digest(&self, data: &[u8]) -> Vec<u8> {
+    ///         vec![data.len() as u8]
+    ///     }
+    /// }
+    ///
+    /// let uri = sbox.hashed_uri("manifest", &Length).unwrap();
+    ///
+    /// assert_eq!(uri.url, "self#jumbf=/c2pa/manifest");
+    /// assert_eq!(uri.alg, "length");
+    /// assert_eq!(uri.hash, vec![0x2a]);
+    /// assert_eq!(uri.to_string(), "self#jumbf=/c2pa/manifest?hl=Kg");
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label()
+    /// [`to_vec()`]: Self::to_vec()
+    pub fn hashed_uri(&self, label: &str, digest: &dyn Digest) -> Option<HashedUri> {
+        let path = LabelPath::from(label);
+        let sbox = self.find_by_label_path(&path)?;
+
+        let url = match self.desc.label {
+            Some(root) => format!("self#jumbf=/{}/{path}", escape_label(root)),
+            None => format!("self#jumbf={path}"),
+        };
+
+        Some(HashedUri {
+            url,
+            alg: digest.algorithm().to_owned(),
+            hash: digest.digest(&sbox.to_vec()),
+        })
+    }
+
+    /// Verify the hash stored in this superbox's description box.
+    ///
+    /// The hash covers the superbox's data payload, which is the serialized
+    /// form of all child boxes following the description box.
+    ///
+    /// Returns `None` if the description box does not contain a hash.
+    /// Otherwise, returns `true` if the hash computed using `digest`
+    /// matches the stored hash.
+    pub fn verify_hash(&self, digest: &dyn Digest) -> Option<bool> {
+        let hash = self.desc.hash?;
+
+        let mut payload: Vec<u8> = Vec::with_capacity(self.original.len());
+        for child in &self.child_boxes {
+            // Writing to an in-memory buffer can't fail.
+            let _ = match child {
+                ChildBox::SuperBox(sbox) => sbox.write_to(&mut payload),
+                ChildBox::DataBox(dbox) => dbox.write_to(&mut payload),
+            };
+        }
+
+        Some(digest.digest(&payload) == hash)
+    }
+
+    /// Check this superbox and all of its descendants for problems which
+    /// don't prevent parsing, but may cause the data structure to be
+    /// interpreted incorrectly.
+    ///
+    /// The following problems are reported:
+    ///
+    /// * Two or more requestable sibling superboxes with the same label.
+    ///   [`find_by_label()`] will not find any of them.
+    /// * A requestable superbox with no label.
+    /// * A superbox whose description box has a hash, but which doesn't contain
+    ///   exactly one data box for the hash to refer to.
+    ///
+    /// [`find_by_label()`]: Self::find_by_label()
+    pub fn verify_all(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        report.check_super_box(self, &mut vec![]);
+        report
+    }
+
+    /// Check that the [`original`] byte ranges of this superbox and all of
+    /// its descendants are consistent with each other.
+    ///
+    /// The following problems are reported, with byte offsets relative to
+    /// the start of this superbox:
+    ///
+    /// * Bytes within a superbox's payload which aren't part of any child box
+    ///   (including its description box).
+    /// * Child boxes which overlap each other.
+    /// * Child boxes which aren't entirely within their parent's payload.
+    /// * Box headers whose declared length (including any XLBox length) doesn't
+    ///   match the box's actual length.
+    ///
+    /// A superbox parsed by this crate is always consistent, except where
+    /// [`from_slice_with_recovery()`] skipped over malformed data. This is
+    /// most useful for superboxes which have been modified or assembled from
+    /// several sources.
+    ///
+    /// [`original`]: Self::original
+    /// [`from_slice_with_recovery()`]: Self::from_slice_with_recovery
+    pub fn verify_structure(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        report.check_structure(self.original, self, &mut vec![]);
+        report
+    }
+
+    /// Serialize this superbox and all of its child boxes to a JUMBF stream.
+    ///
+    /// This preserves the encoding choices made in the original JUMBF data
+    /// wherever possible: boxes which have not been modified since they were
+    /// parsed are written byte-for-byte as they were originally encoded.
+    /// Boxes which have been modified are re-serialized using the same header
+    /// encoding (32-bit length, XLBox length, or length 0 meaning "to end of
+    /// data") as the original box. Reserved toggle bits in description boxes
+    /// are also preserved.
+    ///
+    /// Consequently, an unmodified tree will be serialized to exactly the
+    /// bytes it was parsed from, which keeps any signatures over those bytes
+    /// valid even when unrelated boxes in the tree are edited.
+    ///
+    /// The one exception is a superbox parsed with
+    /// [`ParseOptions::set_lenient_description_box()`] whose description box
+    /// was misplaced or missing. Its description box is always written as the
+    /// first child box, so a misplaced description box is moved to the front
+    /// and a missing one is replaced by an empty description box. Use
+    /// [`copy_original_to()`] to write such a superbox as it was parsed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// assert_eq!(sbox.to_vec(), jumbf);
+    ///
+    /// sbox.desc.label = Some("new.label");
+    ///
+    /// let new_jumbf = sbox.to_vec();
+    /// let (_, new_sbox) = SuperBox::from_slice(&new_jumbf).unwrap();
+    /// assert_eq!(new_sbox.desc.label, Some("new.label"));
+    /// ```
+    ///
+    /// [`ParseOptions::set_lenient_description_box()`]: crate::parser::ParseOptions::set_lenient_description_box
+    /// [`copy_original_to()`]: Self::copy_original_to
+    pub fn write_to(&self, to_stream: &mut dyn Write) -> IoResult<()> {
+        write_header_like(self.original, SUPER_BOX_TYPE, self.payload_len(), to_stream)?;
+
+        self.desc.write_to(to_stream)?;
+
+        for child in &self.child_boxes {
+            match child {
+                ChildBox::SuperBox(sbox) => sbox.write_to(to_stream)?,
+                ChildBox::DataBox(dbox) => dbox.write_to(to_stream)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the [`original`] bytes of this superbox, including all of its
+    /// descendants, to `to_stream`.
+    ///
+    /// Unlike [`write_to()`], this ignores any changes made to the superbox
+    /// or its descendants since it was parsed, which makes it suitable for
+    /// extracting a subtree exactly as it appeared in the source data.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// sbox.desc.label = Some("new.label");
+    ///
+    /// let mut extracted: Vec<u8> = vec![];
+    /// sbox.copy_original_to(&mut extracted).unwrap();
+    /// assert_eq!(extracted, jumbf);
+    /// ```
+    ///
+    /// [`original`]: Self::original
+    /// [`write_to()`]: Self::write_to
+    pub fn copy_original_to(&self, to_stream: &mut dyn Write) -> IoResult<()> {
+        to_stream.write_all(self.original)
+    }
+
+    /// Compute the digest of this superbox, including all of its child
+    /// boxes, exactly as it appeared in the input.
+    ///
+    /// This is the hash used for C2PA hashed URIs which refer to this
+    /// superbox. Like [`copy_original_to()`], this uses [`original`], so any
+    /// changes made to this struct after parsing are not reflected. (By
+    /// contrast, [`hashed_uri()`] hashes the superbox as it would be
+    /// serialized now.)
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::{digest::Digest, parser::SuperBox};
+    ///
+    /// let jumbf = hex!(
+    ///     "0000002f" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000027" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "03" // toggles
+    ///         "746573742e7375706572626f7800" // label
+    /// );
+    ///
+    /// // Use a real digest algorithm here.
+    /// struct Length;
+    ///
+    /// impl Digest for Length {
+    ///     fn algorithm(&self) -> &str {
+    ///         "length"
+    ///     }
+    ///
+    ///     fn digest(&self, data: &[u8]) -> Vec<u8> {
+    ///         vec![data.len() as u8]
+    ///     }
+    /// }
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// assert_eq!(sbox.digest(&Length), vec![0x2f]);
+    /// ```
+    ///
+    /// [`copy_original_to()`]: Self::copy_original_to
+    /// [`original`]: Self::original
+    /// [`hashed_uri()`]: Self::hashed_uri
+    pub fn digest(&self, digest: &dyn Digest) -> Vec<u8> {
+        digest.digest(self.original)
+    }
+
+    /// Serialize this superbox and all of its child boxes to a new byte
+    /// vector.
+    ///
+    /// See [`write_to()`] for a description of how the box is serialized.
+    ///
+    /// [`write_to()`]: Self::write_to
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = Vec::with_capacity(self.serialized_len());
+
+        // Writing to a Vec can't fail.
+        #[allow(clippy::unwrap_used)]
+        self.write_to(&mut result).unwrap();
+
+        result
+    }
+
+    /// Serialize this superbox and all of its child boxes into `buf`,
+    /// replacing its previous contents, and return a new [`SuperBox`] parsed
+    /// from those bytes.
+    ///
+    /// The new superbox borrows from `buf` rather than from the input this
+    /// superbox was parsed from, so it can outlive that input (for example,
+    /// a large memory-mapped file of which only a small subtree is needed).
+    ///
+    /// The bytes are re-parsed using [`ParseOptions::default()`]. If this
+    /// superbox was parsed with other options (such as a smaller depth limit
+    /// or lenient description box parsing), the new tree may be shaped
+    /// differently or parsing may fail.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let mut buf = vec![];
+    ///
+    /// let detached = {
+    ///     let jumbf = hex!(
+    ///         "0000002f" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000027" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "746573742e7375706572626f7800" // label
+    ///     );
+    ///
+    ///     let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    ///     sbox.copy_to_vec(&mut buf).unwrap()
+    /// };
+    ///
+    /// assert_eq!(detached.desc.label, Some("test.superbox"));
+    /// assert_eq!(detached.original.len(), 47);
+    /// ```
+    ///
+    /// [`ParseOptions::default()`]: crate::parser::ParseOptions::default
+    pub fn copy_to_vec<'b>(&self, buf: &'b mut Vec<u8>) -> Result<SuperBox<'b>, Error> {
+        buf.clear();
+        buf.reserve(self.serialized_len());
+
+        // Writing to a Vec can't fail.
+        #[allow(clippy::unwrap_used)]
+        self.write_to(buf).unwrap();
+
+        SuperBox::parse(buf)
+    }
+
+    /// Returns the number of bytes this superbox and all of its child boxes
+    /// occupy when serialized.
+    ///
+    /// This is the number of bytes that [`write_to()`] will write. If the
+    /// superbox has not been modified since it was parsed, it is also the
+    /// length of [`original`].
+    ///
+    /// [`write_to()`]: Self::write_to
+    /// [`original`]: Self::original
+    pub fn serialized_len(&self) -> usize {
+        let payload_len = self.payload_len();
+        header_len_like(self.original, payload_len) + payload_len
+    }
+
+    fn payload_len(&self) -> usize {
+        self.desc.serialized_len()
+            + self
+                .child_boxes
+                .iter()
+                .map(|child| match child {
+                    ChildBox::SuperBox(sbox) => sbox.serialized_len(),
+                    ChildBox::DataBox(dbox) => dbox.serialized_len(),
+                })
+                .sum::<usize>()
+    }
+
     /// If the first child box of this superbox is a data box, return it.
     /// Otherwise, return `None`.
     ///
@@ -185,6 +1363,413 @@ impl<'a> SuperBox<'a> {
                 _ => None,
             })
     }
+
+    /// Find a child superbox by label, as with [`find_by_label()`], and
+    /// return its first child box if that is a data box.
+    ///
+    /// This is equivalent to `find_by_label(label).and_then(|s| s.data_box())`.
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    pub fn find_data_box(&self, label: &str) -> Option<&DataBox<'a>> {
+        self.find_by_label(label)?
+            .child_boxes
+            .first()
+            .and_then(|child_box| match child_box {
+                ChildBox::DataBox(data_box) => Some(data_box),
+                _ => None,
+            })
+    }
+
+    /// Compare this superbox with `new` and return a description of each
+    /// box which was added, removed, or changed.
+    ///
+    /// Boxes are compared by their [`original`] bytes, so this is intended
+    /// for comparing two parsed JUMBF data structures. Child superboxes are
+    /// matched by label and child data boxes by box type; if several siblings
+    /// share a label or box type, they are matched in order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::{DiffKind, SuperBox};
+    ///
+    /// let old = hex!(
+    ///     "00000030" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "0000000f" // box size
+    ///         "6a736f6e" // box type = 'json'
+    ///         "7b2261223a317d" // payload = {"a":1}
+    /// );
+    ///
+    /// let new = hex!(
+    ///     "00000030" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "0000000f" // box size
+    ///         "6a736f6e" // box type = 'json'
+    ///         "7b2261223a327d" // payload = {"a":2}
+    /// );
+    ///
+    /// let old = SuperBox::parse(&old).unwrap();
+    /// let new = SuperBox::parse(&new).unwrap();
+    ///
+    /// let diffs = old.diff(&new);
+    /// assert_eq!(diffs.len(), 1);
+    /// assert_eq!(diffs[0].kind(), DiffKind::Changed);
+    /// assert_eq!(diffs[0].tbox.0, *b"json");
+    /// ```
+    ///
+    /// [`original`]: Self::original
+    pub fn diff(&self, new: &SuperBox<'a>) -> Vec<BoxDiff<'a>> {
+        let mut diffs = vec![];
+        diff_super_boxes(&LabelPath::new(), self, new, &mut diffs);
+        diffs
+    }
+
+    /// Returns a compact, single-line JSON summary of this superbox, suitable
+    /// for structured logging.
+    ///
+    /// The summary includes the label (or `null`), the UUID as hex, the
+    /// content type name (only if the content type is known), and the
+    /// serialized size of this superbox. Its children are summarized one
+    /// level deep: child superboxes report the number of children they
+    /// contain rather than the children themselves, and data boxes report
+    /// only their box type and size. Payloads are never included.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "00000030" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "0000000f" // box size
+    ///         "6a736f6e" // box type = 'json'
+    ///         "7b2261223a317d" // payload = {"a":1}
+    /// );
+    ///
+    /// let sbox = SuperBox::parse(&jumbf).unwrap();
+    ///
+    /// assert_eq!(
+    ///     sbox.summary_json(),
+    ///     r#"{"label":null,"uuid":"00000000000000000000000000000000","size":48,"children":[{"box":"json","size":15}]}"#
+    /// );
+    /// ```
+    pub fn summary_json(&self) -> String {
+        summary_json(self)
+    }
+
+    /// Return every data box in this superbox and its descendants, in
+    /// document order, along with the path to the superbox that contains it.
+    ///
+    /// Paths are relative to this superbox, so data boxes which are direct
+    /// children of this superbox have an empty path. A superbox which has no
+    /// label contributes an empty label to the path. Description boxes and
+    /// their private boxes are not included.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "00000051" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000030" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "0000001f" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "6368696c6400" // label = "child"
+    ///             // ---
+    ///             "00000009" // box size
+    ///             "61626364" // box type = 'abcd'
+    ///             "78" // payload
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// let flat = sbox.flatten();
+    ///
+    /// assert_eq!(flat.len(), 1);
+    /// assert_eq!(flat[0].0.to_string(), "child");
+    /// assert_eq!(flat[0].1.data, b"x");
+    /// ```
+    pub fn flatten(&self) -> Vec<(LabelPath, &DataBox<'a>)> {
+        let mut result = vec![];
+        self.flatten_into(&mut LabelPath::new(), &mut result);
+        result
+    }
+
+    fn flatten_into<'s>(
+        &'s self,
+        path: &mut LabelPath,
+        result: &mut Vec<(LabelPath, &'s DataBox<'a>)>,
+    ) {
+        for child_box in &self.child_boxes {
+            match child_box {
+                ChildBox::SuperBox(sbox) => {
+                    path.push(sbox.desc.label.unwrap_or_default());
+                    sbox.flatten_into(path, result);
+                    path.pop();
+                }
+                ChildBox::DataBox(dbox) => result.push((path.clone(), dbox)),
+            }
+        }
+    }
+
+    /// Search the payloads of every data box in this superbox and its
+    /// descendants for `pattern`.
+    ///
+    /// Returns one [`DataMatch`] for each occurrence of `pattern`, in
+    /// document order. Overlapping occurrences are all reported. Data boxes
+    /// are visited as for [`flatten()`]. An empty pattern matches nothing.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "00000051" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000030" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "0000001f" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "6368696c6400" // label = "child"
+    ///             // ---
+    ///             "00000009" // box size
+    ///             "61626364" // box type = 'abcd'
+    ///             "78" // payload
+    /// );
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// let matches = sbox.find_data_containing(b"x");
+    ///
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].path.to_string(), "child");
+    /// assert_eq!(matches[0].offset, 0);
+    /// ```
+    ///
+    /// [`flatten()`]: Self::flatten
+    pub fn find_data_containing(&self, pattern: &[u8]) -> Vec<DataMatch<'_, 'a>> {
+        if pattern.is_empty() {
+            return vec![];
+        }
+
+        self.flatten()
+            .into_iter()
+            .flat_map(|(path, data_box)| {
+                data_box
+                    .data
+                    .windows(pattern.len())
+                    .enumerate()
+                    .filter(|(_, window)| *window == pattern)
+                    .map(|(offset, _)| DataMatch {
+                        path: path.clone(),
+                        data_box,
+                        offset,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Change the label of the descendant superbox identified by
+    /// `old_path` to `new_label`.
+    ///
+    /// `old_path` is interpreted as for [`find_by_label()`]. Only the
+    /// description box is changed; sizes of the renamed box and its
+    /// ancestors are recomputed when the tree is serialized using
+    /// [`write_to()`] or [`to_vec()`]. Hashes stored in the description
+    /// boxes of ancestors are _not_ updated.
+    ///
+    /// Returns the path of the renamed superbox, or `None` (leaving the tree
+    /// unchanged) if `old_path` does not identify exactly one superbox or if
+    /// `new_label` contains a NUL character, which can't be represented in a
+    /// description box.
+    ///
+    /// Other boxes may refer to the renamed superbox by its old label. Use
+    /// [`references_to()`] with `old_path` to find them.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use jumbf::parser::SuperBox;
+    ///
+    /// let jumbf = hex!(
+    ///     "0000004a" // box size
+    ///     "6a756d62" // box type = 'jumb'
+    ///         "00000019" // box size
+    ///         "6a756d64" // box type = 'jumd'
+    ///         "00000000000000000000000000000000" // UUID
+    ///         "00" // toggles
+    ///         // ---
+    ///         "00000029" // box size
+    ///         "6a756d62" // box type = 'jumb'
+    ///             "00000021" // box size
+    ///             "6a756d64" // box type = 'jumd'
+    ///             "00000000000000000000000000000000" // UUID
+    ///             "03" // toggles
+    ///             "432e436c61696d00" // label = "C.Claim"
+    /// );
+    ///
+    /// let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    /// let new_path = sbox.rename_label("C.Claim", "C.Claim.v2").unwrap();
+    /// assert_eq!(new_path.to_string(), "C.Claim.v2");
+    ///
+    /// let new_jumbf = sbox.to_vec();
+    /// assert_eq!(new_jumbf.len(), jumbf.len() + 3);
+    ///
+    /// let (_, sbox) = SuperBox::from_slice(&new_jumbf).unwrap();
+    /// assert!(sbox.find_by_label("C.Claim.v2").is_some());
+    /// ```
+    ///
+    /// [`find_by_label()`]: Self::find_by_label
+    /// [`write_to()`]: Self::write_to
+    /// [`to_vec()`]: Self::to_vec
+    /// [`references_to()`]: Self::references_to
+    pub fn rename_label(&mut self, old_path: &str, new_label: &'a str) -> Option<LabelPath> {
+        if new_label.contains('\0') {
+            return None;
+        }
+
+        let mut path = LabelPath::from(old_path);
+        let sbox = path
+            .components()
+            .try_fold(self, |sbox, label| sbox.find_child_by_label_mut(label))?;
+
+        sbox.desc.label = Some(new_label);
+
+        path.pop();
+        path.push(new_label);
+        Some(path)
+    }
+
+    fn find_child_by_label_mut(&mut self, label: &str) -> Option<&mut Self> {
+        let label_match = LabelMatch::default();
+
+        let mut matching_children = self.child_boxes.iter_mut().filter_map(|child_box| {
+            let ChildBox::SuperBox(sbox) = child_box else {
+                return None;
+            };
+
+            let matches = sbox.desc.requestable
+                && sbox
+                    .desc
+                    .label
+                    .is_some_and(|sbox_label| label_match.matches(sbox_label, label));
+
+            matches.then_some(sbox)
+        });
+
+        let sbox = matching_children.next()?;
+        if matching_children.next().is_some() {
+            return None;
+        }
+        Some(sbox)
+    }
+
+    /// Search the payloads of every data box in this superbox and its
+    /// descendants for text which may be a JUMBF URI reference (such as
+    /// `self#jumbf=c2pa.assertions/c2pa.actions`) to the superbox at `path`.
+    ///
+    /// This is a textual search: it reports every occurrence of the last
+    /// label in `path`, escaped as for a URI and preceded by `/` or
+    /// `jumbf=`. It does not require the superbox at `path` to exist, so it
+    /// can be used after [`rename_label()`] to find references which may
+    /// now dangle. Some matches may be false positives (for example, a
+    /// reference to a different box with the same label or a longer label
+    /// with the same prefix), so results should be checked before acting on
+    /// them.
+    ///
+    /// Matches are returned in document order. The [`offset`] of each match
+    /// is that of the label, not the preceding `/` or `jumbf=`. A path
+    /// which ends with an empty label matches nothing.
+    ///
+    /// [`rename_label()`]: Self::rename_label
+    /// [`offset`]: DataMatch::offset
+    pub fn references_to(&self, path: &str) -> Vec<DataMatch<'_, 'a>> {
+        let path = LabelPath::from(path);
+        let Some(label) = path.components().last().filter(|l| !l.is_empty()) else {
+            return vec![];
+        };
+
+        let label = escape_label(label);
+        let mut result = vec![];
+
+        for prefix in ["/", "jumbf="] {
+            let pattern = format!("{prefix}{label}");
+            result.extend(
+                self.find_data_containing(pattern.as_bytes())
+                    .into_iter()
+                    .map(|m| DataMatch {
+                        offset: m.offset + prefix.len(),
+                        ..m
+                    }),
+            );
+        }
+
+        result.sort_by_key(|m| m.data_box.data.as_ptr() as usize + m.offset);
+        result
+    }
+}
+
+/// Describes one occurrence of a byte pattern found by
+/// [`SuperBox::find_data_containing()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataMatch<'s, 'a> {
+    /// Path to the superbox which contains the matching data box, relative
+    /// to the superbox that was searched.
+    pub path: LabelPath,
+
+    /// The data box whose payload contains the pattern.
+    pub data_box: &'s DataBox<'a>,
+
+    /// Offset of the pattern within the data box's payload.
+    pub offset: usize,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SuperBox<'a> {
+    type Error = Error;
+
+    /// Equivalent to [`SuperBox::parse()`].
+    fn try_from(i: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::parse(i)
+    }
 }
 
 impl<'a> Debug for SuperBox<'a> {
@@ -197,18 +1782,128 @@ impl<'a> Debug for SuperBox<'a> {
     }
 }
 
-// Parse boxes from slice until slice is empty.
-fn boxes_from_slice(i: &[u8]) -> ParseResult<Vec<DataBox<'_>>> {
-    let mut result: Vec<DataBox> = vec![];
-    let mut i = i;
+// Receives the child boxes of a superbox as they are parsed. This lets
+// `SuperBox` and `ArenaSuperBox` share `parse_child_boxes()`.
+pub(crate) trait ChildSink<'a> {
+    // Parse `d` as a superbox nested `depth` levels deep and add it.
+    fn push_super_box(
+        &mut self,
+        d: &DataBox<'a>,
+        ctx: &mut ParseContext,
+        depth: usize,
+    ) -> Result<(), Error>;
+
+    // Add `d` as a plain data box.
+    fn push_data_box(&mut self, d: DataBox<'a>);
+}
+
+impl<'a> ChildSink<'a> for Vec<ChildBox<'a>> {
+    fn push_super_box(
+        &mut self,
+        d: &DataBox<'a>,
+        ctx: &mut ParseContext,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let (_, sbox) = SuperBox::from_data_box_with_context(d, ctx, depth)?;
+        self.push(ChildBox::SuperBox(sbox));
+        Ok(())
+    }
 
+    fn push_data_box(&mut self, d: DataBox<'a>) {
+        self.push(ChildBox::DataBox(d));
+    }
+}
+
+// Parse the child boxes of a superbox at `depth` from `i` and add them to
+// `sink`. `i` is the payload of the superbox following `desc`, or the whole
+// payload if the description box was misplaced.
+//
+// Returns whatever input could not be parsed, which is only non-empty if
+// parsing stopped at a corrupt header and recovery is enabled.
+pub(crate) fn parse_child_boxes<'a>(
+    mut i: &'a [u8],
+    desc: &DescriptionBox<'a>,
+    ctx: &mut ParseContext,
+    depth: usize,
+    sink: &mut impl ChildSink<'a>,
+) -> Result<&'a [u8], Error> {
     while !i.is_empty() {
-        let (x, data_box) = DataBox::from_slice(i)?;
+        let (x, d) = match DataBox::from_bounded_slice(i) {
+            Ok(result) => result,
+            Err(err) => {
+                // Without a valid header, the next box boundary can't be
+                // found, so the rest of this superbox is skipped.
+                ctx.recover(i, None, ctx.locate(i, err.into()))?;
+                break;
+            }
+        };
         i = x;
-        result.push(data_box);
+
+        if d.tbox == DESCRIPTION_BOX_TYPE {
+            if d.original.as_ptr() == desc.original.as_ptr() {
+                // Misplaced description box, already parsed.
+                continue;
+            }
+            if !ctx.options.lenient_description_box {
+                ctx.recover(d.original, Some(d.tbox), Error::DuplicateDescriptionBox)?;
+            }
+        }
+
+        ctx.check_cancelled()?;
+        if ctx.options.skips_box_type(d.tbox) {
+            ctx.metrics.boxes_parsed += 1;
+            ctx.report_progress(d.original);
+            sink.push_data_box(d);
+            continue;
+        }
+
+        if d.tbox == SUPER_BOX_TYPE && depth < ctx.options.depth_limit {
+            let boxes_parsed = ctx.metrics.boxes_parsed;
+            match sink.push_super_box(&d, ctx, depth + 1) {
+                Ok(()) => continue,
+                Err(err) => {
+                    // Keep the malformed superbox as a plain data box, which
+                    // is counted once below rather than as a superbox.
+                    ctx.demote_super_box(&d, err)?;
+                    ctx.metrics.boxes_parsed = boxes_parsed;
+                }
+            }
+        }
+
+        ctx.metrics.boxes_parsed += 1;
+        ctx.report_progress(d.original);
+        sink.push_data_box(d);
     }
 
-    Ok((i, result))
+    Ok(i)
+}
+
+// Count the boxes in a slice by walking only the box headers. This is used
+// to size child box lists ahead of time. Malformed headers end the scan; the
+// parser will report the actual error when it reaches that box.
+pub(crate) fn count_boxes(mut i: &[u8]) -> usize {
+    let mut count = 0;
+
+    while i.len() >= 8 {
+        count += 1;
+
+        let len = u32::from_be_bytes([i[0], i[1], i[2], i[3]]) as u64;
+        let len = match len {
+            0 => break,
+            1 if i.len() >= 16 => {
+                u64::from_be_bytes([i[8], i[9], i[10], i[11], i[12], i[13], i[14], i[15]])
+            }
+            len => len,
+        };
+
+        if len < 8 || len > i.len() as u64 {
+            break;
+        }
+
+        i = &i[len as usize..];
+    }
+
+    count
 }
 
 /// This type represents a single box within a superbox,
@@ -246,4 +1941,84 @@ impl<'a> ChildBox<'a> {
             None
         }
     }
+
+    /// If this represents a nested super box, return that superbox.
+    /// Otherwise, return this box unchanged as the error value.
+    ///
+    /// Unlike [`as_super_box()`], this consumes the `ChildBox`, which
+    /// allows a parsed tree to be taken apart without cloning.
+    ///
+    /// [`as_super_box()`]: Self::as_super_box
+    #[allow(clippy::result_large_err)] // The error is the `ChildBox` itself.
+    pub fn into_super_box(self) -> Result<SuperBox<'a>, Self> {
+        match self {
+            Self::SuperBox(sb) => Ok(sb),
+            _ => Err(self),
+        }
+    }
+
+    /// If this represents a nested data box, return that data box.
+    /// Otherwise, return this box unchanged as the error value.
+    ///
+    /// Unlike [`as_data_box()`], this consumes the `ChildBox`, which
+    /// allows a parsed tree to be taken apart without cloning.
+    ///
+    /// [`as_data_box()`]: Self::as_data_box
+    #[allow(clippy::result_large_err)] // The error is the `ChildBox` itself.
+    pub fn into_data_box(self) -> Result<DataBox<'a>, Self> {
+        match self {
+            Self::DataBox(db) => Ok(db),
+            _ => Err(self),
+        }
+    }
+}
+
+/// Parse a byte-slice which contains a sequence of JUMBF boxes, such as the
+/// payload of a data box that itself contains boxes.
+///
+/// Boxes of type `jumb` are parsed as superboxes (recursively, without
+/// limit). All other boxes are returned as data boxes. An error is returned
+/// unless the entire input consists of complete boxes.
+///
+/// ## Example
+///
+/// ```
+/// use hex_literal::hex;
+/// use jumbf::parser::{boxes_from_slice, ChildBox};
+///
+/// let jumbf = hex!(
+///     "0000000a" // box size
+///     "6a736f6e" // box type = 'json'
+///     "7b7d" // payload = {}
+///     // ---
+///     "0000002f" // box size
+///     "6a756d62" // box type = 'jumb'
+///         "00000027" // box size
+///         "6a756d64" // box type = 'jumd'
+///         "00000000000000000000000000000000" // UUID
+///         "03" // toggles
+///         "746573742e7375706572626f7800" // label
+/// );
+///
+/// let boxes = boxes_from_slice(&jumbf).unwrap();
+/// assert_eq!(boxes.len(), 2);
+/// assert!(matches!(boxes[0], ChildBox::DataBox(_)));
+/// assert!(matches!(boxes[1], ChildBox::SuperBox(_)));
+/// ```
+pub fn boxes_from_slice(mut i: &[u8]) -> Result<Vec<ChildBox<'_>>, Error> {
+    let mut boxes = vec![];
+
+    while !i.is_empty() {
+        let (rem, dbox) = DataBox::from_slice(i)?;
+        i = rem;
+
+        boxes.push(if dbox.tbox == SUPER_BOX_TYPE {
+            let (_, sbox) = SuperBox::from_data_box(&dbox)?;
+            ChildBox::SuperBox(sbox)
+        } else {
+            ChildBox::DataBox(dbox)
+        });
+    }
+
+    Ok(boxes)
 }
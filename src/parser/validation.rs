@@ -0,0 +1,328 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::BTreeMap;
+
+use crate::parser::{data_box::HeaderEncoding, ChildBox, SuperBox};
+
+/// Describes problems found by [`SuperBox::verify_all()`] or
+/// [`SuperBox::verify_structure()`].
+///
+/// These problems do not prevent a JUMBF data structure from being parsed,
+/// but may cause it to be interpreted incorrectly.
+///
+/// [`SuperBox::verify_all()`]: crate::parser::SuperBox::verify_all
+/// [`SuperBox::verify_structure()`]: crate::parser::SuperBox::verify_structure
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    /// Problems found, in the order in which the boxes appear in the data
+    /// structure.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub(crate) fn check_super_box(&mut self, sbox: &SuperBox, location: &mut Vec<usize>) {
+        if sbox.desc.requestable && sbox.desc.label.is_none() {
+            self.issues.push(ValidationIssue::UnlabeledRequestableBox {
+                location: location.clone(),
+            });
+        }
+
+        if sbox.desc.hash.is_some() {
+            let data_box_count = sbox
+                .child_boxes
+                .iter()
+                .filter(|child| matches!(child, ChildBox::DataBox(_)))
+                .count();
+
+            if data_box_count != 1 {
+                self.issues.push(ValidationIssue::AmbiguousHash {
+                    location: location.clone(),
+                    data_box_count,
+                });
+            }
+        }
+
+        // Count each label, remembering where it first appears so that
+        // duplicates are reported in document order.
+        let mut labels: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for (index, child) in sbox.child_boxes.iter().enumerate() {
+            if let ChildBox::SuperBox(child) = child {
+                if let (true, Some(label)) = (child.desc.requestable, child.desc.label) {
+                    labels.entry(label).or_insert((index, 0)).1 += 1;
+                }
+            }
+        }
+
+        let mut labels: Vec<(&str, (usize, usize))> = labels.into_iter().collect();
+        labels.sort_by_key(|(_, (first, _))| *first);
+
+        for (label, (_, count)) in labels {
+            if count > 1 {
+                self.issues.push(ValidationIssue::DuplicateLabel {
+                    location: location.clone(),
+                    label: label.to_owned(),
+                    count,
+                });
+            }
+        }
+
+        for (index, child) in sbox.child_boxes.iter().enumerate() {
+            if let ChildBox::SuperBox(child) = child {
+                location.push(index);
+                self.check_super_box(child, location);
+                location.pop();
+            }
+        }
+    }
+
+    pub(crate) fn check_structure(
+        &mut self,
+        root: &[u8],
+        sbox: &SuperBox,
+        location: &mut Vec<usize>,
+    ) {
+        let offset_of =
+            |original: &[u8]| (original.as_ptr() as usize).wrapping_sub(root.as_ptr() as usize);
+
+        let start = offset_of(sbox.original);
+        let (Some(payload_start), Some(payload_end)) = (
+            start.checked_add(HeaderEncoding::of(sbox.original).header_len()),
+            start.checked_add(sbox.original.len()),
+        ) else {
+            // This superbox wasn't parsed from `root`, so its offsets are
+            // meaningless. Its parent normally reports this already.
+            let issue = ValidationIssue::BoxOutsideParent {
+                location: location[..location.len().saturating_sub(1)].to_vec(),
+                offset: start,
+            };
+            if !self.issues.contains(&issue) {
+                self.issues.push(issue);
+            }
+            return;
+        };
+
+        if location.is_empty() {
+            self.check_box_size(sbox.original, 0, payload_end, location);
+        }
+
+        let mut children: Vec<(usize, &[u8])> = sbox
+            .child_boxes
+            .iter()
+            .map(|child| match child {
+                ChildBox::SuperBox(sbox) => sbox.original,
+                ChildBox::DataBox(dbox) => dbox.original,
+            })
+            .chain(Some(sbox.desc.original).filter(|desc| !desc.is_empty()))
+            .map(|original| (offset_of(original), original))
+            .collect();
+        children.sort_by_key(|(offset, _)| *offset);
+
+        let mut pos = payload_start;
+        for (offset, original) in children {
+            let end = offset.wrapping_add(original.len());
+            if offset < payload_start || end < offset || end > payload_end {
+                self.issues.push(ValidationIssue::BoxOutsideParent {
+                    location: location.clone(),
+                    offset,
+                });
+                continue;
+            }
+
+            if offset > pos {
+                self.issues.push(ValidationIssue::UncoveredBytes {
+                    location: location.clone(),
+                    offset: pos,
+                    len: offset - pos,
+                });
+            } else if offset < pos {
+                self.issues.push(ValidationIssue::OverlappingBoxes {
+                    location: location.clone(),
+                    offset,
+                    len: pos.min(end) - offset,
+                });
+            }
+
+            self.check_box_size(original, offset, payload_end, location);
+            pos = pos.max(end);
+        }
+
+        if pos < payload_end {
+            self.issues.push(ValidationIssue::UncoveredBytes {
+                location: location.clone(),
+                offset: pos,
+                len: payload_end - pos,
+            });
+        }
+
+        for (index, child) in sbox.child_boxes.iter().enumerate() {
+            if let ChildBox::SuperBox(child) = child {
+                location.push(index);
+                self.check_structure(root, child, location);
+                location.pop();
+            }
+        }
+    }
+
+    // Verify that the length declared in the header of the box at `offset`
+    // matches the length of `original`.
+    fn check_box_size(
+        &mut self,
+        original: &[u8],
+        offset: usize,
+        parent_end: usize,
+        location: &[usize],
+    ) {
+        let actual = original.len() as u64;
+
+        let declared = match HeaderEncoding::of(original) {
+            HeaderEncoding::ToEnd => {
+                // Must extend to the end of the enclosing superbox.
+                (offset + original.len() == parent_end).then_some(actual)
+            }
+            HeaderEncoding::Extended => original
+                .get(8..16)
+                .and_then(|xlbox| xlbox.try_into().ok())
+                .map(u64::from_be_bytes),
+            HeaderEncoding::Compact => original
+                .get(0..4)
+                .and_then(|size| size.try_into().ok())
+                .map(|size| u32::from_be_bytes(size) as u64),
+        };
+
+        if declared != Some(actual) {
+            self.issues.push(ValidationIssue::BoxSizeMismatch {
+                location: location.to_vec(),
+                offset,
+                declared,
+                actual,
+            });
+        }
+    }
+}
+
+/// A problem found by [`SuperBox::verify_all()`] or
+/// [`SuperBox::verify_structure()`].
+///
+/// Each issue has a `location`, which identifies a superbox by the indices
+/// into [`SuperBox::child_boxes`] that lead to it from the superbox on which
+/// the verification method was called. An empty location refers to that
+/// superbox itself.
+///
+/// Issues found by [`SuperBox::verify_structure()`] also have an `offset`,
+/// which is a byte offset relative to the start of the superbox on which it
+/// was called.
+///
+/// [`SuperBox::verify_all()`]: crate::parser::SuperBox::verify_all
+/// [`SuperBox::verify_structure()`]: crate::parser::SuperBox::verify_structure
+/// [`SuperBox::child_boxes`]: crate::parser::SuperBox::child_boxes
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationIssue {
+    /// The superbox at `location` has more than one requestable child
+    /// superbox with the same label. [`SuperBox::find_by_label()`] will not
+    /// find any of them.
+    ///
+    /// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+    DuplicateLabel {
+        /// Location of the parent superbox.
+        location: Vec<usize>,
+
+        /// The duplicated label.
+        label: String,
+
+        /// Number of requestable child superboxes with this label.
+        count: usize,
+    },
+
+    /// The superbox at `location` is marked as requestable, but has no
+    /// label by which it could be requested.
+    UnlabeledRequestableBox {
+        /// Location of the superbox.
+        location: Vec<usize>,
+    },
+
+    /// The superbox at `location` has a hash in its description box, but
+    /// does not contain exactly one data box to which the hash can refer.
+    AmbiguousHash {
+        /// Location of the superbox.
+        location: Vec<usize>,
+
+        /// Number of child boxes of the superbox which are data boxes
+        /// (rather than superboxes).
+        data_box_count: usize,
+    },
+
+    /// Part of the payload of the superbox at `location` is not covered by
+    /// any child box.
+    UncoveredBytes {
+        /// Location of the superbox.
+        location: Vec<usize>,
+
+        /// Offset of the first uncovered byte.
+        offset: usize,
+
+        /// Number of uncovered bytes.
+        len: usize,
+    },
+
+    /// Two or more child boxes of the superbox at `location` occupy the same
+    /// bytes.
+    OverlappingBoxes {
+        /// Location of the superbox.
+        location: Vec<usize>,
+
+        /// Offset of the first byte of the overlap.
+        offset: usize,
+
+        /// Number of overlapping bytes.
+        len: usize,
+    },
+
+    /// A child box of the superbox at `location` is not entirely within
+    /// the payload of that superbox.
+    BoxOutsideParent {
+        /// Location of the superbox.
+        location: Vec<usize>,
+
+        /// Offset of the child box. If the child box was not parsed from
+        /// the same input, this value is meaningless.
+        offset: usize,
+    },
+
+    /// The length declared in the header of a box (or, for a child box,
+    /// the extent implied by a box size of 0) does not match its actual
+    /// length.
+    BoxSizeMismatch {
+        /// Location of the superbox which contains the box. For the
+        /// superbox on which [`SuperBox::verify_structure()`] was called,
+        /// this is empty.
+        ///
+        /// [`SuperBox::verify_structure()`]: crate::parser::SuperBox::verify_structure
+        location: Vec<usize>,
+
+        /// Offset of the box.
+        offset: usize,
+
+        /// Length declared in the box header, if the header could be read
+        /// and is consistent with the box's position.
+        declared: Option<u64>,
+
+        /// Actual length of the box.
+        actual: u64,
+    },
+}
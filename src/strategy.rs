@@ -0,0 +1,253 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! [proptest] strategies which generate JUMBF data structures.
+//!
+//! These are intended to help crates which depend on this crate
+//! property-test their handling of JUMBF. Use [`super_box_model()`] or
+//! [`jumbf_bytes()`] to generate valid JUMBF and [`near_valid_jumbf()`] to
+//! generate JUMBF which has been damaged in small, plausible ways.
+//!
+//! This module is available when the `proptest` crate feature is enabled.
+//!
+//! [proptest]: https://docs.rs/proptest
+
+use proptest::{collection::vec, prelude::*, sample::Index};
+
+use crate::{
+    box_type::{DESCRIPTION_BOX_TYPE, SUPER_BOX_TYPE},
+    model::{to_jumbf, ChildBoxModel, DataBoxModel, SuperBoxModel},
+    BoxType,
+};
+
+/// Describes the shape of the JUMBF trees generated by the strategies in this
+/// module.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TreeConfig {
+    /// Maximum depth of nested superboxes below the top-level superbox.
+    pub max_depth: u32,
+
+    /// Maximum number of child boxes in any superbox.
+    pub max_children: usize,
+
+    /// Maximum length of any data box payload, in bytes.
+    pub max_payload_len: usize,
+
+    /// Maximum length of any label, in characters.
+    pub max_label_len: usize,
+}
+
+impl Default for TreeConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_children: 4,
+            max_payload_len: 64,
+            max_label_len: 16,
+        }
+    }
+}
+
+/// Generates a [`DataBoxModel`] whose type is neither `jumb` nor `jumd`.
+pub fn data_box_model(config: TreeConfig) -> impl Strategy<Value = DataBoxModel> {
+    (
+        any::<[u8; 4]>(),
+        vec(any::<u8>(), 0..=config.max_payload_len),
+    )
+        .prop_map(|(tbox, data)| {
+            let mut tbox = BoxType(tbox);
+            if tbox == SUPER_BOX_TYPE || tbox == DESCRIPTION_BOX_TYPE {
+                tbox = BoxType(*b"data");
+            }
+            DataBoxModel { tbox, data }
+        })
+}
+
+/// Generates a label which can be serialized in a description box.
+///
+/// Labels never contain NUL (which would terminate the label) or `/`
+/// (which separates path components in [`SuperBox::find_by_label()`]).
+///
+/// [`SuperBox::find_by_label()`]: crate::parser::SuperBox::find_by_label
+pub fn label(config: TreeConfig) -> impl Strategy<Value = String> {
+    vec(
+        any::<char>().prop_filter("label separator", |c| *c != '\0' && *c != '/'),
+        0..=config.max_label_len,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+/// Generates a valid [`SuperBoxModel`] tree within the limits described by
+/// `config`.
+pub fn super_box_model(config: TreeConfig) -> BoxedStrategy<SuperBoxModel> {
+    let leaf =
+        super_box_with_children(config, vec(child_data_box(config), 0..=config.max_children));
+
+    leaf.prop_recursive(
+        config.max_depth,
+        (config.max_depth + 1) * config.max_children.max(1) as u32,
+        config.max_children.max(1) as u32,
+        move |inner| {
+            let child = prop_oneof![
+                child_data_box(config),
+                inner.prop_map(ChildBoxModel::SuperBox),
+            ];
+            super_box_with_children(config, vec(child, 0..=config.max_children))
+        },
+    )
+    .boxed()
+}
+
+/// Generates the serialized form of a valid JUMBF superbox within the limits
+/// described by `config`.
+pub fn jumbf_bytes(config: TreeConfig) -> impl Strategy<Value = Vec<u8>> {
+    super_box_model(config).prop_map(|sbox| serialize(&sbox))
+}
+
+/// A small, plausible form of damage to apply to serialized JUMBF.
+#[derive(Clone, Debug)]
+pub enum Mutation {
+    /// Truncate the data at the indicated position.
+    Truncate(Index),
+
+    /// Replace the byte at the indicated position with a new value.
+    ReplaceByte(Index, u8),
+
+    /// Overwrite the length field of the box header which starts at the
+    /// indicated box boundary with a new value.
+    ReplaceBoxLength(Index, u32),
+
+    /// Append extra bytes after the end of the data.
+    AppendBytes(Vec<u8>),
+}
+
+impl Mutation {
+    /// Apply this mutation to serialized JUMBF data.
+    pub fn apply(&self, jumbf: &mut Vec<u8>) {
+        match self {
+            Self::Truncate(index) => {
+                if !jumbf.is_empty() {
+                    jumbf.truncate(index.index(jumbf.len()));
+                }
+            }
+
+            Self::ReplaceByte(index, value) => {
+                if !jumbf.is_empty() {
+                    let i = index.index(jumbf.len());
+                    jumbf[i] = *value;
+                }
+            }
+
+            Self::ReplaceBoxLength(index, len) => {
+                let headers = box_header_offsets(jumbf);
+                if !headers.is_empty() {
+                    let offset = headers[index.index(headers.len())];
+                    jumbf[offset..offset + 4].copy_from_slice(&len.to_be_bytes());
+                }
+            }
+
+            Self::AppendBytes(bytes) => {
+                jumbf.extend_from_slice(bytes);
+            }
+        }
+    }
+}
+
+/// Generates a [`Mutation`].
+pub fn mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        any::<Index>().prop_map(Mutation::Truncate),
+        (any::<Index>(), any::<u8>()).prop_map(|(i, v)| Mutation::ReplaceByte(i, v)),
+        (any::<Index>(), any::<u32>()).prop_map(|(i, v)| Mutation::ReplaceBoxLength(i, v)),
+        vec(any::<u8>(), 1..16).prop_map(Mutation::AppendBytes),
+    ]
+}
+
+/// Generates serialized JUMBF which was valid before one [`Mutation`] was
+/// applied to it.
+///
+/// Note that some mutations (for example, replacing a payload byte) will
+/// leave the JUMBF valid.
+pub fn near_valid_jumbf(config: TreeConfig) -> impl Strategy<Value = Vec<u8>> {
+    (jumbf_bytes(config), mutation()).prop_map(|(mut jumbf, mutation)| {
+        mutation.apply(&mut jumbf);
+        jumbf
+    })
+}
+
+fn child_data_box(config: TreeConfig) -> impl Strategy<Value = ChildBoxModel> {
+    data_box_model(config).prop_map(ChildBoxModel::DataBox)
+}
+
+fn super_box_with_children(
+    config: TreeConfig,
+    children: impl Strategy<Value = Vec<ChildBoxModel>>,
+) -> impl Strategy<Value = SuperBoxModel> {
+    (
+        any::<[u8; 16]>(),
+        proptest::option::of(label(config)),
+        any::<bool>(),
+        any::<Option<u32>>(),
+        any::<Option<[u8; 32]>>(),
+        proptest::option::of(data_box_model(config)),
+        children,
+    )
+        .prop_map(
+            |(uuid, label, requestable, id, hash, private, child_boxes)| SuperBoxModel {
+                uuid,
+                label,
+                requestable,
+                id,
+                hash,
+                private,
+                child_boxes,
+            },
+        )
+}
+
+fn serialize(sbox: &SuperBoxModel) -> Vec<u8> {
+    // Writing to an in-memory buffer can't fail.
+    #[allow(clippy::unwrap_used)]
+    to_jumbf(sbox).unwrap()
+}
+
+// Find the offset of every box header in a well-formed JUMBF tree by
+// following superbox structure.
+fn box_header_offsets(jumbf: &[u8]) -> Vec<usize> {
+    fn walk(jumbf: &[u8], mut offset: usize, end: usize, result: &mut Vec<usize>) {
+        while offset + 8 <= end {
+            let len = u32::from_be_bytes([
+                jumbf[offset],
+                jumbf[offset + 1],
+                jumbf[offset + 2],
+                jumbf[offset + 3],
+            ]) as usize;
+
+            if len < 8 || offset + len > end {
+                return;
+            }
+
+            result.push(offset);
+
+            if jumbf[offset + 4..offset + 8] == SUPER_BOX_TYPE.0 {
+                walk(jumbf, offset + 8, offset + len, result);
+            }
+
+            offset += len;
+        }
+    }
+
+    let mut result = vec![];
+    walk(jumbf, 0, jumbf.len(), &mut result);
+    result
+}
@@ -0,0 +1,220 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Readers which inject I/O failures, for testing code which reads JUMBF
+//! from a stream.
+//!
+//! [`FlakyReader`] fails when a read reaches a chosen offset, and
+//! [`ShortReader`] returns fewer bytes per read than requested. Use
+//! [`assert_read_errors_propagate()`] to check that a failure at _any_
+//! offset is reported to the caller rather than ignored or turned into a
+//! different error.
+//!
+//! This module is available when the `test-utils` crate feature is
+//! enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[cfg(feature = "parser")]
+//! # {
+//! use jumbf::{parser::layout::BoxLayout, test_utils::assert_read_errors_propagate};
+//!
+//! let jumbf = hex_literal::hex!(
+//!     "00000027" // box size
+//!     "6a756d62" // box type = 'jumb'
+//!         "0000001f" // box size
+//!         "6a756d64" // box type = 'jumd'
+//!         "00000000000000000000000000000000" // UUID
+//!         "03" // toggles
+//!         "746573747300" // label = "tests"
+//! );
+//!
+//! let failures = assert_read_errors_propagate(&jumbf, |reader| BoxLayout::from_reader(reader));
+//! assert!(failures > 0);
+//! # }
+//! ```
+
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+    io::{Cursor, Error, Read, Result, Seek, SeekFrom},
+};
+
+/// The error returned by a [`FlakyReader`] when a read reaches its failure
+/// offset.
+///
+/// This is wrapped in an [`std::io::Error`] of kind
+/// [`ErrorKind::Other`](std::io::ErrorKind::Other) and can be recovered
+/// using [`InjectedError::find()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InjectedError {
+    /// Offset within the stream at which the read failed.
+    pub offset: u64,
+}
+
+impl InjectedError {
+    /// Returns the `InjectedError` wrapped by `err`, if any.
+    pub fn find(err: &Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl Display for InjectedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "injected read failure at offset {}", self.offset)
+    }
+}
+
+impl StdError for InjectedError {}
+
+/// A reader which returns an error when a read reaches a given offset.
+///
+/// Bytes before the failure offset are returned normally; a read which
+/// spans the failure offset returns only the bytes before it. A read which
+/// starts at the failure offset returns an [`InjectedError`]. Reads which
+/// start after the failure offset (because the caller has seeked past it)
+/// succeed.
+pub struct FlakyReader<R> {
+    inner: R,
+    fail_at: u64,
+    failed: bool,
+}
+
+impl<R: Read + Seek> FlakyReader<R> {
+    /// Wrap `inner` so that reading the byte at offset `fail_at` fails.
+    pub fn new(inner: R, fail_at: u64) -> Self {
+        Self {
+            inner,
+            fail_at,
+            failed: false,
+        }
+    }
+
+    /// Returns `true` if an error has been returned by this reader.
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Unwrap this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> Read for FlakyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let pos = self.inner.stream_position()?;
+
+        if pos == self.fail_at && !buf.is_empty() {
+            self.failed = true;
+            return Err(Error::other(InjectedError {
+                offset: self.fail_at,
+            }));
+        }
+
+        let len = if pos < self.fail_at {
+            buf.len()
+                .min((self.fail_at - pos).try_into().unwrap_or(usize::MAX))
+        } else {
+            buf.len()
+        };
+
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+impl<R: Seek> Seek for FlakyReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// A reader which returns at most a fixed number of bytes from each read.
+///
+/// Well-behaved callers must handle short reads, typically by using
+/// [`Read::read_exact()`].
+pub struct ShortReader<R> {
+    inner: R,
+    max_read: usize,
+}
+
+impl<R> ShortReader<R> {
+    /// Wrap `inner` so that each read returns at most `max_read` bytes.
+    ///
+    /// `max_read` is treated as 1 if it is 0.
+    pub fn new(inner: R, max_read: usize) -> Self {
+        Self {
+            inner,
+            max_read: max_read.max(1),
+        }
+    }
+
+    /// Unwrap this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ShortReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = buf.len().min(self.max_read);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+impl<R: Seek> Seek for ShortReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// Run `f` once for each offset in `data`, with a read failure injected
+/// at that offset, and check that each failure is reported.
+///
+/// Whenever `f` reads the byte at the injected offset, it must return an
+/// error which wraps the corresponding [`InjectedError`]. Offsets that `f`
+/// never reads (for example, payloads that it seeks over) are not checked.
+///
+/// Returns the number of offsets at which a failure was injected.
+///
+/// ## Panics
+///
+/// Panics if `f` succeeds or returns a different error after a failure
+/// was injected.
+pub fn assert_read_errors_propagate<T, F>(data: &[u8], mut f: F) -> usize
+where
+    F: FnMut(&mut FlakyReader<Cursor<&[u8]>>) -> Result<T>,
+{
+    let mut failures = 0;
+
+    for offset in 0..data.len() as u64 {
+        let mut reader = FlakyReader::new(Cursor::new(data), offset);
+        let result = f(&mut reader);
+
+        if !reader.has_failed() {
+            continue;
+        }
+
+        failures += 1;
+
+        let injected = result.as_ref().err().and_then(InjectedError::find);
+        assert!(
+            injected == Some(&InjectedError { offset }),
+            "read failure at offset {offset} was not reported (got {:?})",
+            result.err()
+        );
+    }
+
+    failures
+}
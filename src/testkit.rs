@@ -0,0 +1,211 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Generators for example JUMBF data structures, for testing JUMBF parsers.
+//!
+//! Each function here returns the serialized bytes of a valid JUMBF
+//! superbox. The output depends only on the arguments, so it can be stored
+//! as a test vector or regenerated on demand. The structures are modeled on
+//! those described by ISO/IEC 19566-5 and the C2PA technical specification,
+//! but are not copies of any example in either document.
+//!
+//! All boxes are written using the most compact header encoding. Any JUMBF
+//! parser should be able to parse all of them.
+//!
+//! Some functions accept payloads or dimensions chosen by the caller. An
+//! error is returned if the resulting superbox can't be written, such as
+//! when a payload is too large for any JUMBF box.
+//!
+//! This module is available when the `test-utils` crate feature is
+//! enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! use jumbf::testkit;
+//!
+//! # fn example() -> std::io::Result<()> {
+//! let jumbf = testkit::c2pa_manifest_store("urn:uuid:00000000-0000-0000-0000-000000000000")?;
+//! assert_eq!(&jumbf[4..8], b"jumb");
+//! # Ok(())
+//! # }
+//! # example().unwrap();
+//! ```
+
+use std::io::{Cursor, Result};
+
+use crate::{
+    builder::{DataBoxBuilder, SuperBoxBuilder},
+    content_type::{self, ContentType},
+    BoxType,
+};
+
+const CBOR_BOX_TYPE: BoxType = BoxType(*b"cbor");
+const JSON_BOX_TYPE: BoxType = BoxType(*b"json");
+
+/// Returns a superbox which contains only a description box.
+///
+/// The description box has an all-zeros UUID and no label, ID, hash, or
+/// private box. This is the smallest possible superbox (33 bytes).
+pub fn empty_super_box() -> Result<Vec<u8>> {
+    to_vec(&SuperBoxBuilder::new(&[0u8; 16]))
+}
+
+/// Returns a JSON content type superbox with a requestable `label` which
+/// contains a single `json` box whose payload is `json`.
+///
+/// `json` is not validated.
+pub fn json_super_box(label: &str, json: &[u8]) -> Result<Vec<u8>> {
+    to_vec(&json_builder(label, json))
+}
+
+/// Returns a tree of superboxes with requestable labels, `depth` levels
+/// deep below the outermost superbox, with `width` child superboxes at each
+/// level.
+///
+/// The outermost superbox is labeled `root`. Its children are labeled `n0`
+/// through `n{width - 1}`, and so on, so that a superbox at depth 2 can be
+/// found using a label path such as `n0/n1`. Each superbox at depth `depth`
+/// contains a JSON box whose payload is a JSON string containing its label
+/// path (i.e. `"n0/n1"`). Superboxes above that level contain only child
+/// superboxes.
+///
+/// The number of superboxes grows as `width` to the power of `depth`, so
+/// both should be kept small.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "parser")]
+/// # {
+/// use jumbf::{parser::SuperBox, testkit};
+///
+/// let jumbf = testkit::nested_labels(3, 2).unwrap();
+/// let sbox = SuperBox::parse(&jumbf).unwrap();
+///
+/// let leaf = sbox.find_by_label("n1/n0/n1").unwrap();
+/// assert_eq!(leaf.data_box().unwrap().data, b"\"n1/n0/n1\"");
+/// # }
+/// ```
+pub fn nested_labels(depth: usize, width: usize) -> Result<Vec<u8>> {
+    fn build(path: &str, depth: usize, width: usize) -> SuperBoxBuilder<'static> {
+        let label = path.rsplit('/').next().unwrap_or(path);
+
+        if depth == 0 {
+            return json_builder(label, format!("{path:?}").as_bytes());
+        }
+
+        let mut sbox = SuperBoxBuilder::new(&content_type::JSON.uuid).set_label(label);
+        for i in 0..width {
+            let child_path = match path {
+                "root" => format!("n{i}"),
+                _ => format!("{path}/n{i}"),
+            };
+            sbox = sbox.add_child_box(build(&child_path, depth - 1, width));
+        }
+        sbox
+    }
+
+    to_vec(&build("root", depth, width))
+}
+
+/// Returns a C2PA manifest store which contains a single manifest with the
+/// given label.
+///
+/// The manifest has the usual skeleton of a C2PA manifest:
+///
+/// * `c2pa.assertions`: an assertion store which contains one CBOR assertion,
+///   `c2pa.hash.data`, whose content is an empty CBOR map
+/// * `c2pa.claim`: a claim whose CBOR content refers to that assertion using
+///   the URI `self#jumbf=/c2pa/{manifest_label}/c2pa.assertions/c2pa.hash.data`
+/// * `c2pa.signature`: a claim signature whose content is a CBOR `null`
+///
+/// This is sufficient for testing how a parser navigates a manifest store,
+/// but the claim and signature are not otherwise meaningful and would
+/// fail C2PA validation.
+///
+/// `manifest_label` is used as is; it should not contain `/`.
+pub fn c2pa_manifest_store(manifest_label: &str) -> Result<Vec<u8>> {
+    let assertion_uri = format!("self#jumbf=/c2pa/{manifest_label}/c2pa.assertions/c2pa.hash.data");
+
+    // {"assertions": [{"url": assertion_uri}]}
+    let mut claim = vec![0xa1];
+    cbor_text(&mut claim, "assertions");
+    claim.extend_from_slice(&[0x81, 0xa1]);
+    cbor_text(&mut claim, "url");
+    cbor_text(&mut claim, &assertion_uri);
+
+    let store = SuperBoxBuilder::new(&content_type::C2PA_MANIFEST_STORE.uuid)
+        .set_label("c2pa")
+        .add_child_super_box(&content_type::C2PA_MANIFEST.uuid, manifest_label, |b| {
+            b.add_child_super_box(
+                &content_type::C2PA_ASSERTION_STORE.uuid,
+                "c2pa.assertions",
+                |b| {
+                    b.add_child_box(cbor_builder(
+                        "c2pa.hash.data",
+                        &content_type::CBOR,
+                        vec![0xa0],
+                    ))
+                },
+            )
+            .add_child_box(cbor_builder("c2pa.claim", &content_type::C2PA_CLAIM, claim))
+            .add_child_box(cbor_builder(
+                "c2pa.signature",
+                &content_type::C2PA_CLAIM_SIGNATURE,
+                vec![0xf6],
+            ))
+        });
+
+    to_vec(&store)
+}
+
+fn json_builder(label: &str, json: &[u8]) -> SuperBoxBuilder<'static> {
+    SuperBoxBuilder::new(&content_type::JSON.uuid)
+        .set_label(label)
+        .add_child_box(DataBoxBuilder::from_owned(JSON_BOX_TYPE, json.to_vec()))
+}
+
+fn cbor_builder(label: &str, ct: &ContentType, cbor: Vec<u8>) -> SuperBoxBuilder<'static> {
+    SuperBoxBuilder::new(&ct.uuid)
+        .set_label(label)
+        .add_child_box(DataBoxBuilder::from_owned(CBOR_BOX_TYPE, cbor))
+}
+
+// Append a CBOR text string (major type 3).
+fn cbor_text(out: &mut Vec<u8>, s: &str) {
+    let len = s.len() as u64;
+    match len {
+        0..=23 => out.push(0x60 | len as u8),
+        24..=0xff => out.extend_from_slice(&[0x78, len as u8]),
+        0x100..=0xffff => {
+            out.push(0x79);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0x7a);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0x7b);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn to_vec(sbox: &SuperBoxBuilder) -> Result<Vec<u8>> {
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf)?;
+    Ok(jumbf.into_inner())
+}
@@ -0,0 +1,104 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, ErrorKind, Write};
+
+use crate::{
+    builder::{BoxWriter, DataBoxBuilder, SuperBoxBuilder},
+    tests::digest::XorDigest,
+    BoxType,
+};
+
+const RANDOM_BOX_TYPE: BoxType = BoxType(*b"abcd");
+
+#[test]
+fn matches_super_box_builder() {
+    let expected = SuperBoxBuilder::new(&[1u8; 16])
+        .set_label("outer")
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"first"))
+        .add_child_box(
+            SuperBoxBuilder::new(&[2u8; 16])
+                .set_label("inner")
+                .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"streamed")),
+        )
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b""));
+
+    let mut expected_jumbf = Cursor::new(b"prefix".to_vec());
+    expected_jumbf.set_position(6);
+    expected.write_jumbf(&mut expected_jumbf).unwrap();
+
+    let mut stream = Cursor::new(b"prefix".to_vec());
+    stream.set_position(6);
+    let mut writer = BoxWriter::new(stream);
+
+    writer
+        .begin_super_box(
+            &SuperBoxBuilder::new(&[1u8; 16])
+                .set_label("outer")
+                .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"first")),
+        )
+        .unwrap();
+
+    writer
+        .begin_super_box(&SuperBoxBuilder::new(&[2u8; 16]).set_label("inner"))
+        .unwrap();
+    assert_eq!(writer.open_boxes(), 2);
+
+    writer.begin_box(RANDOM_BOX_TYPE).unwrap();
+    writer.write_all(b"stream").unwrap();
+    writer.write_all(b"ed").unwrap();
+    writer.end_box().unwrap();
+
+    writer.end_box().unwrap();
+
+    writer
+        .write_box(&DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b""))
+        .unwrap();
+
+    let range = writer.end_box().unwrap();
+    assert_eq!(range.start, 6);
+    assert_eq!(writer.open_boxes(), 0);
+
+    let jumbf = writer.finish().unwrap().into_inner();
+    assert_eq!(range.end, jumbf.len() as u64);
+    assert_eq!(jumbf, expected_jumbf.into_inner());
+}
+
+#[test]
+fn error_no_open_box() {
+    let mut writer = BoxWriter::new(Cursor::new(Vec::<u8>::new()));
+    let err = writer.end_box().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "no box is open");
+}
+
+#[test]
+fn error_box_still_open() {
+    let mut writer = BoxWriter::new(Cursor::new(Vec::<u8>::new()));
+    writer.begin_box(RANDOM_BOX_TYPE).unwrap();
+
+    let err = writer.finish().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "1 box(es) still open");
+}
+
+#[test]
+fn error_computed_hash() {
+    let mut writer = BoxWriter::new(Cursor::new(Vec::<u8>::new()));
+
+    let err = writer
+        .begin_super_box(&SuperBoxBuilder::new(&[0u8; 16]).set_computed_hash(XorDigest))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(writer.open_boxes(), 0);
+}
@@ -11,7 +11,7 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::io::Cursor;
+use std::{borrow::Cow, io::Cursor};
 
 use hex_literal::hex;
 
@@ -74,3 +74,44 @@ fn simple_box_owned() {
     write_jumbf(&boxx, &mut jumbf).unwrap();
     assert_eq!(*jumbf.into_inner(), expected_jumbf);
 }
+
+#[test]
+fn simple_box_segments() {
+    let expected_jumbf = hex!(
+        "00000026" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label
+    );
+
+    let boxx = DataBoxBuilder::from_segments(
+        DESCRIPTION_BOX_TYPE,
+        vec![
+            Cow::Borrowed(&expected_jumbf[8..24]),
+            Cow::Owned(vec![]),
+            Cow::Owned(expected_jumbf[24..30].to_owned()),
+            Cow::Borrowed(&expected_jumbf[30..]),
+        ],
+    );
+
+    assert_eq!(boxx.box_type(), DESCRIPTION_BOX_TYPE);
+    assert_eq!(boxx.payload_size().unwrap(), 30);
+
+    let mut payload = Cursor::new(Vec::<u8>::new());
+    boxx.write_payload(&mut payload).unwrap();
+    assert_eq!(*payload.into_inner(), expected_jumbf[8..]);
+
+    assert_eq!(jumbf_size(&boxx).unwrap(), 38);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    write_jumbf(&boxx, &mut jumbf).unwrap();
+    assert_eq!(*jumbf.into_inner(), expected_jumbf);
+}
+
+#[test]
+fn no_segments() {
+    let boxx = DataBoxBuilder::from_segments(DESCRIPTION_BOX_TYPE, vec![]);
+    assert_eq!(boxx.payload_size().unwrap(), 0);
+    assert_eq!(jumbf_size(&boxx).unwrap(), 8);
+}
@@ -18,6 +18,10 @@
 #![allow(clippy::panic)]
 #![allow(clippy::unwrap_used)]
 
+mod box_writer;
 mod data_box_builder;
 mod placeholder_data_box;
+mod sequence;
+mod signature_placeholder;
 mod super_box_builder;
+mod to_box;
@@ -147,3 +147,70 @@ fn offset_before_write() {
     assert_eq!(jumbf_size(&pbox).unwrap(), 24);
     assert_eq!(pbox.offset(), None);
 }
+
+#[test]
+fn replace_payload_at() {
+    let pbox = PlaceholderDataBox::new(RANDOM_BOX_TYPE, 16);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    write_jumbf(&pbox, &mut jumbf).unwrap();
+
+    pbox.replace_payload_at(&mut jumbf, 0, b"1234").unwrap();
+    pbox.replace_payload_at(&mut jumbf, 12, b"abcd").unwrap();
+
+    let expected_jumbf = hex!(
+        "00000018" // box size
+        "61626364" // box type = 'abcd'
+        "31323334000000000000000061626364" // partially replaced payload
+    );
+    assert_eq!(*jumbf.get_ref(), expected_jumbf);
+
+    let err = pbox
+        .replace_payload_at(&mut jumbf, 13, b"abcd")
+        .unwrap_err();
+    assert_eq!(
+        "Custom { kind: Other, error: \"replace_payload_at: payload (4 bytes) at offset 13 extends beyond reserved capacity (16 bytes)\" }",
+        format!("{err:?}")
+    );
+
+    let err = pbox
+        .replace_payload_at(&mut jumbf, usize::MAX, b"a")
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("extends beyond reserved capacity"));
+
+    // No part of the JUMBF should have been changed by the failed calls.
+    assert_eq!(*jumbf.get_ref(), expected_jumbf);
+}
+
+#[test]
+fn capacity() {
+    let pbox = PlaceholderDataBox::new(RANDOM_BOX_TYPE, 16);
+    assert_eq!(pbox.capacity(), 16);
+    assert_eq!(pbox.written_len(), 0);
+    assert_eq!(pbox.remaining(), 16);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    write_jumbf(&pbox, &mut jumbf).unwrap();
+
+    pbox.replace_payload(&mut jumbf, b"1234").unwrap();
+    assert_eq!(pbox.written_len(), 4);
+    assert_eq!(pbox.remaining(), 12);
+
+    pbox.replace_payload_at(&mut jumbf, 8, b"abcd").unwrap();
+    assert_eq!(pbox.written_len(), 12);
+    assert_eq!(pbox.remaining(), 4);
+
+    // An earlier, shorter write doesn't reduce the written length.
+    pbox.replace_payload(&mut jumbf, b"12").unwrap();
+    assert_eq!(pbox.written_len(), 12);
+
+    // A failed write doesn't change it either.
+    pbox.replace_payload_at(&mut jumbf, 14, b"xyz").unwrap_err();
+    assert_eq!(pbox.written_len(), 12);
+
+    // Writing the box again starts over.
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    write_jumbf(&pbox, &mut jumbf).unwrap();
+    assert_eq!(pbox.written_len(), 0);
+    assert_eq!(pbox.remaining(), 16);
+}
@@ -0,0 +1,70 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, ErrorKind};
+
+use crate::{
+    builder::{write_jumbf_sequence, DataBoxBuilder, SuperBoxBuilder, ToBox},
+    BoxType,
+};
+
+#[test]
+fn two_manifests() {
+    let first = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("first")
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"data"));
+    let second = SuperBoxBuilder::new(&[0u8; 16]).set_label("second");
+
+    let mut jumbf = Cursor::new(b"prefix".to_vec());
+    jumbf.set_position(6);
+
+    let ranges = write_jumbf_sequence(&[&first as &dyn ToBox, &second], &mut jumbf).unwrap();
+    assert_eq!(ranges, vec![6..57, 57..97]);
+
+    let jumbf = jumbf.into_inner();
+    assert_eq!(jumbf.len(), 97);
+
+    #[cfg(feature = "parser")]
+    {
+        use crate::parser::SuperBox;
+
+        let (rem, sbox) = SuperBox::from_slice(&jumbf[6..]).unwrap();
+        assert_eq!(sbox.desc.label, Some("first"));
+        assert_eq!(sbox.original, &jumbf[6..57]);
+
+        let (rem, sbox) = SuperBox::from_slice(rem).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(sbox.desc.label, Some("second"));
+    }
+}
+
+#[test]
+fn empty_sequence() {
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let ranges = write_jumbf_sequence(&[], &mut jumbf).unwrap();
+    assert!(ranges.is_empty());
+    assert!(jumbf.get_ref().is_empty());
+}
+
+#[test]
+fn error_nothing_written() {
+    let first = SuperBoxBuilder::new(&[0u8; 16]).set_label("first");
+    let second = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("second")
+        .set_max_size(10);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = write_jumbf_sequence(&[&first as &dyn ToBox, &second], &mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(jumbf.get_ref().is_empty());
+}
@@ -0,0 +1,108 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, ErrorKind, Write};
+
+use hex_literal::hex;
+
+use crate::{
+    builder::{DataBoxBuilder, SignaturePlaceholder, SuperBoxBuilder},
+    BoxType,
+};
+
+const RANDOM_BOX_TYPE: BoxType = BoxType(*b"abcd");
+const SIG_BOX_TYPE: BoxType = BoxType(*b"sigx");
+
+#[test]
+fn sign_and_inject() {
+    let sig = SignaturePlaceholder::new(SIG_BOX_TYPE, 8);
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"))
+        .add_borrowed_child_box(&sig);
+
+    // Simulate a host file which already has some content.
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    jumbf.write_all(b"HOST").unwrap();
+
+    let ranges = sig.write_jumbf(&sbox, &mut jumbf).unwrap();
+    assert_eq!(ranges, [4..57, 65..65]);
+
+    let signed_data = sig.signed_data(&ranges, &mut jumbf).unwrap();
+    assert_eq!(signed_data, jumbf.get_ref()[4..57]);
+
+    sig.write_signature(&mut jumbf, b"SIG").unwrap();
+
+    let expected_jumbf = hex!(
+        "484f5354" // host content
+        "0000003d" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "41424344" // payload
+            // ---
+            "00000010" // box size
+            "73696778" // box type = 'sigx'
+            "5349470000000000" // signature
+    );
+
+    assert_eq!(*jumbf.get_ref(), expected_jumbf);
+}
+
+#[test]
+fn error_not_in_superbox() {
+    let sig = SignaturePlaceholder::new(SIG_BOX_TYPE, 8);
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"));
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sig.write_jumbf(&sbox, &mut jumbf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "signature placeholder was not written as part of the superbox"
+    );
+}
+
+#[test]
+fn error_signature_too_large() {
+    let sig = SignaturePlaceholder::new(SIG_BOX_TYPE, 2);
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .add_borrowed_child_box(&sig);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sig.write_jumbf(&sbox, &mut jumbf).unwrap();
+
+    assert!(sig.write_signature(&mut jumbf, b"SIG").is_err());
+}
+
+#[test]
+fn error_invalid_signed_range() {
+    let sig = SignaturePlaceholder::new(SIG_BOX_TYPE, 8);
+    let mut jumbf = Cursor::new(vec![0u8; 16]);
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let err = sig.signed_data(&[0..4, 8..4], &mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = sig.signed_data(&[0..8, 12..20], &mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    assert_eq!(
+        sig.signed_data(&[0..8, 12..16], &mut jumbf).unwrap().len(),
+        12
+    );
+}
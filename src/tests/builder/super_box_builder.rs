@@ -11,13 +11,18 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::io::Cursor;
+use std::io::{Cursor, ErrorKind};
 
 use hex_literal::hex;
 
 use crate::{
-    builder::{DataBoxBuilder, PlaceholderDataBox, SuperBoxBuilder},
-    BoxType,
+    builder::{
+        to_box::jumbf_size, BoxPlan, ChildOrder, DataBoxBuilder, PlaceholderDataBox,
+        SuperBoxBuilder, ToBox, WriteAndSeek,
+    },
+    digest::Digest,
+    tests::digest::XorDigest,
+    BoxType, Toggles,
 };
 
 // Used here as an illustration only. This crate does not parse JSON content.
@@ -107,6 +112,61 @@ fn with_hash() {
     assert_eq!(*jumbf.into_inner(), expected_jumbf);
 }
 
+#[test]
+fn with_computed_hash() {
+    let expected_jumbf = hex!(
+        "0000004d" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000039" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "08" // toggles
+            "0000000c616263644142434400000000"
+            "00000000000000000000000000000000" // hash
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "41424344" // payload
+    );
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_sha256_hash(b"This is a bogus hash............" as &[u8; 32])
+        .set_computed_hash(XorDigest)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"));
+
+    assert_eq!(sbox.toggles(), Toggles::HAS_HASH);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(*jumbf.into_inner(), expected_jumbf);
+}
+
+#[test]
+fn error_computed_hash_wrong_size() {
+    struct Short;
+
+    impl Digest for Short {
+        fn algorithm(&self) -> &str {
+            "short"
+        }
+
+        fn digest(&self, _data: &[u8]) -> Vec<u8> {
+            vec![0; 20]
+        }
+    }
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_computed_hash(Short)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"));
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "computed hash must be 32 bytes, short produced 20 bytes"
+    );
+}
+
 #[test]
 fn with_private_box() {
     let expected_jumbf = hex!(
@@ -139,6 +199,63 @@ fn with_private_box() {
     assert_eq!(*jumbf.into_inner(), expected_jumbf);
 }
 
+#[test]
+fn with_salt() {
+    let expected_jumbf = hex!(
+        "00000046" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000003e" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "13" // toggles
+            "746573742e64657363626f7800" // label
+                "00000018" // box size
+                "63327368" // box type = 'c2sh'
+                "000102030405060708090a0b0c0d0e0f" // salt
+    );
+
+    let salt = hex!("000102030405060708090a0b0c0d0e0f");
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_label("test.descbox")
+        .set_salt(&salt);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(*jumbf.get_ref(), expected_jumbf);
+
+    #[cfg(feature = "parser")]
+    {
+        let (_, parsed) = crate::parser::SuperBox::from_slice(jumbf.get_ref()).unwrap();
+        assert_eq!(parsed.salt(), Some(salt.as_slice()));
+    }
+}
+
+#[cfg(all(feature = "getrandom", feature = "parser"))]
+#[test]
+fn with_random_salt() {
+    let write = || {
+        let sbox = SuperBoxBuilder::new(&[0u8; 16])
+            .set_label("test.descbox")
+            .set_random_salt(32)
+            .unwrap();
+
+        let mut jumbf = Cursor::new(Vec::<u8>::new());
+        sbox.write_jumbf(&mut jumbf).unwrap();
+        jumbf.into_inner()
+    };
+
+    let first = write();
+    let second = write();
+    assert_eq!(first.len(), second.len());
+
+    let (_, first) = crate::parser::SuperBox::from_slice(&first).unwrap();
+    let (_, second) = crate::parser::SuperBox::from_slice(&second).unwrap();
+
+    assert_eq!(first.salt().map(|salt| salt.len()), Some(32));
+    assert_ne!(first.salt(), second.salt());
+}
+
 #[test]
 fn no_label() {
     let expected_jumbf = hex!(
@@ -255,3 +372,539 @@ fn with_placeholder() {
 
     assert_eq!(*jumbf.get_ref(), expected_jumbf);
 }
+
+#[test]
+fn canonical_child_order() {
+    let expected_jumbf = hex!(
+        "00000045" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "41424344" // payload
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "45464748" // payload
+            // ---
+            "0000000c" // box size
+            "6a736f6e" // box type = 'json'
+            "7b7d2020" // payload
+    );
+
+    let json = DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, b"{}  ");
+    let efgh = DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"EFGH");
+    let abcd = DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD");
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_child_order(ChildOrder::Canonical)
+        .add_borrowed_child_box(&json)
+        .add_borrowed_child_box(&efgh)
+        .add_borrowed_child_box(&abcd);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(*jumbf.into_inner(), expected_jumbf);
+}
+
+#[test]
+fn canonical_child_order_with_placeholder() {
+    let cbox = DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD");
+    let pbox = PlaceholderDataBox::new(RANDOM_BOX_TYPE, 4);
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_child_order(ChildOrder::Canonical)
+        .add_borrowed_child_box(&cbox)
+        .add_borrowed_child_box(&pbox);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    // The placeholder sorts first, so its offset must reflect its final
+    // position in the stream.
+    assert_eq!(pbox.offset(), Some(41));
+
+    pbox.replace_payload(&mut jumbf, b"0123").unwrap();
+    assert_eq!(jumbf.get_ref()[41..45], *b"0123");
+}
+
+#[test]
+fn nested_canonical_child_order_with_placeholder() {
+    let pbox = PlaceholderDataBox::new(RANDOM_BOX_TYPE, 4);
+
+    let inner = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_child_order(ChildOrder::Canonical)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"))
+        .add_borrowed_child_box(&pbox);
+
+    let sbox = SuperBoxBuilder::new(&hex!("00000000000000000000000000000000"))
+        .set_child_order(ChildOrder::Canonical)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"))
+        .add_borrowed_child_box(&inner);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    // The inner superbox sorts after the data box. Its placeholder sorts
+    // first within it.
+    assert_eq!(pbox.offset(), Some(41 + 12 + 33));
+
+    pbox.replace_payload(&mut jumbf, b"0123").unwrap();
+    assert_eq!(jumbf.get_ref()[86..90], *b"0123");
+}
+
+// Claims an impossibly large payload to exercise size overflow handling.
+struct HugeBox(usize);
+
+impl ToBox for HugeBox {
+    fn box_type(&self) -> BoxType {
+        BoxType(*b"huge")
+    }
+
+    fn payload_size(&self) -> std::io::Result<usize> {
+        Ok(self.0)
+    }
+
+    fn write_payload(&self, _to_stream: &mut dyn WriteAndSeek) -> std::io::Result<()> {
+        unreachable!("payload should not be written");
+    }
+}
+
+#[test]
+fn error_payload_too_large() {
+    let sbox = SuperBoxBuilder::new(&[0; 16]).add_child_box(HugeBox(usize::MAX - 4));
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = SuperBoxBuilder::new(&[0; 16])
+        .add_child_box(HugeBox(0xffff_fff8))
+        .write_jumbf(&mut jumbf)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn error_total_size_too_large() {
+    // Each child fits in a 32-bit box, but together they don't.
+    let sbox = SuperBoxBuilder::new(&[0; 16])
+        .add_child_box(HugeBox(0x8000_0000))
+        .add_child_box(HugeBox(0x8000_0000));
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn find_and_update() {
+    let borrowed = SuperBoxBuilder::new(&[0u8; 16]).set_label("borrowed");
+
+    let claim = SuperBoxBuilder::new(&[0u8; 16]).set_label("claim");
+    let hidden = SuperBoxBuilder::new(&[0u8; 16]).set_non_requestable_label("hidden");
+    let manifest = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("manifest")
+        .add_child_box(DataBoxBuilder::from_owned(JSON_BOX_TYPE, b"{}".to_vec()))
+        .add_child_box(claim)
+        .add_child_box(hidden);
+
+    let dup1 = SuperBoxBuilder::new(&[0u8; 16]).set_label("dup");
+    let dup2 = SuperBoxBuilder::new(&[0u8; 16]).set_label("dup");
+
+    let mut sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("c2pa")
+        .add_child_box(manifest)
+        .add_child_box(dup1)
+        .add_child_box(dup2)
+        .add_borrowed_child_box(&borrowed);
+
+    assert_eq!(jumbf_size(sbox.find("manifest").unwrap()).unwrap(), 131);
+    assert_eq!(
+        jumbf_size(sbox.find("manifest/claim").unwrap()).unwrap(),
+        39
+    );
+    assert!(sbox.find("c2pa").is_none());
+    assert!(sbox.find("manifest/hidden").is_none());
+    assert!(sbox.find("dup").is_none());
+    assert!(sbox.find("borrowed").is_none());
+    assert!(sbox.find("").is_none());
+
+    assert!(sbox.update("manifest/claim", |claim| claim.set_id(42)));
+    assert!(sbox
+        .find("manifest/claim")
+        .unwrap()
+        .toggles()
+        .contains(Toggles::HAS_ID));
+
+    assert!(!sbox.update("manifest/signature", |_| unreachable!()));
+    assert!(!sbox.update("dup", |_| unreachable!()));
+
+    // The updated superbox is written in its original position.
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    let jumbf = jumbf.into_inner();
+    let claim_desc = hex!(
+        "07" // toggles
+        "636c61696d00" // label
+        "0000002a" // id
+    );
+    assert!(jumbf.windows(claim_desc.len()).any(|w| w == claim_desc));
+}
+
+#[test]
+fn max_size() {
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("test.superbox")
+        .set_max_size(47);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(jumbf.get_ref().len(), 47);
+
+    let sbox = sbox.set_max_size(46);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "superbox of 47 bytes exceeds maximum size of 46 bytes"
+    );
+    assert!(jumbf.get_ref().is_empty());
+
+    // The limit is enforced before anything is written, even when nested.
+    let outer = SuperBoxBuilder::new(&[0u8; 16]).add_child_box(sbox);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = outer.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(jumbf.get_ref().is_empty());
+
+    // Also when the parent sorts its children.
+    let outer = outer.set_child_order(ChildOrder::Canonical);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = outer.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(jumbf.get_ref().is_empty());
+}
+
+#[test]
+fn write_jumbf_at() {
+    let sbox =
+        SuperBoxBuilder::new(&hex!("00000000000000000000000000000000")).set_label("test.descbox");
+
+    let mut expected_sbox = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut expected_sbox).unwrap();
+    let expected_sbox = expected_sbox.into_inner();
+    assert_eq!(expected_sbox.len(), 46);
+
+    // Replace 60 bytes of free space at offset 10.
+    let mut file = Cursor::new(vec![0xaa_u8; 100]);
+    assert_eq!(sbox.write_jumbf_at(&mut file, 10, Some(60)).unwrap(), 46);
+    assert_eq!(file.position(), 70);
+
+    let file = file.into_inner();
+    assert_eq!(file[0..10], [0xaa; 10]);
+    assert_eq!(file[10..56], expected_sbox);
+    assert_eq!(
+        file[56..70],
+        hex!(
+            "0000000e" // box size
+            "66726565" // box type = 'free'
+            "000000000000" // padding
+        )
+    );
+    assert_eq!(file[70..], [0xaa; 30]);
+
+    // Exact fit: no free box is needed.
+    let mut file = Cursor::new(vec![0xaa_u8; 100]);
+    sbox.write_jumbf_at(&mut file, 0, Some(46)).unwrap();
+    assert_eq!(file.get_ref()[0..46], expected_sbox);
+    assert_eq!(file.get_ref()[46..], [0xaa; 54]);
+
+    // Without free space, simply overwrite at the offset.
+    let mut file = Cursor::new(vec![0xaa_u8; 100]);
+    sbox.write_jumbf_at(&mut file, 54, None).unwrap();
+    assert_eq!(file.get_ref()[54..], expected_sbox);
+
+    // Doesn't fit.
+    let mut file = Cursor::new(vec![0xaa_u8; 100]);
+    let err = sbox.write_jumbf_at(&mut file, 10, Some(45)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "superbox of 46 bytes does not fit in 45 bytes"
+    );
+    assert_eq!(file.into_inner(), vec![0xaa_u8; 100]);
+
+    // Too little space left over for a free box.
+    let mut file = Cursor::new(vec![0xaa_u8; 100]);
+    let err = sbox.write_jumbf_at(&mut file, 10, Some(50)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "4 bytes left after superbox is too small for a free box"
+    );
+    assert_eq!(file.into_inner(), vec![0xaa_u8; 100]);
+}
+
+#[test]
+fn alignment() {
+    let expected_jumbf = hex!(
+        "00000071" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001b" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "6100" // label = "a"
+            // ---
+            "00000015" // box size
+            "66726565" // box type = 'free'
+            "00000000000000000000000000" // padding
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload (offset 64)
+            // ---
+            "0000000c" // box size
+            "66726565" // box type = 'free'
+            "00000000" // padding
+            // ---
+            "00000021" // box size (offset 80)
+            "6a756d62" // box type = 'jumb'
+                "00000019" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "00" // toggles
+    );
+
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("a")
+        .set_alignment(16)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"data"))
+        .add_child_box(SuperBoxBuilder::new(&[0u8; 16]));
+
+    assert_eq!(jumbf_size(&sbox).unwrap(), expected_jumbf.len());
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(*jumbf.into_inner(), expected_jumbf);
+
+    // Alignments of 0 and 1 are ignored.
+    for alignment in [0, 1] {
+        let aligned = SuperBoxBuilder::new(&[0u8; 16])
+            .set_alignment(alignment)
+            .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"data"));
+        assert_eq!(jumbf_size(&aligned).unwrap(), 45);
+    }
+}
+
+#[test]
+fn alignment_with_computed_hash() {
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_alignment(32)
+        .set_computed_hash(XorDigest)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"));
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    let jumbf = jumbf.into_inner();
+    assert_eq!(jumbf.len(), jumbf_size(&sbox).unwrap());
+
+    // The hash covers the padding, since it is part of the payload.
+    #[cfg(feature = "parser")]
+    {
+        let (_, parsed) = crate::parser::SuperBox::from_slice(&jumbf).unwrap();
+        assert_eq!(parsed.child_boxes.len(), 2);
+        assert_eq!(parsed.verify_hash(&XorDigest), Some(true));
+    }
+}
+
+#[test]
+fn nested_from_closure() {
+    let claim = SuperBoxBuilder::new(&[2u8; 16])
+        .set_label("claim")
+        .add_child_box(DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, b"{}"));
+    let signature = SuperBoxBuilder::new(&[3u8; 16])
+        .set_label("signature")
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"sig"));
+    let manifest = SuperBoxBuilder::new(&[1u8; 16])
+        .set_label("manifest")
+        .add_child_box(claim)
+        .add_child_box(signature);
+    let expected = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("store")
+        .add_child_box(manifest);
+
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("store")
+        .add_child_super_box(&[1u8; 16], "manifest", |b| {
+            b.add_child_super_box(&[2u8; 16], "claim", |b| {
+                b.add_child_box(DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, b"{}"))
+            })
+            .add_child_super_box(&[3u8; 16], "signature", |b| {
+                b.add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"sig"))
+            })
+        });
+
+    assert!(sbox.find("manifest/signature").is_some());
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    let mut expected_jumbf = Cursor::new(Vec::<u8>::new());
+    expected.write_jumbf(&mut expected_jumbf).unwrap();
+
+    assert_eq!(jumbf.into_inner(), expected_jumbf.into_inner());
+}
+
+#[test]
+fn boxed_child_boxes() {
+    // A user-defined box type which borrows its payload.
+    struct Reversed<'a>(&'a [u8]);
+
+    impl<'a> ToBox for Reversed<'a> {
+        fn box_type(&self) -> BoxType {
+            RANDOM_BOX_TYPE
+        }
+
+        fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> std::io::Result<()> {
+            let reversed: Vec<u8> = self.0.iter().rev().copied().collect();
+            to_stream.write_all(&reversed)
+        }
+    }
+
+    let data = b"DCBA".to_vec();
+
+    let children: Vec<Box<dyn ToBox + '_>> = vec![
+        Box::new(Reversed(&data)),
+        Box::new(DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, &data)),
+        Box::new(SuperBoxBuilder::new(&[1u8; 16]).set_label("child")),
+    ];
+
+    let sbox = children
+        .into_iter()
+        .fold(SuperBoxBuilder::new(&[0u8; 16]), |sbox, child| {
+            sbox.add_boxed_child_box(child)
+        })
+        .add_child_box(
+            Box::new(DataBoxBuilder::from_owned(JSON_BOX_TYPE, b"{}".to_vec())) as Box<dyn ToBox>,
+        );
+
+    let expected = SuperBoxBuilder::new(&[0u8; 16])
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"ABCD"))
+        .add_child_box(DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, b"DCBA"))
+        .add_child_box(SuperBoxBuilder::new(&[1u8; 16]).set_label("child"))
+        .add_child_box(DataBoxBuilder::from_borrowed(JSON_BOX_TYPE, b"{}"));
+
+    assert_eq!(jumbf_size(&sbox).unwrap(), jumbf_size(&expected).unwrap());
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    let mut expected_jumbf = Cursor::new(Vec::<u8>::new());
+    expected.write_jumbf(&mut expected_jumbf).unwrap();
+
+    assert_eq!(jumbf.into_inner(), expected_jumbf.into_inner());
+
+    // Boxed superboxes can't be found.
+    assert!(sbox.find("child").is_none());
+}
+
+#[test]
+fn default_and_with_uuid_and_label() {
+    let write = |sbox: &SuperBoxBuilder| {
+        let mut jumbf = Cursor::new(Vec::<u8>::new());
+        sbox.write_jumbf(&mut jumbf).unwrap();
+        jumbf.into_inner()
+    };
+
+    assert_eq!(
+        write(&SuperBoxBuilder::default()),
+        write(&SuperBoxBuilder::new(&[0u8; 16]))
+    );
+
+    let sbox = SuperBoxBuilder::with_uuid_and_label(&[1u8; 16], "abc");
+    assert_eq!(sbox.toggles(), Toggles::REQUESTABLE | Toggles::HAS_LABEL);
+    assert_eq!(
+        write(&sbox),
+        write(&SuperBoxBuilder::new(&[1u8; 16]).set_label("abc"))
+    );
+}
+
+#[test]
+fn plan() {
+    let leaf = |tbox, size| BoxPlan {
+        tbox,
+        label: None,
+        size,
+        max_size: None,
+        children: vec![],
+    };
+
+    let borrowed = SuperBoxBuilder::with_uuid_and_label(&[0u8; 16], "borrowed");
+
+    let sbox = SuperBoxBuilder::with_uuid_and_label(&[0u8; 16], "manifest")
+        .set_alignment(16)
+        .set_computed_hash(XorDigest)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"data"))
+        .add_child_super_box(&[0u8; 16], "nested", |b| {
+            b.set_max_size(40)
+                .add_child_box(DataBoxBuilder::from_owned(JSON_BOX_TYPE, vec![b' '; 40]))
+        })
+        .add_borrowed_child_box(&borrowed);
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    let err = sbox.write_jumbf(&mut jumbf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let plan = sbox.plan().unwrap();
+    assert!(plan.exceeds_max_size());
+
+    let summary: Vec<_> = plan
+        .children
+        .iter()
+        .map(|c| (c.tbox, c.label.as_deref(), c.size, c.max_size))
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (BoxType(*b"jumd"), None, 66, None),
+            (BoxType(*b"free"), None, 14, None),
+            (RANDOM_BOX_TYPE, None, 12, None),
+            (BoxType(*b"free"), None, 12, None),
+            (BoxType(*b"jumb"), Some("nested"), 88, Some(40)),
+            (BoxType(*b"free"), None, 8, None),
+            (BoxType(*b"jumb"), None, 42, None),
+        ]
+    );
+
+    let nested = &plan.children[4];
+    assert!(nested.exceeds_max_size());
+    assert_eq!(nested.children.len(), 2);
+    assert_eq!(nested.children[1], leaf(JSON_BOX_TYPE, 48));
+
+    assert_eq!(plan.size, 8 + summary.iter().map(|c| c.2).sum::<usize>());
+
+    // Once the size limit is met, the plan matches what is written.
+    let sbox = SuperBoxBuilder::with_uuid_and_label(&[0u8; 16], "manifest")
+        .set_alignment(16)
+        .add_child_box(DataBoxBuilder::from_borrowed(RANDOM_BOX_TYPE, b"data"))
+        .add_child_super_box(&[0u8; 16], "nested", |b| {
+            b.set_max_size(100)
+                .add_child_box(DataBoxBuilder::from_owned(JSON_BOX_TYPE, vec![b' '; 40]))
+        });
+
+    let plan = sbox.plan().unwrap();
+    assert!(!plan.exceeds_max_size());
+    assert_eq!(plan.size, jumbf_size(&sbox).unwrap());
+    assert_eq!(plan.children[2], leaf(RANDOM_BOX_TYPE, 12));
+}
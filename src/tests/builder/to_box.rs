@@ -0,0 +1,268 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    io::{Cursor, IoSlice, Result, Seek, SeekFrom, Write},
+};
+
+#[cfg(feature = "parser")]
+use crate::parser::SuperBox;
+use crate::{
+    builder::{
+        ChildOrder, DataBoxBuilder, PlaceholderDataBox, SuperBoxBuilder, ToBox, WriteAndSeek,
+    },
+    BoxType,
+};
+
+// Records the number of write calls, optionally accepting no more than
+// `max_write` bytes per call.
+struct RecordingWriter {
+    inner: Cursor<Vec<u8>>,
+    calls: usize,
+    max_write: usize,
+}
+
+impl RecordingWriter {
+    fn new(max_write: usize) -> Self {
+        Self {
+            inner: Cursor::new(vec![]),
+            calls: 0,
+            max_write,
+        }
+    }
+}
+
+impl Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.write_vectored(&[IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        self.calls += 1;
+
+        let mut written = 0;
+        for buf in bufs {
+            let len = buf.len().min(self.max_write - written);
+            self.inner.write_all(&buf[..len])?;
+            written += len;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for RecordingWriter {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+fn sample_super_box<'a>(placeholder: &'a PlaceholderDataBox) -> SuperBoxBuilder<'a> {
+    SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("test.superbox")
+        .add_child_box(DataBoxBuilder::from_segments(
+            BoxType(*b"abcd"),
+            vec![
+                Cow::Borrowed(b"head"),
+                Cow::Borrowed(b""),
+                Cow::Owned(b"body".to_vec()),
+                Cow::Borrowed(b"foot"),
+            ],
+        ))
+        .add_borrowed_child_box(placeholder)
+}
+
+#[test]
+fn vectored_writes() {
+    let placeholder = PlaceholderDataBox::new(BoxType(*b"sigx"), 8);
+    let sbox = sample_super_box(&placeholder);
+
+    let mut expected = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut expected).unwrap();
+    let expected = expected.into_inner();
+
+    let mut writer = RecordingWriter::new(usize::MAX);
+    sbox.write_jumbf(&mut writer).unwrap();
+    assert_eq!(writer.inner.get_ref(), &expected);
+
+    // Each box header is written on its own, but the fields of the
+    // description box and all of the data box's segments are each written
+    // in a single call.
+    assert_eq!(writer.calls, 7);
+
+    #[cfg(feature = "parser")]
+    {
+        let (rem, parsed) = SuperBox::from_slice(&expected).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(parsed.child_boxes.len(), 2);
+    }
+
+    assert_eq!(placeholder.offset(), Some(expected.len() as u64 - 8));
+}
+
+#[test]
+fn partial_writes() {
+    let placeholder = PlaceholderDataBox::new(BoxType(*b"sigx"), 8);
+    let sbox = sample_super_box(&placeholder);
+
+    let mut expected = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut expected).unwrap();
+    let expected = expected.into_inner();
+
+    for max_write in 1..=20 {
+        let mut writer = RecordingWriter::new(max_write);
+        sbox.write_jumbf(&mut writer).unwrap();
+        assert_eq!(writer.inner.get_ref(), &expected);
+        assert_eq!(placeholder.offset(), Some(expected.len() as u64 - 8));
+    }
+}
+
+// Generates its payload in several pieces without knowing its size in
+// advance, and counts the number of times the payload is generated.
+struct Generator {
+    chunks: Vec<&'static [u8]>,
+    generated: Cell<usize>,
+}
+
+impl Generator {
+    fn new(chunks: Vec<&'static [u8]>) -> Self {
+        Self {
+            chunks,
+            generated: Cell::new(0),
+        }
+    }
+}
+
+impl ToBox for Generator {
+    fn box_type(&self) -> BoxType {
+        BoxType(*b"abcd")
+    }
+
+    fn payload_size_known(&self) -> bool {
+        false
+    }
+
+    fn write_payload(&self, to_stream: &mut dyn WriteAndSeek) -> Result<()> {
+        self.generated.set(self.generated.get() + 1);
+        for chunk in &self.chunks {
+            to_stream.write_all(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn unknown_payload_size() {
+    let generator = Generator::new(vec![b"gene", b"rated"]);
+
+    let inner = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("inner")
+        .add_borrowed_child_box(&generator);
+
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("outer")
+        .add_borrowed_child_box(&inner)
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"known"));
+
+    let expected_sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("outer")
+        .add_child_box(
+            SuperBoxBuilder::new(&[0u8; 16])
+                .set_label("inner")
+                .add_child_box(DataBoxBuilder::from_borrowed(
+                    BoxType(*b"abcd"),
+                    b"generated",
+                )),
+        )
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"known"));
+
+    assert!(!sbox.payload_size_known());
+    assert!(expected_sbox.payload_size_known());
+
+    let mut expected = Cursor::new(Vec::<u8>::new());
+    expected_sbox.write_jumbf(&mut expected).unwrap();
+    let expected = expected.into_inner();
+
+    let mut jumbf = Cursor::new(b"prefix".to_vec());
+    jumbf.set_position(6);
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    assert_eq!(jumbf.position(), 6 + expected.len() as u64);
+    assert_eq!(jumbf.into_inner()[6..], expected);
+    assert_eq!(generator.generated.get(), 1);
+}
+
+#[test]
+fn unknown_payload_size_with_max_size() {
+    let generator = Generator::new(vec![b"generated"]);
+
+    let sbox = SuperBoxBuilder::new(&[0u8; 16])
+        .set_max_size(100)
+        .add_borrowed_child_box(&generator);
+
+    // The size must be computed before writing to enforce the limit.
+    assert!(sbox.payload_size_known());
+
+    // The limit is checked again when the payload is written, in case a
+    // parent superbox sorts its children and skips the size calculation.
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    assert_eq!(generator.generated.get(), 3);
+
+    #[cfg(feature = "parser")]
+    {
+        let (rem, parsed) = SuperBox::from_slice(jumbf.get_ref()).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(parsed.child_boxes.len(), 1);
+    }
+}
+
+#[test]
+fn nested_canonical_child_order() {
+    let generator = Generator::new(vec![b"gene", b"rated"]);
+
+    // Wrap `leaf` in ten levels of superboxes which sort their children.
+    fn nest<B: ToBox>(leaf: &B) -> SuperBoxBuilder<'_> {
+        let mut sbox = SuperBoxBuilder::new(&[0u8; 16])
+            .set_child_order(ChildOrder::Canonical)
+            .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"known"))
+            .add_borrowed_child_box(leaf);
+
+        for _ in 1..10 {
+            sbox = SuperBoxBuilder::new(&[0u8; 16])
+                .set_child_order(ChildOrder::Canonical)
+                .add_boxed_child_box(Box::new(sbox))
+                .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"known"));
+        }
+
+        sbox
+    }
+
+    let sbox = nest(&generator);
+    let data_box = DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"generated");
+    let expected_sbox = nest(&data_box);
+
+    let mut expected = Cursor::new(Vec::<u8>::new());
+    expected_sbox.write_jumbf(&mut expected).unwrap();
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+
+    assert_eq!(jumbf.into_inner(), expected.into_inner());
+    assert_eq!(generator.generated.get(), 1);
+}
@@ -0,0 +1,47 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::HashSet;
+
+use crate::content_type::{self, ContentType, KNOWN_CONTENT_TYPES};
+
+#[test]
+fn from_uuid() {
+    assert_eq!(
+        ContentType::from_uuid(&content_type::C2PA_CLAIM.uuid),
+        Some(&content_type::C2PA_CLAIM)
+    );
+
+    let mut uuid = content_type::CBOR.uuid;
+    uuid[15] ^= 1;
+    assert_eq!(ContentType::from_uuid(&uuid), None);
+}
+
+#[test]
+fn iso_uuids() {
+    assert_eq!(
+        content_type::JSON.uuid,
+        hex_literal::hex!("6a736f6e 0011 0010 8000 00aa00389b71")
+    );
+}
+
+#[test]
+fn unique_uuids() {
+    let uuids: HashSet<[u8; 16]> = KNOWN_CONTENT_TYPES.iter().map(|ct| ct.uuid).collect();
+    assert_eq!(uuids.len(), KNOWN_CONTENT_TYPES.len());
+}
+
+#[test]
+fn impl_display() {
+    assert_eq!(content_type::EMBEDDED_FILE.to_string(), "embedded file");
+}
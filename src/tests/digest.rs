@@ -0,0 +1,53 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::digest::Digest;
+
+// Trivial (and cryptographically useless) digest for use in tests: XOR
+// each input byte into a 32-byte result.
+pub(crate) struct XorDigest;
+
+impl Digest for XorDigest {
+    fn algorithm(&self) -> &str {
+        "xor"
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        let mut result = vec![0u8; 32];
+        for (i, b) in data.iter().enumerate() {
+            result[i % 32] ^= b;
+        }
+        result
+    }
+}
+
+#[test]
+fn xor_digest() {
+    let hash = XorDigest.digest(b"abc");
+    assert_eq!(hash.len(), 32);
+    assert_eq!(&hash[0..4], b"abc\0");
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn sha256() {
+    use hex_literal::hex;
+
+    use crate::digest::Sha256;
+
+    assert_eq!(Sha256.algorithm(), "sha256");
+    assert_eq!(
+        Sha256.digest(b"abc"),
+        hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+}
@@ -21,8 +21,25 @@
 mod box_type;
 mod builder;
 
+mod content_type;
+
 #[cfg(feature = "parser")]
 mod debug;
 
+mod digest;
+
+mod model;
+
 #[cfg(feature = "parser")]
 mod parser;
+
+#[cfg(all(feature = "proptest", feature = "parser"))]
+mod strategy;
+
+#[cfg(feature = "test-utils")]
+mod test_utils;
+
+#[cfg(feature = "test-utils")]
+mod testkit;
+
+mod toggles;
@@ -0,0 +1,196 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::Cursor;
+
+use hex_literal::hex;
+
+use crate::{
+    builder::{DataBoxBuilder, SuperBoxBuilder},
+    model::{to_jumbf, ChildBoxModel, DataBoxModel, SuperBoxModel},
+    BoxType,
+};
+
+#[test]
+fn matches_builder_output() {
+    let model = SuperBoxModel {
+        uuid: hex!("6332706100110010800000aa00389b71"),
+        label: Some("c2pa".to_owned()),
+        requestable: true,
+        id: Some(42),
+        hash: None,
+        private: Some(DataBoxModel {
+            tbox: BoxType(*b"c2sh"),
+            data: b"salt".to_vec(),
+        }),
+        child_boxes: vec![
+            ChildBoxModel::SuperBox(SuperBoxModel {
+                label: Some("nested".to_owned()),
+                requestable: true,
+                ..Default::default()
+            }),
+            ChildBoxModel::DataBox(DataBoxModel {
+                tbox: BoxType(*b"json"),
+                data: b"{}".to_vec(),
+            }),
+        ],
+    };
+
+    let mut from_model = Cursor::new(Vec::<u8>::new());
+    model.write_jumbf(&mut from_model).unwrap();
+
+    let builder = SuperBoxBuilder::new(&hex!("6332706100110010800000aa00389b71"))
+        .set_label("c2pa")
+        .set_id(42)
+        .set_private_box(DataBoxBuilder::from_borrowed(BoxType(*b"c2sh"), b"salt"))
+        .add_child_box(SuperBoxBuilder::new(&[0; 16]).set_label("nested"))
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"json"), b"{}"));
+
+    let mut from_builder = Cursor::new(Vec::<u8>::new());
+    builder.write_jumbf(&mut from_builder).unwrap();
+
+    let from_model = from_model.into_inner();
+    assert_eq!(from_model, from_builder.into_inner());
+    assert_eq!(to_jumbf(&model).unwrap(), from_model);
+
+    let mut via_builder = Cursor::new(Vec::<u8>::new());
+    SuperBoxBuilder::from_model(&model)
+        .write_jumbf(&mut via_builder)
+        .unwrap();
+    assert_eq!(via_builder.into_inner(), from_model);
+}
+
+#[test]
+fn from_model_then_extend() {
+    let model = SuperBoxModel {
+        label: Some("example".to_owned()),
+        requestable: false,
+        ..Default::default()
+    };
+
+    let extra = DataBoxModel {
+        tbox: BoxType(*b"json"),
+        data: b"{}".to_vec(),
+    };
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    SuperBoxBuilder::from_model(&model)
+        .set_id(7)
+        .add_borrowed_child_box(&extra)
+        .write_jumbf(&mut jumbf)
+        .unwrap();
+
+    let mut expected = model.clone();
+    expected.id = Some(7);
+    expected.child_boxes.push(ChildBoxModel::DataBox(extra));
+
+    assert_eq!(jumbf.into_inner(), to_jumbf(&expected).unwrap());
+}
+
+#[cfg(all(feature = "arbitrary", feature = "parser"))]
+mod arbitrary {
+    use std::io::Cursor;
+
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::{model::SuperBoxModel, parser::SuperBox};
+
+    #[test]
+    fn round_trip() {
+        // Simple deterministic byte generator so the test is repeatable.
+        let mut seed = 0x1234_5678u32;
+        let bytes: Vec<u8> = (0..65536)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect();
+
+        let mut u = Unstructured::new(&bytes);
+
+        for _ in 0..20 {
+            let model = SuperBoxModel::arbitrary(&mut u).unwrap();
+
+            let mut jumbf = Cursor::new(Vec::<u8>::new());
+            model.write_jumbf(&mut jumbf).unwrap();
+            let jumbf = jumbf.into_inner();
+
+            let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(SuperBoxModel::from(&sbox), model);
+        }
+    }
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn from_parsed_c2pa_manifest() {
+    use crate::{model::BoxTree, parser::SuperBox};
+
+    let jumbf = include_bytes!("fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let model = SuperBoxModel::from(&sbox);
+    assert_eq!(model.label.as_deref(), Some("c2pa"));
+    assert_eq!(model.child_boxes.len(), 1);
+
+    let mut written = Cursor::new(Vec::<u8>::new());
+    model.write_jumbf(&mut written).unwrap();
+    assert_eq!(written.into_inner(), jumbf);
+
+    // A sequence of two top-level boxes.
+    let mut two = jumbf.to_vec();
+    two.extend_from_slice(&hex!("0000000a" "61626364" "7879"));
+
+    let tree = BoxTree::from_slice(&two).unwrap();
+    assert_eq!(
+        tree.boxes,
+        vec![
+            ChildBoxModel::SuperBox(model),
+            ChildBoxModel::DataBox(DataBoxModel {
+                tbox: BoxType(*b"abcd"),
+                data: b"xy".to_vec(),
+            }),
+        ]
+    );
+
+    let mut written = Cursor::new(Vec::<u8>::new());
+    tree.write_jumbf(&mut written).unwrap();
+    assert_eq!(written.into_inner(), two);
+}
+
+#[test]
+fn data_box_model_into_builder() {
+    let model = DataBoxModel {
+        tbox: BoxType(*b"json"),
+        data: b"{}".to_vec(),
+    };
+
+    let sbox = SuperBoxBuilder::new(&[0; 16])
+        .add_child_box(DataBoxBuilder::from(model.clone()))
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"json"), b"{}"));
+
+    let mut from_model = Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut from_model).unwrap();
+    let from_model = from_model.into_inner();
+
+    let builder = DataBoxBuilder::from(&model);
+    let mut from_borrowed = Cursor::new(Vec::<u8>::new());
+    crate::builder::ToBox::write_payload(&builder, &mut from_borrowed).unwrap();
+    assert_eq!(from_borrowed.into_inner(), b"{}");
+
+    assert_eq!(
+        &from_model[from_model.len() - 10..],
+        &hex!("0000000a" "6a736f6e" "7b7d")
+    );
+}
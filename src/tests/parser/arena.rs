@@ -0,0 +1,108 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use bumpalo::Bump;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::parser::{
+    arena::{ArenaChildBox, ArenaSuperBox},
+    ChildBox, Error, ParseOptions, SuperBox,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+fn assert_same_tree(arena: &ArenaSuperBox, heap: &SuperBox) {
+    assert_eq!(arena.desc, heap.desc);
+    assert_eq!(arena.original, heap.original);
+    assert_eq!(arena.child_boxes.len(), heap.child_boxes.len());
+
+    for (a, h) in arena.child_boxes.iter().zip(heap.child_boxes.iter()) {
+        match (a, h) {
+            (ArenaChildBox::SuperBox(a), ChildBox::SuperBox(h)) => assert_same_tree(a, h),
+            (ArenaChildBox::DataBox(a), ChildBox::DataBox(h)) => assert_eq!(a, h),
+            _ => panic!("child box kinds differ"),
+        }
+    }
+}
+
+#[test]
+fn matches_heap_tree() {
+    let bump = Bump::new();
+
+    let (rem, arena) = ArenaSuperBox::from_slice_in(C2PA_MANIFEST_STORE, &bump).unwrap();
+    assert!(rem.is_empty());
+
+    let (_, heap) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_same_tree(arena, &heap);
+
+    let claim = arena
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim")
+        .unwrap();
+    assert_eq!(claim.data_box().unwrap().original.len(), 640);
+
+    assert!(arena.find_by_label("c2pa.claim").is_none());
+    assert!(arena.data_box().is_none());
+}
+
+#[test]
+fn with_depth_limit() {
+    let bump = Bump::new();
+    let options = ParseOptions::default().set_depth_limit(1);
+
+    let (_, arena) =
+        ArenaSuperBox::from_slice_with_options_in(C2PA_MANIFEST_STORE, &options, &bump).unwrap();
+
+    let (_, heap) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert_same_tree(arena, &heap);
+}
+
+#[test]
+fn error_wrong_box_type() {
+    let bump = Bump::new();
+    let jumbf = hex_literal::hex!(
+        "00000008" // box size
+        "6a756d64" // box type = 'jumd'
+    );
+
+    assert_eq!(
+        ArenaSuperBox::from_slice_in(&jumbf, &bump).unwrap_err(),
+        nom::Err::Error(Error::InvalidSuperBoxType(crate::BoxType(*b"jumd")))
+    );
+}
+
+#[test]
+fn demote_malformed_super_boxes() {
+    let bump = Bump::new();
+    let jumbf = hex_literal::hex!(
+        "0000003b" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "6a756d62" // box type = 'jumb'
+            "00000004" // (INVALID) child box
+    );
+
+    assert!(ArenaSuperBox::from_slice_in(&jumbf, &bump).is_err());
+
+    let options = ParseOptions::default().set_demote_malformed_super_boxes(true);
+    let (_, arena) = ArenaSuperBox::from_slice_with_options_in(&jumbf, &options, &bump).unwrap();
+
+    let (_, heap) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert_same_tree(arena, &heap);
+    assert!(matches!(arena.child_boxes[0], ArenaChildBox::DataBox(_)));
+}
@@ -0,0 +1,132 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::Cursor;
+
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    builder::{DataBoxBuilder, SuperBoxBuilder},
+    content_type,
+    parser::{
+        c2pa::{assertions, check_references, ReferenceIssueKind},
+        SuperBox,
+    },
+    BoxType,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn c2pa_assertions() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let store = sbox
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions")
+        .unwrap();
+
+    let found: Vec<_> = assertions(store).collect();
+    assert_eq!(found.len(), store.child_boxes.len());
+
+    for assertion in &found {
+        assert_eq!(Some(assertion.label), assertion.sbox.desc.label);
+        assert_eq!(assertion.content_type, assertion.sbox.desc.content_type());
+    }
+
+    let creative_work = found
+        .iter()
+        .find(|assertion| assertion.label == "stds.schema-org.CreativeWork")
+        .unwrap();
+    assert_eq!(creative_work.content_type, Some(&content_type::JSON));
+    assert_eq!(
+        creative_work.payload.map(|dbox| dbox.tbox),
+        Some(BoxType(*b"json"))
+    );
+
+    // Labeled child superboxes of any superbox are reported. Here, the only
+    // child of the manifest store is the manifest itself.
+    assert_eq!(assertions(&sbox).count(), 1);
+}
+
+#[test]
+fn check_references_in_cbor_claim() {
+    let (_, mut sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert!(check_references(&sbox).is_empty());
+
+    let assertions = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions";
+    sbox.rename_label(&format!("{assertions}/c2pa.hash.data"), "c2pa.actions")
+        .unwrap();
+
+    let issues = check_references(&sbox);
+    let found: Vec<_> = issues.iter().map(|issue| (issue.uri, issue.kind)).collect();
+    assert_eq!(
+        found,
+        [
+            (
+                "self#jumbf=c2pa.assertions/c2pa.actions",
+                ReferenceIssueKind::Ambiguous
+            ),
+            (
+                "self#jumbf=c2pa.assertions/c2pa.hash.data",
+                ReferenceIssueKind::Dangling
+            ),
+        ]
+    );
+
+    for issue in &issues {
+        assert_eq!(
+            issue.manifest.desc.label,
+            Some("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9")
+        );
+        assert!(issue.claim.data[issue.offset..].starts_with(issue.uri.as_bytes()));
+    }
+}
+
+#[test]
+fn check_references_in_json_claim() {
+    let claim = br#"{"a":"self#jumbf=/c2pa/m/c2pa.assertions/a?hl=xx","b":"self#jumbf=c2pa.assertions/b","c":"self#jumbf=/other/m"}"#;
+
+    let store = SuperBoxBuilder::new(&[0u8; 16])
+        .set_label("c2pa")
+        .add_child_box(
+            SuperBoxBuilder::new(&[0u8; 16])
+                .set_label("m")
+                .add_child_box(
+                    SuperBoxBuilder::new(&[0u8; 16])
+                        .set_label("c2pa.assertions")
+                        .add_child_box(SuperBoxBuilder::new(&[0u8; 16]).set_label("a")),
+                )
+                .add_child_box(
+                    SuperBoxBuilder::new(&[0u8; 16])
+                        .set_label("c2pa.claim")
+                        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"json"), claim)),
+                ),
+        );
+
+    let mut jumbf = Cursor::new(Vec::<u8>::new());
+    store.write_jumbf(&mut jumbf).unwrap();
+
+    let jumbf = jumbf.into_inner();
+    let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+
+    let found: Vec<_> = check_references(&sbox)
+        .iter()
+        .map(|issue| (issue.uri, issue.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("self#jumbf=c2pa.assertions/b", ReferenceIssueKind::Dangling),
+            ("self#jumbf=/other/m", ReferenceIssueKind::Dangling),
+        ]
+    );
+}
@@ -0,0 +1,149 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::parser::conformance::{self, ConformanceReport, Finding, Rule};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn c2pa_manifest_conforms() {
+    let report = conformance::check(C2PA_MANIFEST_STORE);
+    assert_eq!(report, ConformanceReport::default());
+    assert!(report.is_conforming());
+    assert!(report.results().all(|(_, passed)| passed));
+}
+
+#[test]
+fn multiple_findings() {
+    let jumbf = hex!(
+        "00000058" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000008" // box size
+            "6a736f6e" // box type = 'json'
+            "0000001b" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "81" // toggles = reserved | requestable
+            "abcd" // trailing bytes
+            "00000025" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "06" // toggles = has label | has ID
+                "6c00" // label = "l"
+                "0102" // ID (truncated)
+            "00000004" // box size (reserved)
+            "66726565" // box type = 'free'
+    );
+
+    let report = conformance::check(&jumbf);
+
+    assert_eq!(
+        report.findings,
+        [
+            Finding {
+                rule: Rule::DescriptionBoxFirst,
+                offset: 8,
+                detail: "first child box has type b\"json\"".to_owned(),
+            },
+            Finding {
+                rule: Rule::DescriptionBoxFirst,
+                offset: 16,
+                detail: "description box is child box 1".to_owned(),
+            },
+            Finding {
+                rule: Rule::ReservedToggleBits,
+                offset: 40,
+                detail: "reserved toggle bits 0x80 are set".to_owned(),
+            },
+            Finding {
+                rule: Rule::RequestableHasLabel,
+                offset: 16,
+                detail: "requestable description box has no label".to_owned(),
+            },
+            Finding {
+                rule: Rule::DescriptionBoxFields,
+                offset: 41,
+                detail: "2 bytes follow the fields indicated by the toggles".to_owned(),
+            },
+            Finding {
+                rule: Rule::DescriptionBoxFields,
+                offset: 78,
+                detail: "ID requires 4 bytes, but 2 remain".to_owned(),
+            },
+            Finding {
+                rule: Rule::ReservedBoxLength,
+                offset: 80,
+                detail: "Box length value 4 is reserved".to_owned(),
+            },
+        ]
+    );
+
+    assert!(!report.passed(Rule::DescriptionBoxFirst));
+    assert!(report.passed(Rule::SingleDescriptionBox));
+    assert!(!report.passed(Rule::RequestableHasLabel));
+    assert!(report.passed(Rule::SuperBoxType));
+    assert!(report.passed(Rule::BoxLength));
+
+    assert_eq!(
+        report.to_string().lines().take(5).collect::<Vec<_>>(),
+        [
+            "PASS box-length (ISO/IEC 15444-1, Annex I, box definition (LBox, XLBox))",
+            "FAIL reserved-box-length (ISO/IEC 15444-1, Annex I, box definition (LBox, XLBox))",
+            "    offset 80: Box length value 4 is reserved",
+            "PASS superbox-type (ISO/IEC 19566-5, JUMBF superbox definition)",
+            "FAIL description-box-first (ISO/IEC 19566-5, JUMBF superbox definition)",
+        ]
+    );
+}
+
+#[test]
+fn box_lengths() {
+    let jumbf = hex!(
+        "00000031" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000001" // box size (extended)
+            "66726565" // box type = 'free'
+            "000000000000000f" // extended box size (reserved)
+    );
+
+    let report = conformance::check(&jumbf);
+    assert!(report.passed(Rule::BoxLength));
+    assert_eq!(
+        report.findings,
+        [Finding {
+            rule: Rule::ReservedBoxLength,
+            offset: 33,
+            detail: "Box length value 15 is reserved".to_owned(),
+        }]
+    );
+
+    // Same superbox, but the child claims more space than is available.
+    let mut jumbf = jumbf;
+    jumbf[33..49].copy_from_slice(&hex!("00000020" "66726565" "0000000000000000"));
+
+    let report = conformance::check(&jumbf);
+    assert!(report.passed(Rule::ReservedBoxLength));
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].rule, Rule::BoxLength);
+    assert_eq!(report.findings[0].offset, 33);
+}
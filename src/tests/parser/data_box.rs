@@ -12,12 +12,12 @@
 // each license.
 
 use hex_literal::hex;
-use nom::{error::ErrorKind, Needed};
 use pretty_assertions_sorted::assert_eq;
 
 use crate::{
     box_type::DESCRIPTION_BOX_TYPE,
-    parser::{DataBox, Error},
+    parser::{DataBox, Error, SuperBox},
+    BoxType,
 };
 
 #[test]
@@ -56,7 +56,10 @@ fn error_incomplete_box_length() {
 
     assert_eq!(
         DataBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::NomError(ErrorKind::Eof))
+        nom::Err::Error(Error::Truncated {
+            needed: 5,
+            offset: 0
+        })
     );
 }
 
@@ -69,7 +72,10 @@ fn error_incomplete_box_type() {
 
     assert_eq!(
         DataBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::Incomplete(Needed::new(4)))
+        nom::Err::Error(Error::Truncated {
+            needed: 1,
+            offset: 0
+        })
     );
 }
 
@@ -139,6 +145,59 @@ fn read_xlbox_size() {
     );
 }
 
+#[test]
+fn geometry() {
+    let jumbf = hex!(
+        "00000026" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label
+    );
+
+    let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+    assert_eq!(boxx.header_len(), 8);
+    assert_eq!(boxx.payload_len(), 30);
+    assert!(!boxx.uses_extended_length());
+}
+
+#[test]
+fn geometry_to_eof() {
+    let jumbf = hex!(
+        "00000000" // box size (read to EOF)
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label
+    );
+
+    let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+    assert_eq!(boxx.header_len(), 8);
+    assert_eq!(boxx.payload_len(), 30);
+    assert!(!boxx.uses_extended_length());
+}
+
+#[test]
+fn geometry_xlbox_size() {
+    let jumbf = hex!(
+        "00000001" // box size (contained in xlbox)
+        "6a756d64" // box type = 'jumd'
+        "000000000000002e" // XLbox (extra long box size)
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label
+    );
+
+    let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+    assert_eq!(boxx.header_len(), 16);
+    assert_eq!(boxx.payload_len(), 30);
+    assert!(boxx.uses_extended_length());
+    assert_eq!(
+        &boxx.original[boxx.header_len()..boxx.header_len() + boxx.payload_len()],
+        boxx.data
+    );
+}
+
 #[test]
 fn error_xlbox_size_too_small() {
     let jumbf = hex!(
@@ -168,7 +227,10 @@ fn error_incorrect_length() {
 
     assert_eq!(
         DataBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::Incomplete(Needed::new(30)))
+        nom::Err::Error(Error::Truncated {
+            needed: 13,
+            offset: 0
+        })
     );
 }
 
@@ -339,3 +401,194 @@ mod offset_within_superbox {
         assert!(claim_dbox.offset_within_superbox(sig_sbox).is_none());
     }
 }
+
+#[test]
+fn as_utf8() {
+    let jumbf = hex!(
+        "00000012" // box size
+        "6a736f6e" // box type = 'json'
+        "efbbbf" // byte order mark
+        "7b2261223a317d" // payload = {"a":1}
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    assert_eq!(dbox.as_utf8(true).unwrap(), "{\"a\":1}");
+    assert_eq!(dbox.as_utf8(false).unwrap(), "\u{feff}{\"a\":1}");
+
+    let jumbf = hex!(
+        "0000000b" // box size
+        "6a736f6e" // box type = 'json'
+        "61ff62" // payload (not valid UTF-8)
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    assert!(matches!(dbox.as_utf8(true), Err(Error::Utf8Error(_))));
+}
+
+#[test]
+fn sniff_media_type() {
+    let sniff = |payload: &[u8]| {
+        let dbox = DataBox {
+            tbox: BoxType(*b"test"),
+            data: payload,
+            original: &[],
+        };
+        dbox.sniff_media_type()
+    };
+
+    assert_eq!(sniff(&hex!("ffd8ffe000104a464946")), Some("image/jpeg"));
+    assert_eq!(sniff(&hex!("89504e470d0a1a0a0000000d")), Some("image/png"));
+    assert_eq!(sniff(&hex!("d9d9f7a0")), Some("application/cbor"));
+    assert_eq!(sniff(&hex!("a2616101616202")), Some("application/cbor"));
+    assert_eq!(sniff(b"  \n{\"a\": 1}"), Some("application/json"));
+    assert_eq!(sniff(b"\xef\xbb\xbf[1, 2]"), Some("application/json"));
+    assert_eq!(
+        sniff(b"<?xml version=\"1.0\"?><x/>"),
+        Some("application/xml")
+    );
+    assert_eq!(sniff(b"plain text"), None);
+    assert_eq!(sniff(b""), None);
+}
+
+#[test]
+fn sniff_c2pa_payloads() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let claim = sbox
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim")
+        .unwrap();
+    assert_eq!(
+        claim.data_box().unwrap().sniff_media_type(),
+        Some("application/cbor")
+    );
+}
+
+#[test]
+fn error_xlbox_size_huge() {
+    let jumbf = hex!(
+        "00000001" // box size (contained in xlbox)
+        "6a756d64" // box type = 'jumd'
+        "ffffffffffffffff" // XLbox (INCORRECT extra long box size)
+        "00000000000000000000000000000000" // UUID
+    );
+
+    let err = DataBox::from_slice(&jumbf).unwrap_err();
+
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(
+        err,
+        nom::Err::Error(Error::Truncated {
+            needed: usize::MAX - 32,
+            offset: 0
+        })
+    );
+
+    #[cfg(not(target_pointer_width = "64"))]
+    assert_eq!(err, nom::Err::Error(Error::BoxLengthOverflow(u64::MAX)));
+}
+
+#[test]
+fn error_box_size_larger_than_input() {
+    let jumbf = hex!(
+        "ffffffff" // box size (INCORRECT)
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+    );
+
+    assert_eq!(
+        DataBox::from_slice(&jumbf).unwrap_err(),
+        nom::Err::Error(Error::Truncated {
+            needed: 0xffff_ffe7,
+            offset: 0
+        })
+    );
+}
+
+#[test]
+fn try_from_slice() {
+    let jumbf = hex!(
+        "00000010" // box size
+        "6a736f6e" // box type = 'json'
+        "7b2261223a317d" // payload = {"a":1}
+        "0a" // trailing newline
+        "0000" // trailing data
+    );
+
+    let dbox = DataBox::try_from(&jumbf[..16]).unwrap();
+    assert_eq!(dbox.data, b"{\"a\":1}\n");
+
+    assert_eq!(
+        DataBox::try_from(&jumbf[..]).unwrap_err(),
+        Error::TrailingData { offset: 16, len: 2 }
+    );
+
+    assert_eq!(
+        DataBox::try_from(&jumbf[..10]).unwrap_err(),
+        Error::Truncated {
+            needed: 6,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn parse_children() {
+    let jumbf = hex!(
+        "00000035" // box size
+        "74657374" // box type = 'test'
+            "0000000a" // box size
+            "6a736f6e" // box type = 'json'
+            "7b7d" // payload = {}
+            // ---
+            "00000023" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001b" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "6100" // label = "a"
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    let children = dbox.parse_children().unwrap();
+    assert_eq!(children.len(), 2);
+
+    let json = children[0].as_data_box().unwrap();
+    assert_eq!(json.tbox, BoxType(*b"json"));
+    assert_eq!(json.original, &jumbf[8..18]);
+
+    let sbox = children[1].as_super_box().unwrap();
+    assert_eq!(sbox.desc.label, Some("a"));
+    assert_eq!(sbox.original, &jumbf[18..]);
+
+    // A payload which isn't entirely made of boxes is an error.
+    assert!(json.parse_children().is_err());
+}
+
+#[test]
+fn split_uuid_payload() {
+    let jumbf = hex!(
+        "00000018" // box size
+        "75756964" // box type = 'uuid'
+        "00112233445566778899aabbccddeeff" // UUID
+        // (no data)
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    let (uuid, data) = dbox.split_uuid_payload().unwrap();
+    assert_eq!(uuid, hex!("00112233445566778899aabbccddeeff"));
+    assert!(data.is_empty());
+
+    // The data is borrowed from the input.
+    assert_eq!(data.as_ptr(), jumbf[24..].as_ptr());
+
+    let jumbf = hex!(
+        "00000017" // box size
+        "75756964" // box type = 'uuid'
+        "00112233445566778899aabbccddee" // UUID (too short)
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    assert_eq!(dbox.split_uuid_payload(), None);
+}
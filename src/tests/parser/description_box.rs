@@ -12,12 +12,12 @@
 // each license.
 
 use hex_literal::hex;
-use nom::Needed;
 use pretty_assertions_sorted::assert_eq;
 
 use crate::{
-    parser::{DataBox, DescriptionBox, Error},
-    BoxType,
+    content_type,
+    parser::{DataBox, DescriptionBox, Error, ParseOptions, SuperBox},
+    BoxType, Toggles,
 };
 
 #[test]
@@ -125,7 +125,7 @@ fn error_incomplete_id() {
 
     assert_eq!(
         DescriptionBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::NomError(nom::error::ErrorKind::Eof))
+        nom::Err::Error(Error::CorruptHeader { offset: 0 })
     );
 }
 
@@ -232,7 +232,7 @@ fn error_incomplete_uuid() {
 
     assert_eq!(
         DescriptionBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::Incomplete(Needed::new(16)))
+        nom::Err::Error(Error::CorruptHeader { offset: 0 })
     );
 }
 
@@ -278,6 +278,167 @@ fn error_incomplete_hash() {
 
     assert_eq!(
         DescriptionBox::from_slice(&jumbf).unwrap_err(),
-        nom::Err::Error(Error::Incomplete(Needed::new(32)))
+        nom::Err::Error(Error::CorruptHeader { offset: 0 })
     );
 }
+
+#[test]
+fn max_label_len() {
+    let jumbf = hex!(
+        "00000026" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label = "test.descbox"
+    );
+
+    let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+
+    let options = ParseOptions::default().set_max_label_len(12);
+    let (_, dbox) = DescriptionBox::from_box_with_options(boxx.clone(), &options).unwrap();
+    assert_eq!(dbox.label, Some("test.descbox"));
+
+    let options = ParseOptions::default().set_max_label_len(11);
+    assert_eq!(
+        DescriptionBox::from_box_with_options(boxx, &options).unwrap_err(),
+        nom::Err::Error(Error::LabelTooLong(11))
+    );
+}
+
+#[test]
+fn raw_label() {
+    let jumbf = hex!(
+        "0000001e" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "6162ff6400" // label (not valid UTF-8)
+    );
+
+    let (_, boxx) = DataBox::from_slice(&jumbf).unwrap();
+
+    assert!(matches!(
+        DescriptionBox::from_box(boxx.clone()).unwrap_err(),
+        nom::Err::Error(Error::Utf8Error(_))
+    ));
+
+    let options = ParseOptions::default().set_lenient_label_encoding(true);
+    let (rem, mut dbox) = DescriptionBox::from_box_with_options(boxx, &options).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(dbox.label, None);
+    assert_eq!(dbox.raw_label(), Some(&b"ab\xffd"[..]));
+
+    // An unmodified box keeps its raw label when written.
+    let mut written = vec![];
+    dbox.write_to(&mut written).unwrap();
+    assert_eq!(written, jumbf);
+
+    // So does a modified box.
+    dbox.id = Some(7);
+    assert!(dbox.toggles().contains(Toggles::HAS_LABEL));
+    let written = dbox.to_vec();
+    assert_eq!(
+        written,
+        hex!(
+            "00000022" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "07" // toggles
+            "6162ff6400" // label (not valid UTF-8)
+            "00000007" // ID
+        )
+    );
+
+    let (_, boxx) = DataBox::from_slice(&written).unwrap();
+    let (_, rewritten) = DescriptionBox::from_box_with_options(boxx, &options).unwrap();
+    assert_eq!(rewritten.raw_label(), Some(&b"ab\xffd"[..]));
+
+    // Repair the label.
+    dbox.label = Some("abcd");
+    assert_eq!(dbox.raw_label(), Some(&b"abcd"[..]));
+}
+
+#[test]
+fn content_type() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    assert_eq!(
+        sbox.desc.content_type(),
+        Some(&content_type::C2PA_MANIFEST_STORE)
+    );
+
+    let manifest = sbox.child_boxes[0].as_super_box().unwrap();
+    assert_eq!(
+        manifest.desc.content_type(),
+        Some(&content_type::C2PA_MANIFEST)
+    );
+
+    let (_, desc) = DescriptionBox::from_slice(&hex!(
+        "00000019" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "00" // toggles
+    ))
+    .unwrap();
+    assert_eq!(desc.content_type(), None);
+}
+
+#[test]
+fn write_to() {
+    let jumbf = hex!(
+        "00000040" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "13" // toggles
+        "746573742e64657363626f7800" // label
+        "0000001a" // box size
+        "63326873" // box type = 'c2sh'
+        "0102030405060708090a0b0c0d0e0f10" // salt
+        "1112" // more salt
+    );
+
+    let (_, mut desc) = DescriptionBox::from_slice(&jumbf).unwrap();
+
+    // Unmodified boxes are written as is.
+    assert_eq!(desc.to_vec(), jumbf);
+    assert_eq!(desc.serialized_len(), jumbf.len());
+
+    let hash = [0x55u8; 32];
+    desc.requestable = false;
+    desc.hash = Some(&hash);
+
+    let new_jumbf = desc.to_vec();
+    assert_eq!(new_jumbf.len(), jumbf.len() + 32);
+    assert_eq!(desc.serialized_len(), new_jumbf.len());
+    assert_eq!(new_jumbf[24], 0x1a);
+
+    let (rem, new_desc) = DescriptionBox::from_slice(&new_jumbf).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(new_desc.label, Some("test.descbox"));
+    assert!(!new_desc.requestable);
+    assert_eq!(new_desc.hash, Some(&hash));
+    assert_eq!(new_desc.private, desc.private);
+}
+
+#[test]
+fn write_to_shortened_label() {
+    let jumbf = hex!(
+        "00000026" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "746573742e64657363626f7800" // label
+    );
+
+    let (_, mut desc) = DescriptionBox::from_slice(&jumbf).unwrap();
+
+    // A label which still points into the original box, but is shorter,
+    // is a modification.
+    desc.label = desc.label.map(|label| &label[..4]);
+    assert_eq!(desc.serialized_len(), jumbf.len() - 8);
+
+    let new_jumbf = desc.to_vec();
+    let (_, new_desc) = DescriptionBox::from_slice(&new_jumbf).unwrap();
+    assert_eq!(new_desc.label, Some("test"));
+}
@@ -0,0 +1,213 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::parser::{
+    diagnostics::{
+        parse_data_box, parse_super_box, parse_super_box_with_options, Diagnostic,
+        DiagnosticContext,
+    },
+    ChildBox, ParseOptions, SuperBox,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn matches_default_parser() {
+    let (rem, sbox) = parse_super_box(C2PA_MANIFEST_STORE).unwrap();
+    assert!(rem.is_empty());
+
+    let (_, expected) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(sbox, expected);
+}
+
+#[test]
+fn truncated_nested_box() {
+    let mut jumbf = C2PA_MANIFEST_STORE.to_vec();
+    jumbf.truncate(40000);
+
+    // Patch the outer box lengths so that only the claim signature's
+    // payload is truncated.
+    jumbf[0..4].copy_from_slice(&40000u32.to_be_bytes());
+
+    let err = parse_super_box(&jumbf).unwrap_err();
+    assert_eq!(err.context.last().unwrap().description, "superbox \"c2pa\"");
+    assert_eq!(err.context.last().unwrap().offset, 0);
+    assert!(err.expected.ends_with("bytes of box payload"));
+}
+
+#[test]
+fn invalid_box_length() {
+    let jumbf = hex!(
+        "00000002" // box size (INVALID)
+        "6a756d62" // box type = 'jumb'
+    );
+
+    assert_eq!(
+        parse_super_box(&jumbf).unwrap_err(),
+        Diagnostic {
+            offset: 0,
+            expected: "box length of 0, 1, or at least 8".to_owned(),
+            found: "2".to_owned(),
+            context: vec![DiagnosticContext {
+                offset: 0,
+                description: "superbox".to_owned(),
+            }],
+        }
+    );
+}
+
+#[test]
+fn missing_label_terminator() {
+    let jumbf = hex!(
+        "00000026" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001e" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "6c6162656c" // label (no NUL)
+    );
+
+    let err = parse_super_box(&jumbf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at offset 38: expected NUL terminator for label, found end of data\n  \
+         in description box at offset 8\n  \
+         in superbox at offset 0"
+    );
+}
+
+#[test]
+fn invalid_utf8_label() {
+    let jumbf = hex!(
+        "00000025" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001d" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "61ff6300" // label (invalid UTF-8)
+    );
+
+    let err = parse_super_box(&jumbf).unwrap_err();
+    assert_eq!(err.offset, 34);
+    assert_eq!(err.expected, "UTF-8 label");
+}
+
+#[test]
+fn incomplete_header() {
+    let err = parse_data_box(&hex!("000000")).unwrap_err();
+    assert_eq!(
+        err,
+        Diagnostic {
+            offset: 0,
+            expected: "32-bit box length".to_owned(),
+            found: "only 3 bytes".to_owned(),
+            context: vec![],
+        }
+    );
+
+    let err = parse_data_box(&hex!("0000000c6a75")).unwrap_err();
+    assert_eq!(err.offset, 4);
+    assert_eq!(err.expected, "4-byte box type");
+}
+
+#[test]
+fn nested_context() {
+    let jumbf = hex!(
+        "00000049" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001e" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "6f75746500" // label = "oute"
+            // ---
+            "00000023" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001b" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "04" // toggles (has ID)
+                "0000" // ID (truncated)
+    );
+
+    let err = parse_super_box(&jumbf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "at offset 71: expected 32-bit ID, found only 2 bytes\n  \
+         in description box at offset 46\n  \
+         in superbox at offset 38\n  \
+         in superbox \"oute\" at offset 0"
+    );
+}
+
+#[test]
+fn deep_nesting() {
+    // 100,000 nested superboxes, each containing only a description box
+    // and the next superbox.
+    const DEPTH: usize = 100_000;
+    const LEVEL_LEN: usize = 33;
+
+    let mut jumbf = Vec::with_capacity(DEPTH * LEVEL_LEN);
+    for level in 0..DEPTH {
+        jumbf.extend_from_slice(&(((DEPTH - level) * LEVEL_LEN) as u32).to_be_bytes());
+        jumbf.extend_from_slice(b"jumb");
+        jumbf.extend_from_slice(&hex!(
+            "00000019 6a756d64 00000000000000000000000000000000 00"
+        ));
+    }
+
+    let (rem, sbox) = parse_super_box(&jumbf).unwrap();
+    assert!(rem.is_empty());
+
+    let (_, expected) = SuperBox::from_slice(&jumbf).unwrap();
+    assert_eq!(sbox, expected);
+
+    let options = ParseOptions::default().set_depth_limit(0);
+    let (_, sbox) = parse_super_box_with_options(&jumbf, &options).unwrap();
+    let Some(ChildBox::DataBox(dbox)) = sbox.child_boxes.first() else {
+        panic!("expected a data box");
+    };
+    assert_eq!(dbox.tbox.0, *b"jumb");
+}
+
+#[test]
+fn label_too_long() {
+    let jumbf = hex!(
+        "0000002f" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+    );
+
+    let options = ParseOptions::default().set_max_label_len(13);
+    let (_, sbox) = parse_super_box_with_options(&jumbf, &options).unwrap();
+    assert_eq!(sbox.desc.label, Some("test.superbox"));
+
+    let options = ParseOptions::default().set_max_label_len(12);
+    let err = parse_super_box_with_options(&jumbf, &options).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "at offset 33: expected label of at most 12 bytes, found a longer label\n  \
+         in description box at offset 8\n  \
+         in superbox at offset 0"
+    );
+}
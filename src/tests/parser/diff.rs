@@ -0,0 +1,149 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    parser::{BoxDiff, ChildBox, DiffKind, LabelPath, SuperBox},
+    BoxType,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+const MANIFEST: &str = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+
+#[test]
+fn identical() {
+    let (_, old) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let (_, new) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(old.diff(&new), vec![]);
+}
+
+#[test]
+fn changed_data_box() {
+    let mut jumbf = C2PA_MANIFEST_STORE.to_vec();
+    jumbf[33000] ^= 0xff;
+
+    let (_, old) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let (_, new) = SuperBox::from_slice(&jumbf).unwrap();
+
+    assert_eq!(
+        old.diff(&new),
+        vec![BoxDiff {
+            path: LabelPath::from_iter([MANIFEST, "c2pa.claim"]),
+            tbox: BoxType(*b"cbor"),
+            index: 0,
+            old: Some(&C2PA_MANIFEST_STORE[32526..33166]),
+            new: Some(&jumbf[32526..33166]),
+        }]
+    );
+}
+
+#[test]
+fn relabeled_super_box() {
+    let (_, mut sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let ChildBox::SuperBox(manifest) = &mut sbox.child_boxes[0] else {
+        panic!("expected manifest superbox");
+    };
+    let ChildBox::SuperBox(claim) = &mut manifest.child_boxes[1] else {
+        panic!("expected claim superbox");
+    };
+    claim.desc.label = Some("c2pa.claim.v2");
+
+    let jumbf = sbox.to_vec();
+    let (_, old) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let (_, new) = SuperBox::from_slice(&jumbf).unwrap();
+
+    let diffs = old.diff(&new);
+
+    // The root and manifest description boxes are unchanged, so the only
+    // differences are the removed and added claim superboxes.
+    assert_eq!(diffs.len(), 2);
+
+    assert_eq!(diffs[0].kind(), DiffKind::Removed);
+    assert_eq!(diffs[0].tbox, BoxType(*b"jumb"));
+    assert_eq!(
+        diffs[0].path,
+        LabelPath::from_iter([MANIFEST, "c2pa.claim"])
+    );
+    assert_eq!(diffs[0].old.unwrap().len(), 684);
+
+    assert_eq!(diffs[1].kind(), DiffKind::Added);
+    assert_eq!(diffs[1].tbox, BoxType(*b"jumb"));
+    assert_eq!(
+        diffs[1].path,
+        LabelPath::from_iter([MANIFEST, "c2pa.claim.v2"])
+    );
+    assert_eq!(diffs[1].new.unwrap().len(), 687);
+}
+
+#[test]
+fn description_and_sibling_changes() {
+    let old = hex!(
+        "00000030" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000000f" // box size
+            "6a736f6e" // box type = 'json'
+            "7b2261223a317d" // payload = {"a":1}
+    );
+
+    let new = hex!(
+        "00000043" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001d" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "04" // toggles
+            "00000007" // id
+            // ---
+            "0000000f" // box size
+            "6a736f6e" // box type = 'json'
+            "7b2261223a317d" // payload = {"a":1}
+            // ---
+            "0000000f" // box size
+            "6a736f6e" // box type = 'json'
+            "7b2262223a327d" // payload = {"b":2}
+    );
+
+    let (_, old_sbox) = SuperBox::from_slice(&old).unwrap();
+    let (_, new_sbox) = SuperBox::from_slice(&new).unwrap();
+
+    assert_eq!(
+        old_sbox.diff(&new_sbox),
+        vec![
+            BoxDiff {
+                path: LabelPath::new(),
+                tbox: BoxType(*b"jumd"),
+                index: 0,
+                old: Some(&old[8..33]),
+                new: Some(&new[8..37]),
+            },
+            BoxDiff {
+                path: LabelPath::new(),
+                tbox: BoxType(*b"json"),
+                index: 1,
+                old: None,
+                new: Some(&new[52..67]),
+            },
+        ]
+    );
+
+    let diffs = new_sbox.diff(&old_sbox);
+    assert_eq!(diffs[1].kind(), DiffKind::Removed);
+}
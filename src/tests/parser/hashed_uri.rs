@@ -0,0 +1,65 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    digest::Digest,
+    parser::{HashedUri, SuperBox},
+    tests::digest::XorDigest,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn c2pa_assertion() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let label = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions/c2pa.thumbnail.claim.jpeg";
+    let expected = sbox.find_by_label(label).unwrap().original;
+
+    let uri = sbox.hashed_uri(label, &XorDigest).unwrap();
+
+    assert_eq!(
+        uri,
+        HashedUri {
+            url: format!("self#jumbf=/c2pa/{label}"),
+            alg: "xor".to_owned(),
+            hash: XorDigest.digest(expected),
+        }
+    );
+}
+
+#[test]
+fn unknown_label() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(sbox.hashed_uri("not.there", &XorDigest), None);
+}
+
+#[test]
+fn base64_encoding() {
+    let uri = |hash: &[u8]| {
+        HashedUri {
+            url: "self#jumbf=x".to_owned(),
+            alg: "test".to_owned(),
+            hash: hash.to_vec(),
+        }
+        .to_string()
+    };
+
+    assert_eq!(uri(b""), "self#jumbf=x?hl=");
+    assert_eq!(uri(b"f"), "self#jumbf=x?hl=Zg");
+    assert_eq!(uri(b"fo"), "self#jumbf=x?hl=Zm8");
+    assert_eq!(uri(b"foo"), "self#jumbf=x?hl=Zm9v");
+    assert_eq!(uri(&[0xfb, 0xff]), "self#jumbf=x?hl=-_8");
+}
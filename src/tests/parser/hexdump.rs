@@ -0,0 +1,88 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::parser::{hexdump::HexDump, DataBox, SuperBox};
+
+#[test]
+fn simple_super_box() {
+    let jumbf = hex!(
+        "00000047" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "6a736f6e00110010800000aa00389b71" // UUID = JSON
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "00000018" // box size
+            "6a736f6e" // box type = 'json'
+            "7b2022666f6f223a2022626172227d0a" // payload
+    );
+
+    let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    let dump = HexDump::default().super_box(&sbox);
+
+    assert_eq!(
+        dump,
+        concat!(
+        "00000000  00 00 00 47                                      box size = 71\n",
+        "00000004  6a 75 6d 62                                      box type = b\"jumb\"\n",
+        "00000008  00 00 00 27                                        box size = 39\n",
+        "0000000c  6a 75 6d 64                                        box type = b\"jumd\"\n",
+        "00000010  6a 73 6f 6e 00 11 00 10 80 00 00 aa 00 38 9b 71      uuid (json)\n",
+        "00000020  03                                                   toggles = REQUESTABLE | HAS_LABEL\n",
+        "00000021  74 65 73 74 2e 73 75 70 65 72 62 6f 78 00            label = \"test.superbox\"\n",
+        "0000002f  00 00 00 18                                        box size = 24\n",
+        "00000033  6a 73 6f 6e                                        box type = b\"json\"\n",
+        "00000037  7b 20 22 66 6f 6f 22 3a 20 22 62 61 72 22 7d 0a      |{ \"foo\": \"bar\"}.|\n",
+        )
+    );
+}
+
+#[test]
+fn truncated_payload() {
+    let jumbf = hex!(
+        "00000018" // box size
+        "6a736f6e" // box type = 'json'
+        "7b2022666f6f223a2022626172227d0a" // payload
+    );
+
+    let (_, dbox) = DataBox::from_slice(&jumbf).unwrap();
+    let dump = HexDump::default().set_max_payload_len(4).data_box(&dbox);
+
+    assert_eq!(
+        dump,
+        concat!(
+            "00000000  00 00 00 18                                      box size = 24\n",
+            "00000004  6a 73 6f 6e                                      box type = b\"json\"\n",
+            "00000008  7b 20 22 66                                        |{ \"f|\n",
+            "0000000c                                                     ... 12 more bytes\n",
+        )
+    );
+}
+
+#[test]
+fn c2pa_manifest() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let dump = HexDump::default().set_max_payload_len(16).super_box(&sbox);
+    assert!(dump.starts_with("00000000  00 00 b7 64 "));
+    assert!(dump.contains("label = \"c2pa.claim\""));
+    assert!(dump.contains("uuid (c2pa claim)"));
+    assert!(dump.contains(" more bytes"));
+    assert!(dump.lines().count() < 200);
+}
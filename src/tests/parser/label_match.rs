@@ -0,0 +1,51 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::parser::{LabelMatch, SuperBox};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn exact_by_default() {
+    let m = LabelMatch::default();
+    assert!(m.matches("c2pa.claim", "c2pa.claim"));
+    assert!(!m.matches("c2pa.claim", "C2PA.Claim"));
+    assert!(!m.matches("caf\u{e9}", "cafe\u{301}"));
+}
+
+#[test]
+fn case_insensitive() {
+    let m = LabelMatch::default().set_case_insensitive(true);
+    assert!(m.matches("c2pa.claim", "C2PA.Claim"));
+    assert!(m.matches("\u{c9}t\u{c9}", "\u{e9}t\u{e9}"));
+    assert!(!m.matches("c2pa.claim", "c2pa.claims"));
+
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let label = "ContentAuth:urn:uuid:021B555E-5E02-4074-B444-43D7919D89B9/C2PA.Claim";
+    assert!(sbox.find_by_label(label).is_none());
+    assert_eq!(
+        sbox.find_by_label_with(label, &m),
+        sbox.find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim")
+    );
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn nfc() {
+    let m = LabelMatch::default().set_nfc(true);
+    assert!(m.matches("caf\u{e9}", "cafe\u{301}"));
+    assert!(!m.matches("caf\u{e9}", "CAFE\u{301}"));
+
+    let m = m.set_case_insensitive(true);
+    assert!(m.matches("caf\u{e9}", "CAFE\u{301}"));
+}
@@ -0,0 +1,106 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::parser::{escape_label, label_path::unescape_label, LabelPath, SuperBox};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn escape() {
+    assert_eq!(escape_label("c2pa.claim"), "c2pa.claim");
+    assert_eq!(escape_label("a/b/c"), "a%2Fb%2Fc");
+    assert_eq!(escape_label("50%?#"), "50%25%3F%23");
+    assert_eq!(escape_label("tab\there"), "tab%09here");
+    assert_eq!(escape_label("caf\u{e9}"), "caf\u{e9}");
+}
+
+#[test]
+fn unescape() {
+    assert_eq!(unescape_label("c2pa.claim"), "c2pa.claim");
+    assert_eq!(unescape_label("a%2Fb%2fc"), "a/b/c");
+    assert_eq!(unescape_label("caf%C3%A9"), "caf\u{e9}");
+
+    // Malformed escapes are taken literally.
+    assert_eq!(unescape_label("100%"), "100%");
+    assert_eq!(unescape_label("%zz"), "%zz");
+    assert_eq!(unescape_label("%+1"), "%+1");
+    assert_eq!(unescape_label("%-1"), "%-1");
+    assert_eq!(unescape_label("%ff"), "%ff");
+
+    for label in ["a/b", "100%", "x?y#z", "caf\u{e9}/%41"] {
+        assert_eq!(unescape_label(&escape_label(label)), label);
+    }
+}
+
+#[test]
+fn find_label_with_slash() {
+    let jumbf = hex!(
+        "00000046" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000025" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "612f6200" // label = "a/b"
+    );
+
+    let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+
+    assert!(sbox.find_by_label("a/b").is_none());
+
+    let child = sbox.find_by_label(&escape_label("a/b")).unwrap();
+    assert_eq!(child.desc.label, Some("a/b"));
+}
+
+#[test]
+fn label_path() {
+    let mut path = LabelPath::new();
+    assert!(path.is_empty());
+    assert_eq!(path.to_string(), "");
+
+    path.push("c2pa");
+    path.push("a/b");
+    path.push("100%");
+    assert_eq!(path.len(), 3);
+    assert_eq!(path.to_string(), "c2pa/a%2Fb/100%25");
+    assert_eq!(LabelPath::from(path.to_string().as_str()), path);
+
+    assert_eq!(path.pop(), Some("100%".to_owned()));
+    assert_eq!(path, ["c2pa", "a/b"].into_iter().collect());
+
+    let parsed: LabelPath = "x/y%2Fz".parse().unwrap();
+    assert_eq!(parsed.components().collect::<Vec<_>>(), ["x", "y/z"]);
+}
+
+#[test]
+fn find_by_label_path() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let label = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim";
+    let path = LabelPath::from(label);
+    assert_eq!(path.len(), 2);
+
+    assert_eq!(sbox.find_by_label_path(&path), sbox.find_by_label(label));
+    assert!(sbox.find_by_label_path(&path).is_some());
+    assert_eq!(sbox.find_by_label_path(&LabelPath::new()), Some(&sbox));
+}
@@ -0,0 +1,709 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, ErrorKind, Read, Result as IoResult, Seek, SeekFrom};
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    parser::{
+        layout::{
+            extract_box_to, min_header_len, patch, patch_file, rewrite_box_len, write_box_header,
+            BoxLayout, ByteRangeRole,
+        },
+        ChildBox, Error, ParseOptions, SuperBox,
+    },
+    BoxType,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+// Verify that `layout` describes the same boxes as `sbox`.
+fn assert_same_layout(layout: &BoxLayout, sbox: &SuperBox, input: &[u8]) {
+    let offset_of = |slice: &[u8]| (slice.as_ptr() as usize - input.as_ptr() as usize) as u64;
+
+    assert_eq!(layout.tbox, BoxType(*b"jumb"));
+    assert_eq!(layout.offset, offset_of(sbox.original));
+    assert_eq!(layout.len, sbox.original.len() as u64);
+    assert_eq!(layout.label.as_deref(), sbox.desc.label);
+
+    assert_eq!(layout.children[0].tbox, BoxType(*b"jumd"));
+    assert_eq!(layout.children[0].offset, offset_of(sbox.desc.original));
+    assert_eq!(layout.children.len(), sbox.child_boxes.len() + 1);
+
+    for (child_layout, child) in layout.children[1..].iter().zip(&sbox.child_boxes) {
+        match child {
+            ChildBox::SuperBox(child) => assert_same_layout(child_layout, child, input),
+            ChildBox::DataBox(dbox) => {
+                assert_eq!(child_layout.tbox, dbox.tbox);
+                assert_eq!(child_layout.offset, offset_of(dbox.original));
+                assert_eq!(child_layout.len, dbox.original.len() as u64);
+                assert_eq!(child_layout.header_len, dbox.header_len() as u64);
+                assert!(child_layout.children.is_empty());
+            }
+        }
+    }
+}
+
+#[test]
+fn c2pa_manifest() {
+    let mut reader = Cursor::new(C2PA_MANIFEST_STORE);
+    let layout = BoxLayout::from_reader(&mut reader).unwrap();
+    assert_eq!(reader.position(), 46948);
+
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_same_layout(&layout, &sbox, C2PA_MANIFEST_STORE);
+}
+
+#[test]
+fn with_depth_limit() {
+    let options = ParseOptions::default().set_depth_limit(1);
+
+    let mut reader = Cursor::new(C2PA_MANIFEST_STORE);
+    let layout = BoxLayout::from_reader_with_options(&mut reader, &options).unwrap();
+
+    let (_, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert_same_layout(&layout, &sbox, C2PA_MANIFEST_STORE);
+}
+
+#[test]
+fn starts_at_current_position() {
+    let mut input = b"prefix".to_vec();
+    input.extend_from_slice(C2PA_MANIFEST_STORE);
+    input.extend_from_slice(b"suffix");
+
+    let mut reader = Cursor::new(&input);
+    reader.seek(SeekFrom::Start(6)).unwrap();
+
+    let layout = BoxLayout::from_reader(&mut reader).unwrap();
+    assert_eq!(layout.range(), 6..46954);
+    assert_eq!(layout.payload_range(), 14..46954);
+    assert_eq!(reader.position(), 46954);
+}
+
+#[test]
+fn consecutive_super_boxes() {
+    let mut input = C2PA_MANIFEST_STORE.to_vec();
+    input.extend_from_slice(C2PA_MANIFEST_STORE);
+
+    let mut reader = Cursor::new(&input);
+
+    let first = BoxLayout::from_reader(&mut reader).unwrap();
+    assert_eq!(first.end_offset(), 46948);
+    assert_eq!(reader.position(), first.end_offset());
+
+    let second = BoxLayout::from_reader(&mut reader).unwrap();
+    assert_eq!(second.offset, 46948);
+    assert_eq!(second.end_offset(), 93896);
+    assert_eq!(reader.position(), 93896);
+}
+
+#[test]
+fn box_size_zero_extends_to_parent_end() {
+    let jumbf = hex!(
+        "00000000" // box size (to end of data)
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000000" // box size (to end of superbox)
+            "6a736f6e" // box type = 'json'
+            "7b7d" // payload = {}
+    );
+
+    let layout = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap();
+    assert_eq!(layout.range(), 0..43);
+    assert_eq!(layout.label, None);
+    assert_eq!(layout.children[1].range(), 33..43);
+}
+
+#[test]
+fn error_truncated() {
+    let mut reader = Cursor::new(&C2PA_MANIFEST_STORE[..1000]);
+    let err = BoxLayout::from_reader(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(
+        *err,
+        Error::Truncated {
+            needed: 45948,
+            offset: 0
+        }
+    );
+
+    let mut reader = Cursor::new(&C2PA_MANIFEST_STORE[..4]);
+    let err = BoxLayout::from_reader(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(
+        *err,
+        Error::Truncated {
+            needed: 4,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn error_not_super_box() {
+    let jumbf = hex!(
+        "0000000a" // box size
+        "6a736f6e" // box type = 'json'
+        "7b7d" // payload = {}
+    );
+
+    let err = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*err, Error::InvalidSuperBoxType(BoxType(*b"json")));
+}
+
+#[test]
+fn error_xlbox_size_too_small() {
+    let jumbf = hex!(
+        "00000001" // box size (contained in xlbox)
+        "6a756d62" // box type = 'jumb'
+        "000000000000000e" // XLbox (INCORRECT extra long box size)
+        "00000019" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "00" // toggles
+    );
+
+    let err = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*err, Error::InvalidBoxLength(14));
+}
+
+#[test]
+fn error_label_too_long() {
+    let options = ParseOptions::default().set_max_label_len(4);
+
+    let err = BoxLayout::from_reader_with_options(&mut Cursor::new(C2PA_MANIFEST_STORE), &options)
+        .unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*err, Error::LabelTooLong(4));
+}
+
+#[test]
+fn expand() {
+    let mut reader = Cursor::new(C2PA_MANIFEST_STORE);
+    let full = BoxLayout::from_reader(&mut reader).unwrap();
+
+    let options = ParseOptions::default().set_depth_limit(0);
+    reader.set_position(0);
+    let mut layout = BoxLayout::from_reader_with_options(&mut reader, &options).unwrap();
+
+    let manifest = &mut layout.children[1];
+    assert_eq!(manifest.label, None);
+    assert!(manifest.children.is_empty());
+
+    manifest
+        .expand(&mut reader, &ParseOptions::default())
+        .unwrap();
+    assert_eq!(reader.position(), manifest.end_offset());
+    assert_eq!(layout, full);
+
+    // Expanding again changes nothing.
+    let manifest = &mut layout.children[1];
+    manifest.expand(&mut reader, &options).unwrap();
+    assert_eq!(layout, full);
+
+    let err = layout.children[0]
+        .expand(&mut reader, &options)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn expand_after_error() {
+    let full = BoxLayout::from_reader(&mut Cursor::new(C2PA_MANIFEST_STORE)).unwrap();
+
+    // Corrupt the header of the manifest's second child, after its
+    // description box has been read.
+    let mut corrupt = C2PA_MANIFEST_STORE.to_vec();
+    let offset = full.children[1].children[1].offset as usize;
+    corrupt[offset..offset + 4].copy_from_slice(&[0, 0, 0, 2]);
+
+    let options = ParseOptions::default().set_depth_limit(0);
+    let mut reader = Cursor::new(corrupt);
+    let mut layout = BoxLayout::from_reader_with_options(&mut reader, &options).unwrap();
+
+    let manifest = &mut layout.children[1];
+    assert!(manifest
+        .expand(&mut reader, &ParseOptions::default())
+        .is_err());
+    assert_eq!(manifest.label, None);
+    assert!(manifest.children.is_empty());
+
+    // The failed expansion can be retried.
+    let mut reader = Cursor::new(C2PA_MANIFEST_STORE);
+    manifest
+        .expand(&mut reader, &ParseOptions::default())
+        .unwrap();
+    assert_eq!(layout, full);
+}
+
+#[test]
+fn error_child_larger_than_super_box() {
+    let jumbf = hex!(
+        "0000002b" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000010" // box size (INCORRECT, larger than superbox)
+            "61626364" // box type = 'abcd'
+            "0102" // payload
+    );
+
+    let err = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(
+        *err,
+        Error::SizeMismatch {
+            offset: 33,
+            declared: 16,
+            available: 10
+        }
+    );
+}
+
+#[test]
+fn misplaced_description_box() {
+    let jumbf = hex!(
+        "0000003b" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+    );
+
+    assert_eq!(
+        SuperBox::parse(&jumbf).unwrap_err(),
+        Error::MisplacedDescriptionBox
+    );
+
+    let err = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*err, Error::MisplacedDescriptionBox);
+
+    let options = ParseOptions::default().set_lenient_description_box(true);
+    let (_, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    let layout = BoxLayout::from_reader_with_options(&mut Cursor::new(&jumbf), &options).unwrap();
+
+    assert_eq!(layout.label.as_deref(), sbox.desc.label);
+    assert_eq!(layout.children.len(), sbox.child_boxes.len() + 1);
+    assert_eq!(layout.children[1].tbox, BoxType(*b"jumd"));
+}
+
+#[test]
+fn missing_description_box() {
+    let jumbf = hex!(
+        "00000014" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+    );
+
+    assert_eq!(
+        SuperBox::parse(&jumbf).unwrap_err(),
+        Error::MissingDescriptionBox
+    );
+
+    let err = BoxLayout::from_reader(&mut Cursor::new(&jumbf)).unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*err, Error::MissingDescriptionBox);
+
+    let options = ParseOptions::default().set_lenient_description_box(true);
+    let layout = BoxLayout::from_reader_with_options(&mut Cursor::new(&jumbf), &options).unwrap();
+    assert_eq!(layout.label, None);
+    assert_eq!(layout.children.len(), 1);
+}
+
+#[test]
+fn byte_ranges() {
+    let mut input = b"prefix".to_vec();
+    input.extend_from_slice(C2PA_MANIFEST_STORE);
+
+    let mut reader = Cursor::new(&input);
+    reader.set_position(6);
+    let layout = BoxLayout::from_reader(&mut reader).unwrap();
+    let ranges = layout.byte_ranges();
+
+    // The ranges are contiguous and cover the entire superbox.
+    let mut pos = 6;
+    for r in &ranges {
+        assert_eq!(r.offset, pos);
+        assert!(r.len > 0);
+        pos = r.range().end;
+    }
+    assert_eq!(pos, input.len() as u64);
+
+    let desc_boxes = ranges
+        .iter()
+        .filter(|r| r.role == ByteRangeRole::DescriptionBox)
+        .count();
+    assert_eq!(desc_boxes, 9);
+
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    let claim_path = format!("{manifest}/c2pa.claim");
+
+    let claim: Vec<_> = ranges
+        .iter()
+        .filter(|r| r.path.to_string() == claim_path)
+        .map(|r| (r.tbox, r.role))
+        .collect();
+
+    assert_eq!(
+        claim,
+        [
+            (BoxType(*b"jumb"), ByteRangeRole::Header),
+            (BoxType(*b"jumd"), ByteRangeRole::DescriptionBox),
+            (BoxType(*b"cbor"), ByteRangeRole::Header),
+            (BoxType(*b"cbor"), ByteRangeRole::Payload),
+        ]
+    );
+
+    // A superbox whose children weren't read is treated as opaque.
+    let options = ParseOptions::default().set_depth_limit(0);
+    reader.set_position(6);
+    let layout = BoxLayout::from_reader_with_options(&mut reader, &options).unwrap();
+    let ranges = layout.byte_ranges();
+
+    let table: Vec<_> = ranges
+        .iter()
+        .map(|r| (r.path.len(), r.tbox, r.role, r.range()))
+        .collect();
+
+    assert_eq!(
+        table,
+        [
+            (0, BoxType(*b"jumb"), ByteRangeRole::Header, 6..14),
+            (0, BoxType(*b"jumd"), ByteRangeRole::DescriptionBox, 14..44),
+            (0, BoxType(*b"jumb"), ByteRangeRole::Header, 44..52),
+            (0, BoxType(*b"jumb"), ByteRangeRole::Payload, 52..46954),
+        ]
+    );
+}
+
+#[test]
+fn patch_same_size() {
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    let claim_label = format!("{manifest}/c2pa.claim");
+
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let claim = sbox
+        .find_by_label(&claim_label)
+        .unwrap()
+        .data_box()
+        .unwrap();
+    let claim_offset = claim.offset_within_superbox(&sbox).unwrap();
+    let new_claim = vec![0xa0; claim.data.len()];
+
+    let mut stream = Cursor::new(C2PA_MANIFEST_STORE.to_vec());
+    patch(&mut stream, &claim_label, &new_claim).unwrap();
+
+    let mut expected = C2PA_MANIFEST_STORE.to_vec();
+    expected[claim_offset..claim_offset + new_claim.len()].copy_from_slice(&new_claim);
+    assert_eq!(stream.into_inner(), expected);
+}
+
+#[test]
+fn patch_with_free_box() {
+    let jumbf = hex!(
+        "0000005a" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000039" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "61626300" // label = "abc"
+                // ---
+                "00000014" // box size
+                "6a736f6e" // box type = 'json'
+                "7b2261223a3132333435367d" // payload = {"a":123456}
+    );
+
+    // `patch()` reads the superbox from the current position.
+    fn patch_from_start(stream: &mut Cursor<Vec<u8>>, label: &str, payload: &[u8]) -> IoResult<()> {
+        stream.set_position(0);
+        patch(stream, label, payload)
+    }
+
+    let mut stream = Cursor::new(jumbf.to_vec());
+    patch_from_start(&mut stream, "abc", b"{}").unwrap();
+
+    assert_eq!(
+        stream.get_ref()[70..],
+        hex!(
+            "0000000a" // box size
+            "6a736f6e" // box type = 'json'
+            "7b7d" // payload = {}
+            "0000000a" // box size
+            "66726565" // box type = 'free'
+            "0000" // padding
+        )
+    );
+
+    // The payload can grow into the free box.
+    patch_from_start(&mut stream, "abc", b"[10]").unwrap();
+    assert_eq!(
+        stream.get_ref()[70..],
+        hex!(
+            "0000000c" // box size
+            "6a736f6e" // box type = 'json'
+            "5b31305d" // payload = [10]
+            "00000008" // box size
+            "66726565" // box type = 'free'
+        )
+    );
+
+    let sbox = SuperBox::parse(stream.get_ref()).unwrap();
+    assert!(sbox.verify_structure().is_valid());
+
+    // Nothing is written if the payload doesn't fit. Any space left over
+    // must be large enough for a free box.
+    let grown = stream.get_ref().clone();
+
+    let err = patch_from_start(&mut stream, "abc", b"{\"bc\":12}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = patch_from_start(&mut stream, "abc", b"{\"a\":1234567}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = patch_from_start(&mut stream, "xyz", b"{}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = patch_from_start(&mut stream, "", b"{}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    assert_eq!(stream.get_ref(), &grown);
+
+    // The payload can use all of the free box.
+    patch_from_start(&mut stream, "abc", b"{\"a\":123456}").unwrap();
+    assert_eq!(stream.into_inner(), jumbf);
+}
+
+#[test]
+fn patch_frees_too_much() {
+    // A stream whose contents after `head` read as zeros, so that a box
+    // larger than 4 GiB can be described without allocating it.
+    struct SparseStream {
+        head: Vec<u8>,
+        len: u64,
+        pos: u64,
+    }
+
+    impl Read for SparseStream {
+        fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+            let len = buf.len().min((self.len - self.pos.min(self.len)) as usize);
+            for (i, b) in buf[..len].iter_mut().enumerate() {
+                *b = *self.head.get(self.pos as usize + i).unwrap_or(&0);
+            }
+            self.pos += len as u64;
+            Ok(len)
+        }
+    }
+
+    impl std::io::Write for SparseStream {
+        fn write(&mut self, _buf: &[u8]) -> IoResult<usize> {
+            panic!("nothing should be written");
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for SparseStream {
+        fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+            self.pos = match pos {
+                SeekFrom::Start(pos) => pos,
+                SeekFrom::End(delta) => self.len.checked_add_signed(delta).unwrap(),
+                SeekFrom::Current(delta) => self.pos.checked_add_signed(delta).unwrap(),
+            };
+            Ok(self.pos)
+        }
+    }
+
+    let payload_len: u64 = 5 << 30;
+    let abc_len = 16 + 29 + 16 + payload_len;
+    let len = 16 + 25 + abc_len;
+
+    let mut head = vec![];
+    head.extend_from_slice(&hex!("00000001 6a756d62"));
+    head.extend_from_slice(&len.to_be_bytes());
+    head.extend_from_slice(&hex!(
+        "00000019" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "00" // toggles
+    ));
+    head.extend_from_slice(&hex!("00000001 6a756d62"));
+    head.extend_from_slice(&abc_len.to_be_bytes());
+    head.extend_from_slice(&hex!(
+        "0000001d" // box size
+        "6a756d64" // box type = 'jumd'
+        "00000000000000000000000000000000" // UUID
+        "03" // toggles
+        "61626300" // label = "abc"
+    ));
+    head.extend_from_slice(&hex!("00000001 6a736f6e"));
+    head.extend_from_slice(&(16 + payload_len).to_be_bytes());
+
+    let mut stream = SparseStream { head, len, pos: 0 };
+
+    let err = patch(&mut stream, "abc", b"{}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("would free"));
+}
+
+#[test]
+fn patch_file_in_place() {
+    let path = std::env::temp_dir().join(format!("jumbf-patch-{}.c2pa", std::process::id()));
+    std::fs::write(&path, C2PA_MANIFEST_STORE).unwrap();
+
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    let label = format!("{manifest}/c2pa.assertions/c2pa.hash.data");
+
+    let result = patch_file(&path, &label, b"too short");
+    let patched = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    result.unwrap();
+
+    let (_, sbox) = SuperBox::from_slice(&patched).unwrap();
+    let hash = sbox.find_by_label(&label).unwrap();
+    assert_eq!(hash.data_box().unwrap().data, b"too short");
+    assert_eq!(patched.len(), C2PA_MANIFEST_STORE.len());
+}
+
+#[test]
+fn extract_box() {
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    let label = format!("{manifest}/c2pa.claim");
+
+    let mut claim = Vec::new();
+    let len = extract_box_to(&mut Cursor::new(C2PA_MANIFEST_STORE), &label, &mut claim).unwrap();
+
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let expected = sbox.find_by_label(&label).unwrap().data_box().unwrap();
+    assert_eq!(claim, expected.data);
+    assert_eq!(len, expected.data.len() as u64);
+
+    let mut out = Vec::new();
+    let err = extract_box_to(
+        &mut Cursor::new(C2PA_MANIFEST_STORE),
+        "no.such.box",
+        &mut out,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn short_skips_read_instead_of_seeking() {
+    struct CountSeeks<R> {
+        inner: R,
+        seeks: usize,
+    }
+
+    impl<R: Read> Read for CountSeeks<R> {
+        fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl<R: Seek> Seek for CountSeeks<R> {
+        fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+            self.seeks += 1;
+            self.inner.seek(pos)
+        }
+    }
+
+    let mut reader = CountSeeks {
+        inner: Cursor::new(C2PA_MANIFEST_STORE),
+        seeks: 0,
+    };
+
+    let layout = BoxLayout::from_reader(&mut reader).unwrap();
+    assert_eq!(reader.inner.position(), 46948);
+
+    // Three seeks to find the length of the stream, then one each to skip
+    // the thumbnail and signature payloads.
+    assert_eq!(reader.seeks, 5);
+
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_same_layout(&layout, &sbox, C2PA_MANIFEST_STORE);
+}
+
+#[test]
+fn box_header_rewrites() {
+    assert_eq!(min_header_len(u32::MAX as u64 - 8), 8);
+    assert_eq!(min_header_len(u32::MAX as u64 - 7), 16);
+
+    let mut header = Vec::new();
+    write_box_header(&mut header, BoxType(*b"json"), 2, 16).unwrap();
+    assert_eq!(header, hex!("00000001 6a736f6e 0000000000000012"));
+
+    let err = write_box_header(&mut header, BoxType(*b"json"), 1 << 32, 8).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = write_box_header(&mut header, BoxType(*b"json"), 2, 12).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // An XLBox header stays an XLBox header.
+    let mut stream = Cursor::new(hex!("00000001 6a736f6e 0000000000000012 7b7d").to_vec());
+    rewrite_box_len(&mut stream, 0, 1).unwrap();
+    assert_eq!(stream.position(), 16);
+    assert_eq!(
+        stream.into_inner(),
+        hex!("00000001 6a736f6e 0000000000000011 7b7d")
+    );
+
+    // A header with a length of 0 is given an explicit length.
+    let mut stream = Cursor::new(hex!("ffff 00000000 6a736f6e 7b7d").to_vec());
+    rewrite_box_len(&mut stream, 2, 2).unwrap();
+    assert_eq!(stream.into_inner(), hex!("ffff 0000000a 6a736f6e 7b7d"));
+
+    // A compact header can't grow into an XLBox header in place.
+    let original = hex!("0000000a 6a736f6e 7b7d");
+    let mut stream = Cursor::new(original.to_vec());
+    let err = rewrite_box_len(&mut stream, 0, u32::MAX as u64).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(stream.into_inner(), original);
+
+    let mut stream = Cursor::new(hex!("00000004 6a736f6e").to_vec());
+    let err = rewrite_box_len(&mut stream, 0, 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
@@ -11,7 +11,28 @@
 // specific language governing permissions and limitations under
 // each license.
 
+#[cfg(feature = "bumpalo")]
+mod arena;
+
+#[cfg(feature = "c2pa")]
+mod c2pa;
+
+mod conformance;
 mod data_box;
 mod description_box;
+
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+
+mod diff;
+mod hashed_uri;
+mod hexdump;
+mod label_match;
+mod label_path;
+mod layout;
+mod parse_options;
+mod recovery;
 mod super_box;
 mod super_box_depth_limit;
+mod validation;
+mod write_to;
@@ -0,0 +1,293 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    parser::{ChildBox, Error, ParseOptions, SuperBox},
+    BoxType,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn metrics_for_c2pa_manifest() {
+    let (rem, (sbox, metrics)) =
+        SuperBox::from_slice_with_metrics(C2PA_MANIFEST_STORE, &ParseOptions::default()).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox, SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap().1);
+
+    // 9 superboxes (each with a description box), 7 data boxes,
+    // and 1 private box.
+    assert_eq!(metrics.boxes_parsed, 26);
+    assert_eq!(metrics.bytes_consumed, 46948);
+    assert_eq!(metrics.max_depth, 4);
+    assert_eq!(metrics.trailing_bytes, 0);
+    assert!(metrics.elapsed.is_some());
+}
+
+#[test]
+fn metrics_with_depth_limit() {
+    let (rem, (_sbox, metrics)) = SuperBox::from_slice_with_metrics(
+        C2PA_MANIFEST_STORE,
+        &ParseOptions::default().set_depth_limit(1),
+    )
+    .unwrap();
+    assert!(rem.is_empty());
+
+    // 2 superboxes (each with a description box) and 3 children of the
+    // manifest superbox which are returned as data boxes.
+    assert_eq!(metrics.boxes_parsed, 7);
+    assert_eq!(metrics.bytes_consumed, 46948);
+    assert_eq!(metrics.max_depth, 2);
+}
+
+#[test]
+fn progress_callback() {
+    let reports: Arc<Mutex<Vec<(usize, usize)>>> = Arc::new(Mutex::new(vec![]));
+    let reports_cb = reports.clone();
+
+    let options = ParseOptions::default().set_progress_callback(move |done, total| {
+        reports_cb.lock().unwrap().push((done, total));
+    });
+
+    let (rem, _sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert!(rem.is_empty());
+
+    let reports = reports.lock().unwrap();
+
+    // One report for each of 9 superboxes and 7 data boxes.
+    assert_eq!(reports.len(), 16);
+    assert!(reports.iter().all(|(_, total)| *total == 46948));
+    assert!(reports.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert_eq!(reports.last(), Some(&(46948, 46948)));
+
+    let options = options.set_depth_limit(3);
+    assert_eq!(
+        format!("{options:?}"),
+        "ParseOptions { depth_limit: 3, progress: Some(\"callback\"), cancel: None, lenient_description_box: false, demote_malformed_super_boxes: false, reject_trailing_data: false, max_label_len: None, lenient_label_encoding: false, box_type_filter: None }"
+    );
+}
+
+#[test]
+fn cancel_mid_parse() {
+    let checks = Arc::new(AtomicUsize::new(0));
+    let checks_cb = checks.clone();
+
+    // Cancel after a few boxes have been parsed.
+    let options = ParseOptions::default()
+        .set_cancel_callback(move || checks_cb.fetch_add(1, Ordering::Relaxed) >= 5);
+
+    assert_eq!(
+        SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap_err(),
+        nom::Err::Error(Error::Cancelled)
+    );
+
+    assert_eq!(checks.load(Ordering::Relaxed), 6);
+}
+
+#[test]
+fn not_cancelled() {
+    let options = ParseOptions::default().set_cancel_callback(|| false);
+
+    let (rem, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(sbox, SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap().1);
+}
+
+#[test]
+fn demote_malformed_super_boxes() {
+    let jumbf = hex_literal::hex!(
+        "00000047" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "6a756d62" // box type = 'jumb'
+            "00000004" // (INVALID) child box
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+    );
+
+    assert!(SuperBox::from_slice(&jumbf).is_err());
+
+    let options = ParseOptions::default().set_demote_malformed_super_boxes(true);
+    let (rem, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(sbox.child_boxes.len(), 2);
+
+    let ChildBox::DataBox(demoted) = &sbox.child_boxes[0] else {
+        panic!("malformed superbox should be demoted to a data box");
+    };
+    assert_eq!(demoted.tbox, crate::box_type::SUPER_BOX_TYPE);
+    assert_eq!(demoted.original, &jumbf[47..59]);
+
+    // The demoted superbox is counted once, as a data box.
+    let (_, (_, metrics)) = SuperBox::from_slice_with_metrics(&jumbf, &options).unwrap();
+    assert_eq!(metrics.boxes_parsed, 4);
+
+    // Well-formed superboxes are unaffected.
+    assert_eq!(
+        SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options)
+            .unwrap()
+            .1,
+        SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap().1
+    );
+}
+
+#[test]
+fn trailing_data() {
+    let mut jumbf = C2PA_MANIFEST_STORE.to_vec();
+    jumbf.extend_from_slice(&[0, 1, 2]);
+
+    let (rem, (_, metrics)) =
+        SuperBox::from_slice_with_metrics(&jumbf, &ParseOptions::default()).unwrap();
+    assert_eq!(rem, [0, 1, 2]);
+    assert_eq!(metrics.bytes_consumed, 46948);
+    assert_eq!(metrics.trailing_bytes, 3);
+
+    let options = ParseOptions::default().set_reject_trailing_data(true);
+    assert_eq!(
+        SuperBox::from_slice_with_options(&jumbf, &options).unwrap_err(),
+        nom::Err::Error(Error::TrailingData {
+            offset: 46948,
+            len: 3
+        })
+    );
+
+    assert!(SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).is_ok());
+}
+
+// Build a chain of `levels` superboxes, each containing only a description
+// box and the next superbox in the chain.
+fn nested_super_boxes(levels: usize) -> Vec<u8> {
+    let desc: &[u8] = &[
+        0, 0, 0, 25, b'j', b'u', b'm', b'd', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    let mut jumbf = vec![];
+    for _ in 0..levels {
+        let size = (8 + desc.len() + jumbf.len()) as u32;
+        let mut outer = size.to_be_bytes().to_vec();
+        outer.extend_from_slice(b"jumb");
+        outer.extend_from_slice(desc);
+        outer.extend_from_slice(&jumbf);
+        jumbf = outer;
+    }
+    jumbf
+}
+
+fn super_box_depth(sbox: &SuperBox) -> usize {
+    match sbox.child_boxes.first() {
+        Some(ChildBox::SuperBox(child)) => 1 + super_box_depth(child),
+        _ => 1,
+    }
+}
+
+#[test]
+fn default_depth_limit() {
+    let jumbf = nested_super_boxes(100);
+
+    let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(
+        super_box_depth(&sbox),
+        ParseOptions::DEFAULT_DEPTH_LIMIT + 1
+    );
+
+    let options = ParseOptions::default().set_depth_limit(usize::MAX);
+    let (rem, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(super_box_depth(&sbox), 100);
+}
+
+#[test]
+fn skip_box_types() {
+    let bidb = BoxType(*b"bidb");
+
+    let (_, full) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let options = ParseOptions::default().set_skip_box_types([bidb]);
+    let (rem, (sbox, metrics)) =
+        SuperBox::from_slice_with_metrics(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(metrics.boxes_parsed, 26);
+
+    let thumbnail = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions/c2pa.thumbnail.claim.jpeg";
+
+    let ChildBox::DataBox(full_bidb) = &full.find_by_label(thumbnail).unwrap().child_boxes[1]
+    else {
+        panic!("expected a data box");
+    };
+    let ChildBox::DataBox(skipped_bidb) = &sbox.find_by_label(thumbnail).unwrap().child_boxes[1]
+    else {
+        panic!("expected a data box");
+    };
+
+    assert_eq!(skipped_bidb, full_bidb);
+}
+
+#[test]
+fn skipped_boxes_round_trip() {
+    let assertions = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions";
+
+    for options in [
+        ParseOptions::default().set_skip_box_types([BoxType(*b"bidb")]),
+        ParseOptions::default().set_skip_box_types([BoxType(*b"jumb")]),
+        ParseOptions::default().set_only_box_types([BoxType(*b"jumb")]),
+    ] {
+        let (_, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+        assert_eq!(sbox.to_vec(), C2PA_MANIFEST_STORE);
+    }
+
+    // A subtree containing skipped boxes serializes as if fully parsed.
+    let (_, full) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let options = ParseOptions::default().set_skip_box_types([BoxType(*b"cbor")]);
+    let (_, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert_eq!(
+        sbox.find_by_label(assertions).unwrap().to_vec(),
+        full.find_by_label(assertions).unwrap().to_vec()
+    );
+}
+
+#[test]
+fn only_box_types() {
+    // Superboxes aren't in the list, so the manifest isn't parsed.
+    let options = ParseOptions::default().set_only_box_types([BoxType(*b"cbor")]);
+    let (rem, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox.child_boxes.len(), 1);
+    let ChildBox::DataBox(manifest) = &sbox.child_boxes[0] else {
+        panic!("expected a data box");
+    };
+    assert_eq!(manifest.tbox, BoxType(*b"jumb"));
+    assert_eq!(manifest.original, &C2PA_MANIFEST_STORE[38..]);
+
+    // Replacing the filter parses superboxes again.
+    let options = options.set_skip_box_types([]);
+    let (_, sbox) = SuperBox::from_slice_with_options(C2PA_MANIFEST_STORE, &options).unwrap();
+    assert!(matches!(sbox.child_boxes[0], ChildBox::SuperBox(_)));
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    box_type::SUPER_BOX_TYPE,
+    parser::{ChildBox, ParseOptions, SuperBox},
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn valid_c2pa_manifest() {
+    let (rem, (sbox, errors)) =
+        SuperBox::from_slice_with_recovery(C2PA_MANIFEST_STORE, &ParseOptions::default()).unwrap();
+
+    assert!(rem.is_empty());
+    assert!(errors.is_empty());
+    assert_eq!(sbox, SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap().1);
+}
+
+#[test]
+fn damaged_child_super_box() {
+    let jumbf = hex!(
+        "00000047" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "6a756d62" // box type = 'jumb'
+            "00000004" // (INVALID) child box
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+    );
+
+    assert!(SuperBox::from_slice(&jumbf).is_err());
+
+    let (rem, (sbox, errors)) =
+        SuperBox::from_slice_with_recovery(&jumbf, &ParseOptions::default()).unwrap();
+
+    assert!(rem.is_empty());
+    assert_eq!(sbox.desc.label, Some("test.superbox"));
+    assert_eq!(sbox.child_boxes.len(), 2);
+
+    let ChildBox::DataBox(damaged) = &sbox.child_boxes[0] else {
+        panic!("damaged superbox should be kept as a data box");
+    };
+    assert_eq!(damaged.tbox, SUPER_BOX_TYPE);
+    assert_eq!(damaged.original, &jumbf[47..59]);
+
+    let ChildBox::DataBox(dbox) = &sbox.child_boxes[1] else {
+        panic!("expected a data box");
+    };
+    assert_eq!(dbox.data, b"data");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].offset, 47);
+    assert_eq!(errors[0].tbox, Some(SUPER_BOX_TYPE));
+}
+
+#[test]
+fn damaged_box_header() {
+    let jumbf = hex!(
+        "00000043" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000002" // (INVALID) box size
+            "61626364" // box type = 'abcd'
+    );
+
+    assert!(SuperBox::from_slice(&jumbf).is_err());
+
+    let (rem, (sbox, errors)) =
+        SuperBox::from_slice_with_recovery(&jumbf, &ParseOptions::default()).unwrap();
+
+    assert!(rem.is_empty());
+    assert_eq!(sbox.child_boxes.len(), 1);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].offset, 59);
+    assert_eq!(errors[0].tbox, None);
+}
@@ -15,7 +15,9 @@ use hex_literal::hex;
 use pretty_assertions_sorted::assert_eq;
 
 use crate::{
-    parser::{ChildBox, DataBox, DescriptionBox, Error, SuperBox},
+    builder::{DataBoxBuilder, SuperBoxBuilder},
+    parser::{ChildBox, DataBox, DescriptionBox, Error, ParseOptions, SuperBox},
+    tests::digest::XorDigest,
     BoxType,
 };
 
@@ -511,6 +513,14 @@ fn complex_example() {
         552
     );
 
+    assert_eq!(
+        sbox.find_data_box("cb.adobe_1/c2pa.signature")
+            .map(|sig| sig.original),
+        Some(&jumbf[544..615])
+    );
+    assert_eq!(sbox.find_data_box("cb.adobe_1"), None);
+    assert_eq!(sbox.find_data_box("cb.adobe_1/c2pa.signaturex"), None);
+
     assert_eq!(sbox.data_box(), None);
 }
 
@@ -867,3 +877,771 @@ fn parse_c2pa_manifest() {
         }
     );
 }
+
+#[test]
+fn error_missing_description_box() {
+    let jumbf = hex!(
+        "00000014" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+    );
+
+    assert_eq!(
+        SuperBox::from_slice(&jumbf).unwrap_err(),
+        nom::Err::Error(Error::MissingDescriptionBox)
+    );
+
+    let options = ParseOptions::default().set_lenient_description_box(true);
+    let (rem, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox.desc.uuid, &[0; 16]);
+    assert_eq!(sbox.desc.label, None);
+    assert!(sbox.desc.original.is_empty());
+    assert_eq!(sbox.data_box().unwrap().data, b"data");
+}
+
+#[test]
+fn error_empty_super_box() {
+    let jumbf = hex!(
+        "00000008" // box size
+        "6a756d62" // box type = 'jumb'
+    );
+
+    assert_eq!(
+        SuperBox::from_slice(&jumbf).unwrap_err(),
+        nom::Err::Error(Error::MissingDescriptionBox)
+    );
+}
+
+#[test]
+fn error_child_larger_than_super_box() {
+    let jumbf = hex!(
+        "0000002b" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000010" // box size (INCORRECT, larger than superbox)
+            "61626364" // box type = 'abcd'
+            "0102" // payload
+    );
+
+    let err = SuperBox::parse(&jumbf).unwrap_err();
+    assert!(!err.is_truncated());
+    assert_eq!(
+        err,
+        Error::SizeMismatch {
+            offset: 33,
+            declared: 16,
+            available: 10
+        }
+    );
+}
+
+#[test]
+fn error_corrupt_header_in_nested_super_box() {
+    let jumbf = hex!(
+        "00000045" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000024" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000019" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "00" // toggles
+                // ---
+                "000000ff" // box header (INCORRECT, no box type)
+    );
+
+    assert_eq!(
+        SuperBox::parse(&jumbf).unwrap_err(),
+        Error::CorruptHeader { offset: 66 }
+    );
+
+    // The description box is missing its toggles.
+    let jumbf = hex!(
+        "00000041" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "00000020" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000018" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                // toggles (missing)
+    );
+
+    assert_eq!(
+        SuperBox::parse(&jumbf).unwrap_err(),
+        Error::CorruptHeader { offset: 41 }
+    );
+}
+
+#[test]
+fn error_misplaced_description_box() {
+    let jumbf = hex!(
+        "0000003b" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+    );
+
+    assert_eq!(
+        SuperBox::from_slice(&jumbf).unwrap_err(),
+        nom::Err::Error(Error::MisplacedDescriptionBox)
+    );
+
+    let options = ParseOptions::default().set_lenient_description_box(true);
+    let (rem, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox.desc.label, Some("test.superbox"));
+    assert_eq!(sbox.child_boxes.len(), 1);
+    assert_eq!(sbox.data_box().unwrap().data, b"data");
+
+    // The description box is written first, so the output differs from the
+    // input but now parses without lenient options.
+    let new_jumbf = sbox.to_vec();
+    assert_eq!(new_jumbf[..8], jumbf[..8]);
+    assert_eq!(new_jumbf[8..47], jumbf[20..]);
+    assert_eq!(new_jumbf[47..], jumbf[8..20]);
+
+    let (_, new_sbox) = SuperBox::from_slice(&new_jumbf).unwrap();
+    assert_eq!(new_sbox.desc.label, Some("test.superbox"));
+
+    let mut original: Vec<u8> = vec![];
+    sbox.copy_original_to(&mut original).unwrap();
+    assert_eq!(original, jumbf);
+}
+
+#[test]
+fn error_corrupt_box_before_description_box() {
+    let jumbf = hex!(
+        "0000001e" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000010" // box size (INCORRECT, larger than superbox)
+            "61626364" // box type = 'abcd'
+            "0102" // payload
+    );
+
+    assert_eq!(
+        SuperBox::parse(&jumbf).unwrap_err(),
+        Error::SizeMismatch {
+            offset: 20,
+            declared: 16,
+            available: 10
+        }
+    );
+}
+
+#[test]
+fn error_duplicate_description_box() {
+    let jumbf = hex!(
+        "00000057" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "00000028" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "6f746865722e6c6162656c2e783200" // label
+    );
+
+    assert_eq!(
+        SuperBox::from_slice(&jumbf).unwrap_err(),
+        nom::Err::Error(Error::DuplicateDescriptionBox)
+    );
+
+    let options = ParseOptions::default().set_lenient_description_box(true);
+    let (rem, sbox) = SuperBox::from_slice_with_options(&jumbf, &options).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox.desc.label, Some("test.superbox"));
+    assert_eq!(sbox.child_boxes.len(), 1);
+    assert_eq!(sbox.data_box().unwrap().tbox, BoxType(*b"jumd"));
+}
+
+#[test]
+fn verify_hash() {
+    let sbox = SuperBoxBuilder::new(&[0; 16])
+        .set_computed_hash(XorDigest)
+        .add_child_box(DataBoxBuilder::from_borrowed(BoxType(*b"abcd"), b"ABCD"))
+        .add_child_box(SuperBoxBuilder::new(&[1; 16]).set_label("child"));
+
+    let mut jumbf = std::io::Cursor::new(Vec::<u8>::new());
+    sbox.write_jumbf(&mut jumbf).unwrap();
+    let jumbf = jumbf.into_inner();
+
+    let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert_eq!(sbox.verify_hash(&XorDigest), Some(true));
+    assert_eq!(
+        sbox.find_by_label("child").unwrap().verify_hash(&XorDigest),
+        None
+    );
+
+    let ChildBox::DataBox(dbox) = &mut sbox.child_boxes[0] else {
+        panic!("expected data box");
+    };
+    dbox.data = b"ABCE";
+    assert_eq!(sbox.verify_hash(&XorDigest), Some(false));
+}
+
+#[test]
+fn flatten_c2pa_manifest() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let flat = sbox.flatten();
+    let paths: Vec<(String, BoxType)> = flat
+        .iter()
+        .map(|(path, dbox)| (path.to_string(), dbox.tbox))
+        .collect();
+
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    assert_eq!(paths.len(), 7);
+    assert_eq!(
+        paths[0],
+        (
+            format!("{manifest}/c2pa.assertions/c2pa.thumbnail.claim.jpeg"),
+            BoxType(*b"bfdb")
+        )
+    );
+
+    // Every data box can be found again using its path.
+    for (path, dbox) in &flat {
+        let owner = sbox.find_by_label_path(path).unwrap();
+        assert!(owner
+            .child_boxes
+            .iter()
+            .any(|child| matches!(child, ChildBox::DataBox(d) if std::ptr::eq(d, *dbox))));
+    }
+}
+
+#[test]
+fn find_data_containing() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let matches = sbox.find_data_containing(b"c2pa.thumbnail.claim.jpeg");
+    assert!(!matches.is_empty());
+
+    for m in &matches {
+        assert_eq!(
+            &m.data_box.data[m.offset..m.offset + 25],
+            b"c2pa.thumbnail.claim.jpeg"
+        );
+        assert!(sbox.find_by_label_path(&m.path).is_some());
+    }
+
+    assert!(sbox.find_data_containing(b"not in the manifest").is_empty());
+    assert!(sbox.find_data_containing(b"").is_empty());
+}
+
+#[test]
+fn find_data_containing_overlapping() {
+    let jumbf = hex!(
+        "0000002d" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "61616161" // payload = "aaaa"
+    );
+
+    let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    let offsets: Vec<usize> = sbox
+        .find_data_containing(b"aa")
+        .iter()
+        .map(|m| m.offset)
+        .collect();
+
+    assert_eq!(offsets, [0, 1, 2]);
+}
+
+#[test]
+fn find_by_id() {
+    let jumbf = hex!(
+        "000000b5" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000004a" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "04" // toggles
+                "00000001" // id
+                // ---
+                "00000025" // box size
+                "6a756d62" // box type = 'jumb'
+                    "0000001d" // box size
+                    "6a756d64" // box type = 'jumd'
+                    "00000000000000000000000000000000" // UUID
+                    "04" // toggles
+                    "00000002" // id
+            // ---
+            "00000025" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "04" // toggles
+                "00000003" // id
+            // ---
+            "00000025" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001d" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "04" // toggles
+                "00000003" // id
+    );
+
+    let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+
+    assert_eq!(sbox.find_by_id(1).unwrap().original, &jumbf[33..107]);
+    assert_eq!(
+        sbox.find_by_id_recursive(1).unwrap().original,
+        &jumbf[33..107]
+    );
+
+    // Only found when searching recursively.
+    assert_eq!(sbox.find_by_id(2), None);
+    assert_eq!(
+        sbox.find_by_id_recursive(2).unwrap().original,
+        &jumbf[70..107]
+    );
+
+    // Not unique.
+    assert_eq!(sbox.find_by_id(3), None);
+    assert_eq!(sbox.find_by_id_recursive(3), None);
+
+    assert_eq!(sbox.find_by_id(4), None);
+    assert_eq!(sbox.find_by_id_recursive(4), None);
+}
+
+#[test]
+fn children_with_private_box() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let assertions = sbox
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions")
+        .unwrap();
+
+    let salted: Vec<_> = assertions
+        .children_with_private_box(BoxType(*b"c2sh"))
+        .collect();
+    assert!(!salted.is_empty());
+
+    for (assertion, salt) in salted {
+        assert_eq!(salt.tbox, BoxType(*b"c2sh"));
+        assert_eq!(assertion.desc.private.as_ref(), Some(salt));
+    }
+
+    let salted_labels: Vec<&str> = assertions
+        .children_with_private_box(BoxType(*b"c2sh"))
+        .filter_map(|(assertion, _)| assertion.desc.label)
+        .collect();
+    assert!(salted_labels.contains(&"stds.schema-org.CreativeWork"));
+
+    let creative_work = assertions
+        .find_by_label("stds.schema-org.CreativeWork")
+        .unwrap();
+    assert_eq!(
+        creative_work.salt(),
+        creative_work.desc.private.as_ref().map(|salt| salt.data)
+    );
+    assert!(creative_work.salt().is_some_and(|salt| !salt.is_empty()));
+    assert_eq!(assertions.salt(), None);
+    assert_eq!(
+        assertions
+            .children_with_private_box(BoxType(*b"xxxx"))
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn parse() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let sbox = SuperBox::parse(jumbf).unwrap();
+    assert_eq!(sbox.original, jumbf);
+    assert_eq!(SuperBox::try_from(&jumbf[..]).unwrap(), sbox);
+
+    let mut with_trailing_data = jumbf.to_vec();
+    with_trailing_data.extend_from_slice(b"xyz");
+    assert_eq!(
+        SuperBox::parse(&with_trailing_data).unwrap_err(),
+        Error::TrailingData {
+            offset: jumbf.len(),
+            len: 3
+        }
+    );
+
+    let err = SuperBox::parse(&jumbf[..100]).unwrap_err();
+    assert!(err.is_truncated());
+    assert_eq!(
+        err,
+        Error::Truncated {
+            needed: jumbf.len() - 100,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn find_by_label_deep() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let path = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions/stds.schema-org.CreativeWork";
+
+    let (_, full) = SuperBox::from_slice(jumbf).unwrap();
+    let expected = full.find_by_label(path).unwrap();
+
+    // Nothing needs to be re-parsed, so the result is borrowed.
+    let found = full.find_by_label_deep(path).unwrap();
+    assert!(matches!(found, std::borrow::Cow::Borrowed(_)));
+    assert_eq!(*found, *expected);
+
+    let (_, limited) = SuperBox::from_slice_with_depth_limit(jumbf, 1).unwrap();
+    assert!(limited.find_by_label(path).is_none());
+
+    let found = limited.find_by_label_deep(path).unwrap();
+    assert!(matches!(found, std::borrow::Cow::Owned(_)));
+    assert_eq!(found.desc, expected.desc);
+    assert_eq!(found.original, expected.original);
+    assert_eq!(found.child_boxes.len(), expected.child_boxes.len());
+
+    assert!(limited
+        .find_by_label_deep("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/not_there")
+        .is_none());
+}
+
+#[test]
+fn find_by_pattern() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+
+    let paths = |pattern: &str| -> Vec<String> {
+        sbox.find_by_pattern(pattern)
+            .iter()
+            .map(|(path, _)| path.to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        paths("*/c2pa.signature"),
+        [format!("{manifest}/c2pa.signature")]
+    );
+
+    assert_eq!(
+        paths("contentauth:*/c2pa.assertions/c2pa.*"),
+        [
+            format!("{manifest}/c2pa.assertions/c2pa.thumbnail.claim.jpeg"),
+            format!("{manifest}/c2pa.assertions/c2pa.actions"),
+            format!("{manifest}/c2pa.assertions/c2pa.hash.data"),
+        ]
+    );
+
+    assert_eq!(
+        paths("**/c2pa.*.*"),
+        [
+            format!("{manifest}/c2pa.assertions/c2pa.thumbnail.claim.jpeg"),
+            format!("{manifest}/c2pa.assertions/c2pa.hash.data"),
+        ]
+    );
+
+    assert_eq!(
+        paths("**/*.*/**/stds.*"),
+        [format!(
+            "{manifest}/c2pa.assertions/stds.schema-org.CreativeWork"
+        )]
+    );
+
+    // `**` matches this superbox and every descendant, once each.
+    let all = sbox.find_by_pattern("**/**");
+    assert_eq!(all.len(), 9);
+    assert!(all[0].0.is_empty());
+    assert_eq!(*all[0].1, sbox);
+
+    // Each match can be found again using its path.
+    for (path, found) in &all[1..] {
+        assert!(std::ptr::eq(sbox.find_by_label_path(path).unwrap(), *found));
+    }
+
+    assert!(paths("c2pa.*").is_empty());
+    assert!(paths("*/c2pa.claim%2A").is_empty());
+}
+
+#[test]
+fn find_with_predicate() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    // Assertions that carry a C2PA salt.
+    let salted = sbox.find_all(|desc| desc.private.is_some());
+    assert_eq!(salted.len(), 1);
+    assert_eq!(salted[0].desc.label, Some("stds.schema-org.CreativeWork"));
+
+    let claim = sbox.find(|desc| desc.label == Some("c2pa.claim")).unwrap();
+    assert!(std::ptr::eq(
+        claim,
+        sbox.find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim")
+            .unwrap()
+    ));
+
+    // Every superbox except the top-level one, in document order.
+    let all = sbox.find_all(|_| true);
+    assert_eq!(all.len(), 8);
+    assert_eq!(
+        sbox.find(|_| true).map(|found| found.desc.label),
+        Some(all[0].desc.label)
+    );
+
+    assert!(sbox.find(|desc| desc.id == Some(42)).is_none());
+    assert!(sbox.find_all(|desc| !desc.requestable).is_empty());
+}
+
+#[test]
+fn child_box_into() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+    let expected = sbox.clone();
+
+    // Take the tree apart down to the claim's data box without cloning.
+    let manifest = sbox
+        .child_boxes
+        .into_iter()
+        .next()
+        .unwrap()
+        .into_super_box()
+        .unwrap();
+
+    let claim = manifest
+        .child_boxes
+        .into_iter()
+        .find_map(|child| {
+            child
+                .into_super_box()
+                .ok()
+                .filter(|sbox| sbox.desc.label == Some("c2pa.claim"))
+        })
+        .unwrap();
+
+    let mut claim_children = claim.child_boxes.into_iter();
+    let cbor = claim_children.next().unwrap();
+
+    // The wrong conversion returns the box unchanged.
+    let cbor = cbor.into_super_box().unwrap_err();
+    let cbor = cbor.into_data_box().unwrap();
+    assert_eq!(
+        Some(&cbor),
+        expected
+            .find_data_box("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim")
+    );
+}
+
+#[test]
+fn rename_label() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, mut sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    let manifest = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9";
+    let old_path = format!("{manifest}/c2pa.assertions/c2pa.actions");
+
+    let new_path = sbox.rename_label(&old_path, "c2pa.actions.v2").unwrap();
+    assert_eq!(
+        new_path.to_string(),
+        format!("{manifest}/c2pa.assertions/c2pa.actions.v2")
+    );
+    assert!(sbox.find_by_label(&old_path).is_none());
+
+    // The claim refers to the assertion by its old label.
+    let references = sbox.references_to(&old_path);
+    for m in &references {
+        assert_eq!(m.path.to_string(), format!("{manifest}/c2pa.claim"));
+        assert!(m.data_box.data[m.offset..].starts_with(b"c2pa.actions"));
+    }
+    assert!(!references.is_empty());
+
+    // Sizes are recomputed when the tree is serialized.
+    let new_jumbf = sbox.to_vec();
+    assert_eq!(new_jumbf.len(), jumbf.len() + 3);
+
+    let (rem, new_sbox) = SuperBox::from_slice(&new_jumbf).unwrap();
+    assert!(rem.is_empty());
+    assert!(new_sbox.verify_structure().is_valid());
+    let renamed = new_sbox.find_by_label_path(&new_path).unwrap();
+    assert_eq!(renamed.desc.label, Some("c2pa.actions.v2"));
+    assert_eq!(renamed.to_vec(), {
+        sbox.find_by_label_path(&new_path).unwrap().to_vec()
+    });
+
+    // Nothing is changed if the path or label is unusable.
+    assert!(sbox.rename_label(&old_path, "c2pa.actions.v3").is_none());
+    assert!(sbox
+        .rename_label(manifest, "contentauth:urn:uuid:\0")
+        .is_none());
+    assert_eq!(sbox.to_vec(), new_jumbf);
+
+    assert!(sbox.references_to("").is_empty());
+}
+
+#[test]
+fn summary_json() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let (_, sbox) = SuperBox::from_slice(jumbf).unwrap();
+
+    assert_eq!(
+        sbox.summary_json(),
+        concat!(
+            r#"{"label":"c2pa","uuid":"6332706100110010800000aa00389b71","#,
+            r#""content_type":"c2pa manifest store","size":46948,"children":["#,
+            r#"{"label":"contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9","#,
+            r#""uuid":"63326d6100110010800000aa00389b71","#,
+            r#""content_type":"c2pa manifest","size":46910,"children":3}]}"#
+        )
+    );
+
+    let manifest = sbox
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9")
+        .unwrap();
+
+    assert_eq!(
+        manifest.summary_json(),
+        concat!(
+            r#"{"label":"contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9","#,
+            r#""uuid":"63326d6100110010800000aa00389b71","#,
+            r#""content_type":"c2pa manifest","size":46910,"children":["#,
+            r#"{"label":"c2pa.assertions","uuid":"6332617300110010800000aa00389b71","#,
+            r#""content_type":"c2pa assertion store","size":32353,"children":4},"#,
+            r#"{"label":"c2pa.claim","uuid":"6332636c00110010800000aa00389b71","#,
+            r#""content_type":"c2pa claim","size":684,"children":1},"#,
+            r#"{"label":"c2pa.signature","uuid":"6332637300110010800000aa00389b71","#,
+            r#""content_type":"c2pa claim signature","size":13782,"children":1}]}"#
+        )
+    );
+}
+
+#[test]
+fn summary_json_escaping() {
+    let jumbf = hex!(
+        "0000002d" // box size
+        "6a756d62" // box type = 'jumb'
+            "0000001d" // box size
+            "6a756d64" // box type = 'jumd'
+            "11111111111111111111111111111111" // UUID
+            "03" // toggles
+            "61226200" // label = 'a"b'
+            // ---
+            "00000008" // box size
+            "00010203" // box type (not printable)
+    );
+
+    let sbox = SuperBox::parse(&jumbf).unwrap();
+
+    assert_eq!(
+        sbox.summary_json(),
+        concat!(
+            r#"{"label":"a\"b","uuid":"11111111111111111111111111111111","size":45,"#,
+            r#""children":[{"box":"0x00010203","size":8}]}"#
+        )
+    );
+}
+
+#[test]
+fn parse_path() {
+    let jumbf = include_bytes!("../fixtures/C.c2pa");
+    let full = SuperBox::parse(jumbf).unwrap();
+
+    let label = "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions";
+    let assertions = SuperBox::parse_path(jumbf, label).unwrap().unwrap();
+    assert_eq!(&assertions, full.find_by_label(label).unwrap());
+
+    assert_eq!(SuperBox::parse_path(jumbf, "no.such.box").unwrap(), None);
+}
+
+#[test]
+fn parse_path_skips_siblings() {
+    let jumbf = hex!(
+        "0000007a" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles
+            // ---
+            "0000002b" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001b" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "6100" // label = "a"
+                "00000002" // box size (reserved value)
+                "66726565" // box type = 'free'
+            // ---
+            "0000002e" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001b" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "6200" // label = "b"
+                // ---
+                "0000000b" // box size
+                "6a736f6e" // box type = 'json'
+                "7b7d0a" // payload = {}
+    );
+
+    assert_eq!(SuperBox::parse(&jumbf), Err(Error::InvalidBoxLength(2)));
+
+    let b = SuperBox::parse_path(&jumbf, "b").unwrap().unwrap();
+    assert_eq!(b.data_box().unwrap().data, b"{}\n");
+
+    assert_eq!(
+        SuperBox::parse_path(&jumbf, "a"),
+        Err(Error::InvalidBoxLength(2))
+    );
+}
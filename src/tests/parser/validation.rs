@@ -0,0 +1,313 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    builder::{DataBoxBuilder, SuperBoxBuilder},
+    parser::{ChildBox, ParseOptions, SuperBox, ValidationIssue, ValidationReport},
+    BoxType,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn valid_c2pa_manifest() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    let report = sbox.verify_all();
+    assert!(report.is_valid());
+    assert_eq!(report, ValidationReport::default());
+}
+
+#[test]
+fn duplicate_and_unlabeled() {
+    let jumbf = hex!(
+        "0000009c" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "01" // toggles (requestable, no label)
+            // ---
+            "00000029" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000021" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "7369626c696e6700" // label = "sibling"
+            // ---
+            "00000029" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000021" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "7369626c696e6700" // label = "sibling"
+            // ---
+            "00000029" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000021" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "02" // toggles (not requestable)
+                "7369626c696e6700" // label = "sibling"
+    );
+
+    let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(sbox.find_by_label("sibling"), None);
+
+    let report = sbox.verify_all();
+    assert!(!report.is_valid());
+
+    assert_eq!(
+        report.issues,
+        vec![
+            ValidationIssue::UnlabeledRequestableBox { location: vec![] },
+            ValidationIssue::DuplicateLabel {
+                location: vec![],
+                label: "sibling".to_owned(),
+                count: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn duplicate_labels_in_document_order() {
+    let jumbf = hex!(
+        "000000bb" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000019" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "00" // toggles (not requestable, no label)
+            // ---
+            "00000026" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001e" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "7a65746100" // label = "zeta"
+            // ---
+            "00000027" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001f" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "616c70686100" // label = "alpha"
+            // ---
+            "00000027" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001f" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "616c70686100" // label = "alpha"
+            // ---
+            "00000026" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001e" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "03" // toggles
+                "7a65746100" // label = "zeta"
+    );
+
+    let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+
+    let report = sbox.verify_all();
+
+    assert_eq!(
+        report.issues,
+        vec![
+            ValidationIssue::DuplicateLabel {
+                location: vec![],
+                label: "zeta".to_owned(),
+                count: 2,
+            },
+            ValidationIssue::DuplicateLabel {
+                location: vec![],
+                label: "alpha".to_owned(),
+                count: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn structure_of_c2pa_manifest() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(sbox.verify_structure(), ValidationReport::default());
+}
+
+#[test]
+fn structure_gap_after_recovery() {
+    let jumbf = hex!(
+        "00000043" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000002" // (INVALID) box size
+            "61626364" // box type = 'abcd'
+    );
+
+    let (_, (sbox, _errors)) =
+        SuperBox::from_slice_with_recovery(&jumbf, &ParseOptions::default()).unwrap();
+
+    assert_eq!(
+        sbox.verify_structure().issues,
+        vec![ValidationIssue::UncoveredBytes {
+            location: vec![],
+            offset: 59,
+            len: 8,
+        }]
+    );
+}
+
+#[test]
+fn structure_overlap_and_size_mismatch() {
+    let jumbf = hex!(
+        "00000043" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e7375706572626f7800" // label
+            // ---
+            "0000000c" // box size
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+            // ---
+            "00000008" // box size
+            "61626364" // box type = 'abcd'
+    );
+
+    let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(sbox.verify_structure().is_valid());
+
+    // Simulate a tree that has been edited inconsistently: the first data
+    // box appears twice and the second box claims more bytes than it has.
+    let first = sbox.child_boxes[0].clone();
+    sbox.child_boxes.push(first);
+
+    let crate::parser::ChildBox::DataBox(last) = &mut sbox.child_boxes[1] else {
+        panic!("expected a data box");
+    };
+    last.original = &jumbf[59..65];
+
+    assert_eq!(
+        sbox.verify_structure().issues,
+        vec![
+            ValidationIssue::OverlappingBoxes {
+                location: vec![],
+                offset: 47,
+                len: 12,
+            },
+            ValidationIssue::BoxSizeMismatch {
+                location: vec![],
+                offset: 59,
+                declared: Some(8),
+                actual: 6,
+            },
+            ValidationIssue::UncoveredBytes {
+                location: vec![],
+                offset: 65,
+                len: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn hash_without_single_data_box() {
+    let data = || DataBoxBuilder::from_owned(BoxType(*b"abcd"), b"data".to_vec());
+    let hashed = || {
+        SuperBoxBuilder::new(&[0u8; 16])
+            .set_label("hashed")
+            .set_sha256_hash(&[0u8; 32])
+    };
+
+    let outer = SuperBoxBuilder::new(&[0u8; 16])
+        .add_child_box(hashed().add_child_box(data()))
+        .add_child_box(hashed().set_label("empty"))
+        .add_child_box(
+            hashed()
+                .set_label("two")
+                .add_child_box(data())
+                .add_child_box(data()),
+        );
+
+    let mut jumbf = vec![];
+    outer
+        .write_jumbf(&mut std::io::Cursor::new(&mut jumbf))
+        .unwrap();
+
+    let (_, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+
+    assert_eq!(
+        sbox.verify_all().issues,
+        vec![
+            ValidationIssue::AmbiguousHash {
+                location: vec![1],
+                data_box_count: 0,
+            },
+            ValidationIssue::AmbiguousHash {
+                location: vec![2],
+                data_box_count: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn structure_child_before_root() {
+    let jumbf = hex!(
+        "00000029" // box size
+        "6a756d62" // box type = 'jumb'
+            "00000021" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "7369626c696e6700" // label = "sibling"
+    );
+
+    // Graft a superbox which starts 10 bytes before the root's input. Its
+    // offset wraps around, so its end offset doesn't fit in a usize.
+    let (_, child) = SuperBox::from_slice(&jumbf).unwrap();
+    let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    sbox.original = &jumbf[10..];
+    sbox.child_boxes = vec![ChildBox::SuperBox(child)];
+
+    let issue = ValidationIssue::BoxOutsideParent {
+        location: vec![],
+        offset: usize::MAX - 9,
+    };
+
+    let report = sbox.verify_structure();
+    assert_eq!(report.issues.iter().filter(|i| **i == issue).count(), 1);
+}
@@ -0,0 +1,252 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+use pretty_assertions_sorted::assert_eq;
+
+use crate::{
+    digest::Digest,
+    parser::{ChildBox, DataBox, SuperBox},
+    tests::digest::XorDigest,
+    BoxType, Toggles,
+};
+
+const C2PA_MANIFEST_STORE: &[u8; 46948] = include_bytes!("../fixtures/C.c2pa");
+
+#[test]
+fn unmodified_c2pa_manifest() {
+    let (_, sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(sbox.to_vec(), C2PA_MANIFEST_STORE.to_vec());
+    assert_eq!(sbox.serialized_len(), C2PA_MANIFEST_STORE.len());
+}
+
+#[test]
+fn unmodified_with_depth_limit() {
+    let (_, sbox) = SuperBox::from_slice_with_depth_limit(C2PA_MANIFEST_STORE, 1).unwrap();
+    assert_eq!(sbox.to_vec(), C2PA_MANIFEST_STORE.to_vec());
+}
+
+#[test]
+fn modified_label_leaves_siblings_intact() {
+    let (_, mut sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let ChildBox::SuperBox(manifest) = &mut sbox.child_boxes[0] else {
+        panic!("expected manifest superbox");
+    };
+    let ChildBox::SuperBox(claim) = &mut manifest.child_boxes[1] else {
+        panic!("expected claim superbox");
+    };
+    claim.desc.label = Some("c2pa.claim.v2");
+
+    let jumbf = sbox.to_vec();
+    assert_eq!(jumbf.len(), C2PA_MANIFEST_STORE.len() + 3);
+    assert_eq!(sbox.serialized_len(), jumbf.len());
+
+    // Assertion store is unchanged.
+    assert_eq!(jumbf[129..32482], C2PA_MANIFEST_STORE[129..32482]);
+
+    let (rem, new_sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert!(rem.is_empty());
+
+    let claim = new_sbox
+        .find_by_label("contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.claim.v2")
+        .unwrap();
+    assert_eq!(
+        claim.data_box().unwrap().original,
+        &C2PA_MANIFEST_STORE[32526..33166]
+    );
+}
+
+#[test]
+fn preserves_xlbox_and_reserved_toggles() {
+    let jumbf = hex!(
+        "00000001" // box size (see XLBox)
+        "6a756d62" // box type = 'jumb'
+        "0000000000000047" // XLBox size
+            "00000027" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "83" // toggles (with reserved bit)
+            "746573742e7375706572626f7800" // label
+            // ---
+            "00000001" // box size (see XLBox)
+            "61626364" // box type = 'abcd'
+            "0000000000000010" // XLBox size
+    );
+
+    let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert_eq!(sbox.to_vec(), jumbf.to_vec());
+    assert_eq!(
+        sbox.desc.toggles(),
+        Toggles::REQUESTABLE | Toggles::HAS_LABEL | Toggles::from_bits(0x80)
+    );
+
+    sbox.desc.label = Some("x");
+    let ChildBox::DataBox(dbox) = &mut sbox.child_boxes[0] else {
+        panic!("expected data box");
+    };
+    dbox.data = b"data";
+
+    let expected_jumbf = hex!(
+        "00000001" // box size (see XLBox)
+        "6a756d62" // box type = 'jumb'
+        "000000000000003f" // XLBox size
+            "0000001b" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "83" // toggles (with reserved bit)
+            "7800" // label
+            // ---
+            "00000001" // box size (see XLBox)
+            "61626364" // box type = 'abcd'
+            "0000000000000014" // XLBox size
+            "64617461" // payload
+    );
+
+    assert_eq!(sbox.to_vec(), expected_jumbf.to_vec());
+    assert_eq!(sbox.serialized_len(), expected_jumbf.len());
+    assert_eq!(
+        sbox.child_boxes[0].as_data_box().unwrap().serialized_len(),
+        20
+    );
+}
+
+#[test]
+fn preserves_size_0_box() {
+    let jumbf = hex!(
+        "00000000" // box size (to end of data)
+        "6a756d62" // box type = 'jumb'
+            "00000026" // box size
+            "6a756d64" // box type = 'jumd'
+            "00000000000000000000000000000000" // UUID
+            "03" // toggles
+            "746573742e64617461626f7800" // label
+            // ---
+            "00000000" // box size (to end of data)
+            "61626364" // box type = 'abcd'
+            "64617461" // payload
+    );
+
+    let (_, mut sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert_eq!(sbox.to_vec(), jumbf.to_vec());
+
+    let ChildBox::DataBox(dbox) = &mut sbox.child_boxes[0] else {
+        panic!("expected data box");
+    };
+    dbox.data = b"new data";
+
+    let jumbf = sbox.to_vec();
+    let (_, new_sbox) = SuperBox::from_slice(&jumbf).unwrap();
+    assert_eq!(new_sbox.data_box().unwrap().data, b"new data");
+    assert_eq!(new_sbox.original[0..4], [0, 0, 0, 0]);
+}
+
+#[test]
+fn data_box_built_without_original() {
+    let dbox = DataBox {
+        tbox: BoxType(*b"abcd"),
+        data: b"data",
+        original: &[],
+    };
+
+    assert_eq!(dbox.to_vec(), hex!("0000000c6162636464617461").to_vec());
+    assert_eq!(dbox.serialized_len(), 12);
+}
+
+#[test]
+fn copy_original_to() {
+    let (_, mut sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+
+    let ChildBox::SuperBox(manifest) = &mut sbox.child_boxes[0] else {
+        panic!("expected manifest superbox");
+    };
+    let ChildBox::SuperBox(claim) = &mut manifest.child_boxes[1] else {
+        panic!("expected claim superbox");
+    };
+    claim.desc.label = Some("c2pa.claim.v2");
+
+    let ChildBox::DataBox(claim_data) = &mut claim.child_boxes[0] else {
+        panic!("expected claim data box");
+    };
+    claim_data.data = b"new data";
+
+    let mut extracted: Vec<u8> = vec![];
+    claim_data.copy_original_to(&mut extracted).unwrap();
+    assert_eq!(extracted, &C2PA_MANIFEST_STORE[32526..33166]);
+
+    let mut extracted: Vec<u8> = vec![];
+    sbox.copy_original_to(&mut extracted).unwrap();
+    assert_eq!(extracted, C2PA_MANIFEST_STORE.to_vec());
+}
+
+#[test]
+fn copy_to_vec() {
+    let mut buf = b"stale contents".to_vec();
+
+    let child_count = {
+        let input = C2PA_MANIFEST_STORE.to_vec();
+        let (_, sbox) = SuperBox::from_slice(&input).unwrap();
+
+        let mut assertions = sbox
+            .find_by_label(
+                "contentauth:urn:uuid:021b555e-5e02-4074-b444-43d7919d89b9/c2pa.assertions",
+            )
+            .unwrap()
+            .clone();
+        assertions.desc.label = Some("renamed.assertions");
+
+        let detached = assertions.copy_to_vec(&mut buf).unwrap();
+        assert_eq!(detached, assertions.copy_to_vec(&mut vec![]).unwrap());
+        assert_eq!(detached.original.len(), assertions.serialized_len());
+        detached.child_boxes.len()
+    };
+
+    // The copy is entirely independent of the original input.
+    let (rem, sbox) = SuperBox::from_slice(&buf).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(sbox.desc.label, Some("renamed.assertions"));
+    assert_eq!(sbox.child_boxes.len(), child_count);
+}
+
+#[test]
+fn digest_uses_original() {
+    let (_, mut sbox) = SuperBox::from_slice(C2PA_MANIFEST_STORE).unwrap();
+    assert_eq!(
+        sbox.digest(&XorDigest),
+        XorDigest.digest(C2PA_MANIFEST_STORE)
+    );
+
+    let ChildBox::SuperBox(manifest) = &mut sbox.child_boxes[0] else {
+        panic!("expected manifest superbox");
+    };
+    let ChildBox::SuperBox(claim) = &mut manifest.child_boxes[1] else {
+        panic!("expected claim superbox");
+    };
+    let ChildBox::DataBox(claim_data) = &mut claim.child_boxes[0] else {
+        panic!("expected claim data box");
+    };
+    claim_data.data = b"new data";
+
+    assert_eq!(
+        claim_data.digest(&XorDigest),
+        XorDigest.digest(&C2PA_MANIFEST_STORE[32526..33166])
+    );
+    assert_ne!(
+        claim_data.digest(&XorDigest),
+        XorDigest.digest(&claim_data.to_vec())
+    );
+
+    claim.desc.label = Some("c2pa.claim.v2");
+    assert_eq!(claim.digest(&XorDigest), XorDigest.digest(claim.original));
+    assert_ne!(claim.digest(&XorDigest), XorDigest.digest(&claim.to_vec()));
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use proptest::prelude::*;
+
+use crate::{
+    parser::SuperBox,
+    strategy::{jumbf_bytes, near_valid_jumbf, super_box_model, TreeConfig},
+};
+
+fn depth(sbox: &SuperBox) -> u32 {
+    sbox.child_boxes
+        .iter()
+        .filter_map(|c| c.as_super_box())
+        .map(|c| depth(c) + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+proptest! {
+    #[test]
+    fn generated_jumbf_parses(jumbf in jumbf_bytes(TreeConfig::default())) {
+        let (rem, sbox) = SuperBox::from_slice(&jumbf).unwrap();
+        prop_assert!(rem.is_empty());
+        prop_assert!(depth(&sbox) <= TreeConfig::default().max_depth);
+    }
+
+    #[test]
+    fn generated_model_respects_limits(sbox in super_box_model(TreeConfig {
+        max_depth: 1,
+        max_children: 2,
+        max_payload_len: 4,
+        max_label_len: 3,
+    })) {
+        prop_assert!(sbox.child_boxes.len() <= 2);
+        prop_assert!(sbox.label.map_or(0, |l| l.chars().count()) <= 3);
+    }
+
+    #[test]
+    fn near_valid_jumbf_does_not_panic(jumbf in near_valid_jumbf(TreeConfig::default())) {
+        let _ = SuperBox::from_slice(&jumbf);
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+use crate::test_utils::{assert_read_errors_propagate, FlakyReader, InjectedError, ShortReader};
+
+#[test]
+fn flaky_reader() {
+    let mut reader = FlakyReader::new(Cursor::new(b"abcdef"), 4);
+    let mut buf = [0u8; 6];
+
+    // A read which spans the failure offset stops short of it.
+    assert_eq!(reader.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"abcd");
+    assert!(!reader.has_failed());
+
+    let err = reader.read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(
+        InjectedError::find(&err),
+        Some(&InjectedError { offset: 4 })
+    );
+    assert_eq!(err.to_string(), "injected read failure at offset 4");
+    assert!(reader.has_failed());
+
+    // Reads after the failure offset succeed.
+    reader.seek(SeekFrom::Start(5)).unwrap();
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], b'f');
+}
+
+#[test]
+fn short_reader() {
+    let mut reader = ShortReader::new(Cursor::new(b"abcdef"), 0);
+    let mut buf = [0u8; 6];
+
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    reader.read_exact(&mut buf[1..]).unwrap();
+    assert_eq!(&buf, b"abcdef");
+}
+
+#[test]
+fn swallowed_error_is_detected() {
+    let result = std::panic::catch_unwind(|| {
+        assert_read_errors_propagate(b"abcd", |reader| {
+            let mut buf = vec![];
+            let _ = reader.read_to_end(&mut buf);
+            Ok(())
+        })
+    });
+
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "parser")]
+mod layout {
+    use std::io::Cursor;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::{
+        parser::layout::BoxLayout,
+        test_utils::{assert_read_errors_propagate, ShortReader},
+    };
+
+    const C2PA_MANIFEST_STORE: &[u8] = include_bytes!("fixtures/C.c2pa");
+
+    #[test]
+    fn errors_propagate() {
+        let failures = assert_read_errors_propagate(C2PA_MANIFEST_STORE, |reader| {
+            BoxLayout::from_reader(reader)
+        });
+
+        // Box headers and labels are read, but payloads are skipped.
+        assert!(failures > 0);
+        assert!(failures < C2PA_MANIFEST_STORE.len());
+    }
+
+    #[test]
+    fn short_reads() {
+        let expected = BoxLayout::from_reader(&mut Cursor::new(C2PA_MANIFEST_STORE)).unwrap();
+
+        let mut reader = ShortReader::new(Cursor::new(C2PA_MANIFEST_STORE), 3);
+        assert_eq!(BoxLayout::from_reader(&mut reader).unwrap(), expected);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use hex_literal::hex;
+
+use crate::testkit;
+
+#[test]
+fn empty_super_box() {
+    assert_eq!(
+        testkit::empty_super_box().unwrap(),
+        hex!(
+            "00000021" // box size
+            "6a756d62" // box type = 'jumb'
+                "00000019" // box size
+                "6a756d64" // box type = 'jumd'
+                "00000000000000000000000000000000" // UUID
+                "00" // toggles
+        )
+    );
+}
+
+#[test]
+fn json_super_box() {
+    assert_eq!(
+        testkit::json_super_box("a", b"{}").unwrap(),
+        hex!(
+            "0000002d" // box size
+            "6a756d62" // box type = 'jumb'
+                "0000001b" // box size
+                "6a756d64" // box type = 'jumd'
+                "6a736f6e00110010800000aa00389b71" // UUID
+                "03" // toggles
+                "6100" // label = "a"
+                // ---
+                "0000000a" // box size
+                "6a736f6e" // box type = 'json'
+                "7b7d" // payload = {}
+        )
+    );
+}
+
+#[test]
+fn deterministic() {
+    assert_eq!(
+        testkit::nested_labels(2, 3).unwrap(),
+        testkit::nested_labels(2, 3).unwrap()
+    );
+    assert_eq!(
+        testkit::c2pa_manifest_store("m").unwrap(),
+        testkit::c2pa_manifest_store("m").unwrap()
+    );
+}
+
+#[cfg(feature = "parser")]
+mod parsed {
+    use crate::{
+        content_type,
+        parser::{ChildBox, SuperBox},
+        testkit,
+    };
+
+    #[test]
+    fn nested_labels() {
+        let jumbf = testkit::nested_labels(3, 2).unwrap();
+        let sbox = SuperBox::parse(&jumbf).unwrap();
+        assert_eq!(sbox.desc.label, Some("root"));
+        assert!(sbox.verify_structure().is_valid());
+
+        let leaves = sbox.find_by_pattern("**");
+        assert_eq!(leaves.len(), 1 + 2 + 4 + 8);
+
+        for (path, leaf) in leaves.iter().filter(|(path, _)| path.len() == 3) {
+            let expected = format!("{:?}", path.to_string());
+            assert_eq!(leaf.data_box().unwrap().data, expected.as_bytes());
+        }
+
+        let jumbf = testkit::nested_labels(0, 5).unwrap();
+        let sbox = SuperBox::parse(&jumbf).unwrap();
+        assert_eq!(sbox.data_box().unwrap().data, b"\"root\"");
+    }
+
+    #[test]
+    fn c2pa_manifest_store() {
+        let label = "urn:uuid:00000000-0000-0000-0000-000000000000";
+        let jumbf = testkit::c2pa_manifest_store(label).unwrap();
+
+        let store = SuperBox::parse(&jumbf).unwrap();
+        assert!(store.verify_structure().is_valid());
+        assert_eq!(store.desc.label, Some("c2pa"));
+        assert_eq!(
+            store.desc.content_type(),
+            Some(&content_type::C2PA_MANIFEST_STORE)
+        );
+
+        let manifest = store.find_by_label(label).unwrap();
+        assert_eq!(
+            manifest.desc.content_type(),
+            Some(&content_type::C2PA_MANIFEST)
+        );
+
+        let labels: Vec<_> = manifest
+            .child_boxes
+            .iter()
+            .map(|child| match child {
+                ChildBox::SuperBox(sbox) => sbox.desc.label.unwrap(),
+                ChildBox::DataBox(_) => panic!("unexpected data box"),
+            })
+            .collect();
+        assert_eq!(labels, ["c2pa.assertions", "c2pa.claim", "c2pa.signature"]);
+
+        let hash = store
+            .find_by_label(&format!("{label}/c2pa.assertions/c2pa.hash.data"))
+            .unwrap();
+        assert_eq!(hash.data_box().unwrap().data, [0xa0]);
+
+        let claim = manifest.find_by_label("c2pa.claim").unwrap();
+        let claim = claim.data_box().unwrap();
+        let uri = format!("self#jumbf=/c2pa/{label}/c2pa.assertions/c2pa.hash.data");
+        assert!(claim.data.ends_with(uri.as_bytes()));
+
+        #[cfg(feature = "c2pa")]
+        assert!(crate::parser::c2pa::check_references(&store).is_empty());
+    }
+
+    #[test]
+    fn long_manifest_label() {
+        // The assertion URI is too long for a 2-byte CBOR length.
+        let label = "m".repeat(70_000);
+        let jumbf = testkit::c2pa_manifest_store(&label).unwrap();
+
+        let store = SuperBox::parse(&jumbf).unwrap();
+        let claim = store.find_by_label(&format!("{label}/c2pa.claim")).unwrap();
+        let claim = claim.data_box().unwrap();
+
+        let uri = format!("self#jumbf=/c2pa/{label}/c2pa.assertions/c2pa.hash.data");
+        let mut header = vec![0x7a];
+        header.extend_from_slice(&(uri.len() as u32).to_be_bytes());
+
+        let (text_header, text) = claim.data[18..].split_at(5);
+        assert_eq!(text_header, header);
+        assert_eq!(text, uri.as_bytes());
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::Toggles;
+
+#[test]
+fn bit_operations() {
+    let mut t = Toggles::REQUESTABLE | Toggles::HAS_ID;
+    assert_eq!(t.bits(), 0x05);
+    assert!(t.contains(Toggles::HAS_ID));
+    assert!(!t.contains(Toggles::HAS_ID | Toggles::HAS_HASH));
+
+    t.insert(Toggles::HAS_HASH);
+    t.remove(Toggles::REQUESTABLE);
+    assert_eq!(t, Toggles::HAS_ID | Toggles::HAS_HASH);
+
+    t.set(Toggles::HAS_ID, false);
+    assert_eq!(t & Toggles::HAS_ID, Toggles::empty());
+    assert!(Toggles::default().is_empty());
+    assert_eq!((!Toggles::RESERVED).bits(), 0x1f);
+}
+
+#[test]
+fn impl_display() {
+    assert_eq!(Toggles::empty().to_string(), "");
+    assert_eq!(
+        Toggles::from_bits(0x1f).to_string(),
+        "REQUESTABLE | HAS_LABEL | HAS_ID | HAS_HASH | HAS_PRIVATE_BOX"
+    );
+    assert_eq!(
+        Toggles::from_bits(0x83).to_string(),
+        "REQUESTABLE | HAS_LABEL | 0x80"
+    );
+    assert_eq!(Toggles::from_bits(0x40).to_string(), "0x40");
+    assert_eq!(format!("{:?}", Toggles::HAS_HASH), "Toggles(HAS_HASH)");
+}
+
+#[test]
+fn impl_from_str() {
+    for bits in 0..=255u8 {
+        let t = Toggles::from_bits(bits);
+        assert_eq!(t.to_string().parse::<Toggles>().unwrap(), t);
+    }
+
+    assert_eq!(
+        " HAS_ID|REQUESTABLE ".parse::<Toggles>().unwrap().bits(),
+        0x05
+    );
+    assert_eq!(
+        "0x08 | HAS_HASH".parse::<Toggles>().unwrap(),
+        Toggles::HAS_HASH
+    );
+
+    let err = "HAS_LABEL | BOGUS".parse::<Toggles>().unwrap_err();
+    assert_eq!(err.to_string(), "unrecognized toggle \"BOGUS\"");
+
+    assert!("0xzz".parse::<Toggles>().is_err());
+}
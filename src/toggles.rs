@@ -11,24 +11,216 @@
 // specific language governing permissions and limitations under
 // each license.
 
-//! Toggles in a JUMBF description box describe which additional
-//! information is present in the description
+use std::{
+    fmt::{Debug, Display, Formatter},
+    ops::{BitAnd, BitOr, BitOrAssign, Not},
+    str::FromStr,
+};
 
-/// Toggle bit 0 (0x01) indicates that this superbox can be requested
-/// via URI requests.
-pub(crate) const REQUESTABLE: u8 = 0x01;
+/// Toggles in a JUMBF description box describe which additional
+/// information is present in the description.
+///
+/// This is a set of bit flags. Combine flags with the `|` operator and test
+/// for them with [`contains()`].
+///
+/// `Toggles` can be formatted and parsed as a list of flag names separated
+/// by `|` (i.e. `REQUESTABLE | HAS_LABEL`). Bits which don't correspond to
+/// a named flag are formatted as a hexadecimal value (i.e. `0x80`).
+///
+/// ## Example
+///
+/// ```
+/// use jumbf::Toggles;
+///
+/// let toggles = Toggles::REQUESTABLE | Toggles::HAS_LABEL;
+/// assert_eq!(toggles.bits(), 0x03);
+/// assert!(toggles.contains(Toggles::HAS_LABEL));
+/// assert_eq!(toggles.to_string(), "REQUESTABLE | HAS_LABEL");
+///
+/// let parsed: Toggles = "HAS_LABEL | REQUESTABLE".parse().unwrap();
+/// assert_eq!(parsed, toggles);
+/// ```
+///
+/// [`contains()`]: Self::contains()
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub struct Toggles(u8);
 
-/// Toggle bit 1 (0x02) indicates that the label has an optional textual label.
-pub(crate) const HAS_LABEL: u8 = 0x02;
+impl Toggles {
+    /// Toggle bit 3 (0x08) indicates that a SHA-256 hash of the superbox's
+    /// data box is present.
+    pub const HAS_HASH: Self = Self(0x08);
+    /// Toggle bit 2 (0x04) indicates that the label has an optional
+    /// application-specific 32-bit identifier.
+    pub const HAS_ID: Self = Self(0x04);
+    /// Toggle bit 1 (0x02) indicates that the label has an optional textual
+    /// label.
+    pub const HAS_LABEL: Self = Self(0x02);
+    /// Toggle bit 4 (0x10) indicates that an application-specific "private"
+    /// box is contained within the description box.
+    pub const HAS_PRIVATE_BOX: Self = Self(0x10);
+    const NAMED: [(&'static str, Self); 5] = [
+        ("REQUESTABLE", Self::REQUESTABLE),
+        ("HAS_LABEL", Self::HAS_LABEL),
+        ("HAS_ID", Self::HAS_ID),
+        ("HAS_HASH", Self::HAS_HASH),
+        ("HAS_PRIVATE_BOX", Self::HAS_PRIVATE_BOX),
+    ];
+    /// Toggle bit 0 (0x01) indicates that this superbox can be requested
+    /// via URI requests.
+    pub const REQUESTABLE: Self = Self(0x01);
+    /// Toggle bits 5 through 7 are reserved for future use.
+    pub const RESERVED: Self = Self(0xe0);
 
-/// Toggle bit 2 (0x04) indicates that the label has an optional
-/// application-specific 32-bit identifier.
-pub(crate) const HAS_ID: u8 = 0x04;
+    /// Returns an empty set of toggles.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
 
-/// Toggle bit 3 (0x08) indicates that a SHA-256 hash of the superbox's
-/// data box is present.
-pub(crate) const HAS_HASH: u8 = 0x08;
+    /// Create a set of toggles from a raw toggles byte.
+    ///
+    /// All bits are preserved, including reserved bits.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
 
-/// Toggle bit 4 (0x10) indicates that an application-specific "private"
-/// box is contained within the description box.
-pub(crate) const HAS_PRIVATE_BOX: u8 = 0x10;
+    /// Returns the raw toggles byte.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if no toggles are set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if all of the toggles in `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set all of the toggles in `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Clear all of the toggles in `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Set or clear all of the toggles in `other`.
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.insert(other);
+        } else {
+            self.remove(other);
+        }
+    }
+}
+
+impl BitAnd for Toggles {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Toggles {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Toggles {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Not for Toggles {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl Debug for Toggles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Toggles({self})")
+    }
+}
+
+impl Display for Toggles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut remaining = self.0;
+        let mut first = true;
+
+        let mut separator = |f: &mut Formatter<'_>| {
+            if first {
+                first = false;
+                Ok(())
+            } else {
+                f.write_str(" | ")
+            }
+        };
+
+        for (name, flag) in Self::NAMED {
+            if self.contains(flag) {
+                separator(f)?;
+                f.write_str(name)?;
+                remaining &= !flag.0;
+            }
+        }
+
+        if remaining != 0 {
+            separator(f)?;
+            write!(f, "{remaining:#04x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Toggles {
+    type Err = ParseTogglesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut toggles = Self::empty();
+
+        if s.trim().is_empty() {
+            return Ok(toggles);
+        }
+
+        for part in s.split('|').map(str::trim) {
+            if let Some(hex) = part.strip_prefix("0x") {
+                let bits =
+                    u8::from_str_radix(hex, 16).map_err(|_| ParseTogglesError(part.to_owned()))?;
+                toggles.insert(Self(bits));
+            } else {
+                let (_, flag) = Self::NAMED
+                    .iter()
+                    .find(|(name, _)| *name == part)
+                    .ok_or_else(|| ParseTogglesError(part.to_owned()))?;
+                toggles.insert(*flag);
+            }
+        }
+
+        Ok(toggles)
+    }
+}
+
+/// The error returned when a string can not be parsed as [`Toggles`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseTogglesError(String);
+
+impl Display for ParseTogglesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unrecognized toggle {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTogglesError {}